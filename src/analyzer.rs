@@ -1,68 +1,403 @@
 // src/analyzer.rs - Main analysis logic
 // This module contains the core logic to compare CSS definitions with usage
 
-use std::collections::HashSet;
-use std::path::Path;
-use crate::{AnalysisResult, CssClass, CustomProperty, AnalysisConfig};
-use crate::css_parser::{find_css_classes, find_custom_properties, find_custom_property_usage};
-use crate::js_parser::find_js_css_references_with_context;
-use crate::complexity_analyzer::find_complexity_warnings; 
-
-/// Main function that analyzes a directory and returns results
-pub fn analyze_directory(path: &Path, config: &AnalysisConfig) -> anyhow::Result<AnalysisResult> {
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use crate::{AnalysisResult, ClassExplanation, CssClass, CssRule, CustomProperty, AnalysisConfig, DirectoryStats, RuleLocation, SafelistEntry, StylesheetWarning, StylesheetWarningType, UndefinedReference, UsageEvidence, UsageKind, WarningSeverity};
+use crate::css_parser::{find_css_classes_in, find_css_rules_in, find_custom_properties_in, find_custom_property_usage_in, parse_stylesheet, selector_specificity, split_selector_list, subject_and_ancestors};
+use crate::js_parser::{find_class_usages, find_class_usages_in_directory, find_js_css_usage_evidence_in, find_style_imports, normalize_path, resolve_style_import_in, should_process_js_file};
+use crate::complexity_analyzer::{find_complexity_warnings_in, is_reported, severity_level, with_every_rule}; 
+use crate::duplicate_analyzer::group_duplicate_rules;
+use crate::suppressions::{apply_suppressions, find_suppressions_in};
+use crate::safelist::Safelist;
+use crate::import_graph::apply_reachability;
+use crate::stylesheet_files::unused_stylesheet_files;
+use crate::error::{self, Error};
+use crate::files::{find_file_diagnostics_in, source_files, FileProvider, FsFiles, SourceFile};
+use crate::progress::{Phase, PhaseTimer, ProgressReporter, SilentReporter, Verbosity};
+
+/// Main function that analyzes a directory and returns results, without any progress output
+pub fn analyze_directory(path: &Path, config: &AnalysisConfig) -> error::Result<AnalysisResult> {
+    analyze_directory_with_progress(path, config, &SilentReporter)
+}
+
+/// Run the analysis, reporting each phase to `progress`
+pub fn analyze_directory_with_progress(path: &Path, config: &AnalysisConfig, progress: &dyn ProgressReporter) -> error::Result<AnalysisResult> {
+    analyze_sources_with_progress(&FsFiles::new(path), config, progress)
+}
+
+/// Analyze a set of files that doesn't have to be on disk, e.g. `MemoryFiles` from a build tool
+pub fn analyze_sources(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<AnalysisResult> {
+    analyze_sources_with_progress(files, config, &SilentReporter)
+}
+
+/// `analyze_sources`, reporting each phase to `progress`
+pub fn analyze_sources_with_progress(files: &dyn FileProvider, config: &AnalysisConfig, progress: &dyn ProgressReporter) -> error::Result<AnalysisResult> {
+    let mut timer = PhaseTimer::new(progress);
+    let analysis = run_analysis(files, config, &mut timer)?;
+    timer.finish();
+    Ok(analysis)
+}
+
+/// The phases of an analysis; the caller finishes `timer` so diff mode can time its own phase too
+pub(crate) fn run_analysis(files: &dyn FileProvider, config: &AnalysisConfig, timer: &mut PhaseTimer) -> error::Result<AnalysisResult> {
+    // Strict mode refuses to analyze a tree with unreadable or unparsable files, listing all of them at once
+    if config.strict {
+        timer.start(Phase::FileDiagnostics);
+        let diagnostics = find_file_diagnostics_in(files, config);
+        if !diagnostics.is_empty() {
+            return Err(Error::Strict { diagnostics });
+        }
+    }
+    
     // Step 1: Find all CSS classes defined in stylesheets
-    println!("🔍 Scanning CSS files for class definitions...");
-    let css_classes = find_css_classes(path, config)?;
+    timer.start(Phase::CssClasses);
+    let css_classes = find_css_classes_in(files, config)?;
+    timer.log(Verbosity::Verbose, format!("{} class definitions", css_classes.len()));
     
     // Step 2: Extract CSS class names for context-aware JS parsing
     let css_class_names: Vec<String> = css_classes.iter().map(|c| c.name.clone()).collect();
     
     // Step 3: Find all CSS class references in JavaScript/React files (with CSS context)
-    println!("🔍 Scanning JS/React files for class usage...");
-    let js_references = find_js_css_references_with_context(path, config, &css_class_names)?;
+    timer.start(Phase::ClassUsage);
+    let js_references = find_js_css_usage_evidence_in(files, config, &css_class_names)?;
+    timer.log(Verbosity::Verbose, format!("{} class references", js_references.len()));
+    
+    // Step 3b: Find references to classes that no stylesheet defines
+    timer.start(Phase::UndefinedReferences);
+    let undefined_references = find_undefined_references_in(files, config, &css_classes)?;
     
     // Step 4: Find CSS custom properties
-    println!("🔍 Scanning for CSS custom properties...");
-    let custom_properties = find_custom_properties(path, config)?;
+    timer.start(Phase::CustomProperties);
+    let custom_properties = find_custom_properties_in(files, config)?;
     
     // Step 5: Find custom property usage
-    println!("🔍 Analyzing custom property usage...");
-    let used_property_names = find_custom_property_usage(path, config)?;
+    timer.start(Phase::CustomPropertyUsage);
+    let used_property_names = find_custom_property_usage_in(files, config)?;
     
-    // Step 6: Find complexity warnings
-    println!("🔍 Analyzing code complexity patterns...");
-    let complexity_warnings = find_complexity_warnings(path, config)?;
+    // Step 6: Find complexity warnings; suppressions are matched against all of them, reported or not
+    timer.start(Phase::Complexity);
+    let all_complexity_warnings = find_complexity_warnings_in(files, &with_every_rule(config))?;
+    let complexity_warnings: Vec<_> = all_complexity_warnings.iter().filter(|w| is_reported(w, config)).cloned().collect();
+    timer.log(Verbosity::Verbose, format!("{} complexity warnings", complexity_warnings.len()));
+    
+    // Step 6b: Parse rule blocks for the stylesheet-side checks
+    timer.start(Phase::StylesheetRules);
+    let css_rules = find_css_rules_in(files, config)?;
+    timer.log(Verbosity::Verbose, format!("{} rule blocks", css_rules.len()));
+    let duplicate_rules = group_duplicate_rules(&css_rules, config);
+    let mut stylesheet_warnings = find_excessive_important_usage(&css_rules, config);
+    stylesheet_warnings.extend(find_always_overridden_classes(&css_rules, config));
+    stylesheet_warnings.extend(find_specificity_issues(&css_rules, config));
+    stylesheet_warnings.sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
     
     // Step 7: Analyze usage patterns
-    println!("📊 Analyzing usage patterns...");
-    let analysis = analyze_css_usage(
+    timer.start(Phase::UsagePatterns);
+    let mut analysis = analyze_css_usage(
         css_classes, 
         js_references, 
         custom_properties, 
         used_property_names, 
-        complexity_warnings 
+        complexity_warnings,
+        &config.safelist,
+        config.config_file.as_deref(),
     )?;
+    analysis.undefined_references = undefined_references;
+    analysis.duplicate_rules = duplicate_rules;
+    analysis.stylesheet_warnings = stylesheet_warnings;
+    
+    // Step 7b: Follow the module graph from the entry points; usage from dead components doesn't count
+    if !config.entry_points.is_empty() {
+        timer.start(Phase::Reachability);
+        apply_reachability(&mut analysis, files, config)?;
+    }
+    
+    // Step 7c: Whole stylesheets that can go; classes kept by a `rustbrother-ignore` comment keep their file too
+    timer.start(Phase::StylesheetFiles);
+    let suppressions = find_suppressions_in(files, config)?;
+    analysis.unused_stylesheet_files = unused_stylesheet_files(files, config, &analysis, &suppressions)?;
     
-    println!("✅ Analysis complete!");
+    // Step 8: Drop findings silenced by `rustbrother-ignore` comments
+    timer.start(Phase::Suppressions);
+    apply_suppressions(&mut analysis, suppressions, &all_complexity_warnings);
+    
+    // Step 9: Files that were skipped or misread along the way (strict mode already checked them up front)
+    if !config.strict {
+        timer.start(Phase::FileDiagnostics);
+        analysis.diagnostics = find_file_diagnostics_in(files, config);
+        timer.log(Verbosity::Verbose, format!("{} files could not be fully analyzed", analysis.diagnostics.len()));
+    }
+    timer.end_phase();
     Ok(analysis)
 }
 
+/// Explain a single class: its definitions, every located reference and why it's used or unused
+pub fn explain_class(path: &Path, config: &AnalysisConfig, class_name: &str) -> error::Result<ClassExplanation> {
+    let result = analyze_directory(path, config)?;
+    let class_name = class_name.trim_start_matches('.');
+    
+    let definitions: Vec<CssClass> = result.used_classes
+        .iter()
+        .chain(&result.dead_code_classes)
+        .chain(&result.unused_classes)
+        .filter(|class| class.name == class_name)
+        .cloned()
+        .collect();
+    
+    let references: Vec<_> = find_class_usages_in_directory(path, config)?
+        .into_iter()
+        .filter(|usage| usage.name == class_name)
+        .collect();
+    
+    let is_used = result.used_classes.iter().any(|class| class.name == class_name);
+    let used_only_by_dead_code = !is_used && result.dead_code_classes.iter().any(|class| class.name == class_name);
+    
+    let reason = if definitions.is_empty() {
+        if references.is_empty() {
+            "No stylesheet defines this class and nothing references it".to_string()
+        } else {
+            let suggestion = result.undefined_references
+                .iter()
+                .find_map(|reference| reference.suggestion.as_ref().filter(|_| reference.name == class_name));
+            let hint = suggestion.map(|s| format!(" (did you mean .{}?)", s)).unwrap_or_default();
+            format!("No stylesheet defines this class, but it is referenced {} time(s) - these resolve to undefined at runtime{}", references.len(), hint)
+        }
+    } else if used_only_by_dead_code {
+        let dead_files: BTreeSet<&str> = definitions.iter().flat_map(|class| &class.evidence).map(|e| e.file_path.as_str()).collect();
+        format!("Used only by dead code - referenced only from {}, which no entry point imports", dead_files.into_iter().collect::<Vec<_>>().join(", "))
+    } else if definitions.iter().any(is_referenced_by_name) {
+        let definite = definitions[0].evidence.iter().filter(|e| e.kind.is_definite() && e.kind != UsageKind::Safelisted).count();
+        format!("Definitely used - referenced by name in {} place(s)", definite)
+    } else if let Some(evidence) = definitions.iter().flat_map(|class| &class.evidence).find(|e| e.kind == UsageKind::Safelisted) {
+        format!("Safelisted - kept as used by the {}", evidence.pattern)
+    } else if is_used {
+        "Possibly used (dynamic) - only matched by guessing from a dynamic class pattern, see the evidence below".to_string()
+    } else {
+        "No JS/TS file references it, directly or through a dynamic class pattern".to_string()
+    };
+    
+    Ok(ClassExplanation {
+        class_name: class_name.to_string(),
+        definitions,
+        references,
+        is_used,
+        used_only_by_dead_code,
+        reason,
+    })
+}
+
+/// Find `styles.x` accesses, destructured names and `className="x"` strings that no stylesheet defines
+/// Module references are checked against the stylesheet their import resolves to; plain class strings
+/// against every global (non-module) stylesheet. Imports that can't be resolved on disk are skipped, and so are
+/// imported stylesheets that can't be read or parsed (an error with `strict` on) and class strings when the
+/// project has no global stylesheets to check them against.
+pub fn find_undefined_references(path: &Path, config: &AnalysisConfig, css_classes: &[CssClass]) -> error::Result<Vec<UndefinedReference>> {
+    find_undefined_references_in(&FsFiles::new(path), config, css_classes)
+}
+
+/// Same as `find_undefined_references`, reading from `files`
+pub fn find_undefined_references_in(files: &dyn FileProvider, config: &AnalysisConfig, css_classes: &[CssClass]) -> error::Result<Vec<UndefinedReference>> {
+    let global_classes: HashSet<&str> = css_classes
+        .iter()
+        .filter(|class| !class.file_path.contains(".module."))
+        .map(|class| class.name.as_str())
+        .collect();
+    
+    // `None` for a stylesheet that couldn't be read or parsed
+    let mut module_classes: HashMap<PathBuf, Option<HashSet<String>>> = HashMap::new();
+    let mut undefined = Vec::new();
+    
+    for file in source_files(files, config, should_process_js_file) {
+        let SourceFile { path: script, file_path, content } = file?;
+        
+        // binding -> the stylesheet it was imported from
+        let mut stylesheets: HashMap<String, PathBuf> = HashMap::new();
+        if config.include_css_modules {
+            for import in find_style_imports(&content) {
+                if let Some(stylesheet) = resolve_style_import_in(files, &script, &import.source) {
+                    stylesheets.insert(import.binding, stylesheet);
+                }
+            }
+        }
+        
+        for usage in find_class_usages(&content, &file_path) {
+            let (candidates, stylesheet): (Vec<&str>, Option<String>) = match &usage.binding {
+                Some(binding) => {
+                    let Some(stylesheet) = stylesheets.get(binding) else { continue };
+                    let key = normalize_path(stylesheet);
+                    if !module_classes.contains_key(&key) {
+                        let classes = match stylesheet_class_names(files, stylesheet) {
+                            Ok(classes) => Some(classes),
+                            Err(error) if config.strict => return Err(error),
+                            Err(_) => None,
+                        };
+                        module_classes.insert(key.clone(), classes);
+                    }
+                    // Its file diagnostic explains an unreadable stylesheet, rather than one finding per class
+                    let Some(classes) = &module_classes[&key] else { continue };
+                    if classes.contains(&usage.name) {
+                        continue;
+                    }
+                    (classes.iter().map(|name| name.as_str()).collect(), Some(stylesheet.to_string_lossy().to_string()))
+                }
+                None => {
+                    if global_classes.is_empty() || global_classes.contains(usage.name.as_str()) {
+                        continue;
+                    }
+                    (global_classes.iter().copied().collect(), None)
+                }
+            };
+            
+            undefined.push(UndefinedReference {
+                suggestion: suggest_class_name(&usage.name, &candidates),
+                name: usage.name,
+                binding: usage.binding,
+                file_path: usage.file_path,
+                line_number: usage.line_number,
+                column: usage.column,
+                stylesheet,
+            });
+        }
+    }
+    
+    undefined.sort_by(|a, b| (&a.file_path, a.line_number, a.column).cmp(&(&b.file_path, b.line_number, b.column)));
+    Ok(undefined)
+}
+
+/// Every class defined in one stylesheet; `FsFiles` reads any path, so imports outside the scanned tree work too
+fn stylesheet_class_names(files: &dyn FileProvider, stylesheet: &Path) -> error::Result<HashSet<String>> {
+    let content = files.read(stylesheet)?;
+    Ok(parse_stylesheet(&content, &stylesheet.to_string_lossy())?
+        .into_iter()
+        .map(|class| class.name)
+        .collect())
+}
+
+/// Pick the closest existing class name for a "did you mean" hint
+/// Only names within a third of the length (at least 2 edits) are close enough to suggest
+fn suggest_class_name(name: &str, candidates: &[&str]) -> Option<String> {
+    let max_distance = (name.chars().count() / 3).max(2);
+    
+    candidates
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// Levenshtein distance between two class names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    
+    previous[b.len()]
+}
+
+/// Group analysis results by the directory of each stylesheet or component
+pub fn directory_stats(result: &AnalysisResult) -> Vec<DirectoryStats> {
+    let mut stats: BTreeMap<String, DirectoryStats> = BTreeMap::new();
+    let mut css_files: BTreeMap<String, HashSet<&str>> = BTreeMap::new();
+    
+    fn entry<'a>(stats: &'a mut BTreeMap<String, DirectoryStats>, file_path: &str) -> &'a mut DirectoryStats {
+        let directory = directory_of(file_path);
+        stats.entry(directory.clone()).or_insert_with(|| DirectoryStats {
+            directory,
+            css_files: 0,
+            total_classes: 0,
+            used_classes: 0,
+            unused_classes: 0,
+            dead_code_classes: 0,
+            unused_custom_properties: 0,
+            complexity_warnings: 0,
+        })
+    }
+    
+    for class in &result.used_classes {
+        let dir_stats = entry(&mut stats, &class.file_path);
+        dir_stats.total_classes += 1;
+        dir_stats.used_classes += 1;
+        css_files.entry(directory_of(&class.file_path)).or_default().insert(&class.file_path);
+    }
+    for class in &result.unused_classes {
+        let dir_stats = entry(&mut stats, &class.file_path);
+        dir_stats.total_classes += 1;
+        dir_stats.unused_classes += 1;
+        css_files.entry(directory_of(&class.file_path)).or_default().insert(&class.file_path);
+    }
+    for class in &result.dead_code_classes {
+        let dir_stats = entry(&mut stats, &class.file_path);
+        dir_stats.total_classes += 1;
+        dir_stats.dead_code_classes += 1;
+        css_files.entry(directory_of(&class.file_path)).or_default().insert(&class.file_path);
+    }
+    for property in &result.unused_custom_properties {
+        entry(&mut stats, &property.file_path).unused_custom_properties += 1;
+    }
+    for warning in &result.complexity_warnings {
+        entry(&mut stats, &warning.file_path).complexity_warnings += 1;
+    }
+    
+    for (directory, files) in css_files {
+        if let Some(dir_stats) = stats.get_mut(&directory) {
+            dir_stats.css_files = files.len();
+        }
+    }
+    
+    stats.into_values().collect()
+}
+
+/// True when JS references the class literally, not only through the safelist
+fn is_referenced_by_name(class: &CssClass) -> bool {
+    class.evidence.iter().any(|e| e.kind.is_definite() && e.kind != UsageKind::Safelisted)
+}
+
+pub(crate) fn directory_of(file_path: &str) -> String {
+    Path::new(file_path)
+        .parent()
+        .map(|dir| dir.to_string_lossy().to_string())
+        .filter(|dir| !dir.is_empty())
+        .unwrap_or_else(|| ".".to_string())
+}
+
 ///  Analysis function to include complexity warnings
 fn analyze_css_usage(
     css_classes: Vec<CssClass>,
-    js_references: Vec<String>,
+    js_references: HashMap<String, Vec<UsageEvidence>>,
     custom_properties: Vec<CustomProperty>,
     used_property_names: HashSet<String>,
     complexity_warnings: Vec<crate::ComplexityWarning>, 
-) -> anyhow::Result<AnalysisResult> {
+    safelist: &[SafelistEntry],
+    config_file: Option<&Path>,
+) -> error::Result<AnalysisResult> {
     
-    let js_class_set: HashSet<String> = js_references.into_iter().collect();
+    // Attach the evidence that makes each class count as used: JS references, then safelist matches
+    let mut safelist_matched = vec![false; safelist.len()];
+    let safelist = Safelist::compile(safelist, config_file)?;
+    let css_classes: Vec<CssClass> = css_classes
+        .into_iter()
+        .map(|mut class| {
+            class.evidence = js_references.get(&class.name).cloned().unwrap_or_default();
+            for index in safelist.matching_entries(&class) {
+                safelist_matched[index] = true;
+                class.evidence.push(safelist.evidence(index, &class));
+            }
+            class
+        })
+        .collect();
     
     let (used_classes, unused_classes): (Vec<CssClass>, Vec<CssClass>) = css_classes
         .into_iter()
-        .partition(|css_class| {
-            js_class_set.contains(&css_class.name)
-        });
+        .partition(|css_class| !css_class.evidence.is_empty());
     
     let (used_custom_properties, unused_custom_properties): (Vec<CustomProperty>, Vec<CustomProperty>) = 
         custom_properties
@@ -72,15 +407,25 @@ fn analyze_css_usage(
             });
     
     let total_css_files = count_unique_css_files(&used_classes, &unused_classes);
-    let total_js_files = count_unique_js_files(&js_class_set);
+    let total_js_files = count_unique_js_files(&js_references);
     let total_files_scanned = total_css_files + total_js_files;
     
     Ok(AnalysisResult {
         used_classes,
         unused_classes,
+        dead_code_classes: Vec::new(),
         used_custom_properties,
         unused_custom_properties,
         complexity_warnings, 
+        undefined_references: Vec::new(),
+        duplicate_rules: Vec::new(),
+        stylesheet_warnings: Vec::new(),
+        unused_suppressions: Vec::new(),
+        unused_safelist_entries: safelist.unmatched(&safelist_matched),
+        dead_components: Vec::new(),
+        unreachable_stylesheets: Vec::new(),
+        unused_stylesheet_files: Vec::new(),
+        diagnostics: Vec::new(),
         total_files_scanned,
         total_css_files,
         total_js_files,
@@ -105,30 +450,303 @@ fn count_unique_css_files(used_classes: &[CssClass], unused_classes: &[CssClass]
     unique_files.len()
 }
 
-/// Count the number of unique JS files (estimated from class references)
-fn count_unique_js_files(_js_class_set: &HashSet<String>) -> usize {
-    // For now, we'll return 0 since we don't track which files the references came from
-    // This could be improved to track source files in the parser
-    0
+/// Count the number of unique JS files that reference at least one CSS class
+fn count_unique_js_files(js_references: &HashMap<String, Vec<UsageEvidence>>) -> usize {
+    js_references
+        .values()
+        .flatten()
+        .map(|evidence| evidence.file_path.as_str())
+        .collect::<HashSet<_>>()
+        .len()
 }
 
-/// Advanced analysis functions (for future phases)
-/// These are placeholder functions that we can implement later
+// Stylesheet cascade analysis
+
+/// Shorthands and the longhand prefix they reset (`margin` overrides `margin-top`)
+const SHORTHAND_PROPERTIES: &[&str] = &[
+    "margin", "padding", "border", "background", "font", "flex", "grid", "outline",
+    "transition", "animation", "list-style", "overflow", "text-decoration", "columns", "inset",
+];
 
-/// Detect CSS classes that are always overridden
-/// This would look for patterns where a class is defined but always overridden by more specific selectors
-#[allow(dead_code)]
-fn find_always_overridden_classes(_css_classes: &[CssClass]) -> Vec<CssClass> {
-    // TODO: Implement in Phase 2
-    // This would analyze CSS specificity and find classes that are never actually applied
-    Vec::new()
+/// Detect declarations and whole rules that are always overridden
+/// A declaration is shadowed when another rule in the same stylesheet sets the same property
+/// (or a shorthand of it), matches every element the first rule matches, applies under the same
+/// (or fewer) at-rule conditions and wins the cascade through specificity, source order or `!important`.
+/// When every declaration of a class rule is shadowed, the rule is reported once as always overridden.
+pub fn find_always_overridden_classes(rules: &[CssRule], config: &AnalysisConfig) -> Vec<StylesheetWarning> {
+    let mut warnings = Vec::new();
+    
+    // Split every rule into its selectors once: (subject, ancestors, specificity)
+    let selectors: Vec<Vec<(Vec<String>, String, crate::Specificity)>> = rules
+        .iter()
+        .map(|rule| {
+            split_selector_list(&rule.selector)
+                .iter()
+                .map(|complex| {
+                    let (subject, ancestors) = subject_and_ancestors(complex);
+                    (subject, ancestors, selector_specificity(complex))
+                })
+                .collect()
+        })
+        .collect();
+    
+    let mut rules_by_file: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, rule) in rules.iter().enumerate() {
+        rules_by_file.entry(&rule.file_path).or_default().push(index);
+    }
+    
+    for (a, rule) in rules.iter().enumerate() {
+        // Only rules whose every selector targets a class
+        if selectors[a].is_empty() || !selectors[a].iter().all(|(subject, _, _)| subject.iter().any(|s| s.starts_with('.'))) {
+            continue;
+        }
+        
+        let mut shadowed = Vec::new();
+        for declaration in &rule.declarations {
+            let winner = rules_by_file[rule.file_path.as_str()].iter().copied().filter(|&b| b != a).find_map(|b| {
+                let other = &rules[b];
+                if !other.conditions.iter().all(|c| rule.conditions.contains(c)) {
+                    return None;
+                }
+                let overriding = other.declarations.iter().find(|d| overrides_property(&d.property, &declaration.property))?;
+                
+                // Every selector of the shadowed rule must lose to some selector of the other rule
+                let always_loses = selectors[a].iter().all(|(subject, ancestors, specificity)| {
+                    selectors[b].iter().any(|(other_subject, other_ancestors, other_specificity)| {
+                        let covers = (other_ancestors.is_empty() || other_ancestors == ancestors)
+                            && other_subject.iter().all(|simple| subject.contains(simple));
+                        let wins = match (declaration.important, overriding.important) {
+                            (true, false) => false,
+                            (false, true) => true,
+                            _ => (other_specificity, b) > (specificity, a),
+                        };
+                        covers && wins
+                    })
+                });
+                always_loses.then_some((other, overriding))
+            });
+            
+            if let Some((other, overriding)) = winner {
+                shadowed.push((declaration, other, overriding));
+            }
+        }
+        
+        if shadowed.is_empty() {
+            continue;
+        }
+        
+        if shadowed.len() == rule.declarations.len() {
+            let (_, other, _) = shadowed[0];
+            warnings.push(StylesheetWarning {
+                file_path: rule.file_path.clone(),
+                line_number: rule.start_line,
+                selector: Some(rule.selector.clone()),
+                warning_type: StylesheetWarningType::AlwaysOverriddenRule,
+                pattern: format!(
+                    "Every declaration in `{}` {} (line {}) is overridden by `{}` {} (line {})",
+                    rule.selector, rule.specificity, rule.start_line, other.selector, other.specificity, other.start_line
+                ),
+                suggestion: "This rule never applies - delete it or merge it into the rule that wins".to_string(),
+                severity: WarningSeverity::High,
+                competing_rule: Some(rule_location(other)),
+            });
+            continue;
+        }
+        
+        for (declaration, other, overriding) in shadowed {
+            warnings.push(StylesheetWarning {
+                file_path: rule.file_path.clone(),
+                line_number: declaration.line_number,
+                selector: Some(rule.selector.clone()),
+                warning_type: StylesheetWarningType::ShadowedDeclaration,
+                pattern: format!(
+                    "`{}: {}` in `{}` {} loses to `{}: {}` in `{}` {} (line {})",
+                    declaration.property, declaration.value, rule.selector, rule.specificity,
+                    overriding.property, overriding.value, other.selector, other.specificity, overriding.line_number
+                ),
+                suggestion: "Remove the dead declaration, or move the value into the rule that wins".to_string(),
+                severity: WarningSeverity::Medium,
+                competing_rule: Some(rule_location(other)),
+            });
+        }
+    }
+    
+    warnings
+        .into_iter()
+        .filter(|w| severity_level(&w.severity) >= severity_level(&config.complexity_threshold))
+        .collect()
+}
+
+/// Flag selectors over the specificity budget and ID selectors in component (CSS module) styles
+pub fn find_specificity_issues(rules: &[CssRule], config: &AnalysisConfig) -> Vec<StylesheetWarning> {
+    let mut warnings = Vec::new();
+    
+    for rule in rules {
+        // Conditional copies of a rule (`@media` inside it) share its selector - report it once
+        if !rule.conditions.is_empty() && rules.iter().any(|r| r.selector == rule.selector && r.file_path == rule.file_path && r.start_line < rule.start_line) {
+            continue;
+        }
+        
+        let id_selector = split_selector_list(&rule.selector)
+            .into_iter()
+            .find(|complex| selector_specificity(complex).ids > 0);
+        
+        if let Some(complex) = id_selector.filter(|_| rule.file_path.contains(".module.")) {
+            warnings.push(StylesheetWarning {
+                file_path: rule.file_path.clone(),
+                line_number: rule.start_line,
+                selector: Some(rule.selector.clone()),
+                warning_type: StylesheetWarningType::IdSelector,
+                pattern: format!("`{}` {}", complex, selector_specificity(&complex)),
+                suggestion: "Component styles should target classes - an ID out-specifies every class rule and can't be reused".to_string(),
+                severity: WarningSeverity::Medium,
+                competing_rule: None,
+            });
+        } else if rule.specificity > config.specificity_budget {
+            warnings.push(StylesheetWarning {
+                file_path: rule.file_path.clone(),
+                line_number: rule.start_line,
+                selector: Some(rule.selector.clone()),
+                warning_type: StylesheetWarningType::SpecificityOverBudget,
+                pattern: format!("`{}` {} is over the {} budget", rule.selector, rule.specificity, config.specificity_budget),
+                suggestion: "Flatten the selector to a single class so later rules don't need to escalate to win".to_string(),
+                severity: if rule.specificity.ids > 0 { WarningSeverity::High } else { WarningSeverity::Medium },
+                competing_rule: None,
+            });
+        }
+    }
+    
+    warnings
+        .into_iter()
+        .filter(|w| severity_level(&w.severity) >= severity_level(&config.complexity_threshold))
+        .collect()
+}
+
+/// Does setting `winner` override `property`? Same property, or a shorthand that resets it
+fn overrides_property(winner: &str, property: &str) -> bool {
+    let (winner, property) = (winner.to_ascii_lowercase(), property.to_ascii_lowercase());
+    winner == property
+        || (SHORTHAND_PROPERTIES.contains(&winner.as_str()) && property.starts_with(&format!("{}-", winner)))
+        || (winner == "inset" && matches!(property.as_str(), "top" | "right" | "bottom" | "left"))
+}
+
+fn rule_location(rule: &CssRule) -> RuleLocation {
+    RuleLocation {
+        selector: rule.selector.clone(),
+        file_path: rule.file_path.clone(),
+        start_line: rule.start_line,
+        end_line: rule.end_line,
+    }
 }
 
 /// Detect excessive use of !important
-/// This would flag CSS rules that use !important too frequently
-#[allow(dead_code)]
-fn find_excessive_important_usage(_path: &Path) -> anyhow::Result<Vec<String>> {
-    // TODO: Implement in Phase 2
-    // This would scan CSS files and count !important usage per file
-    Ok(Vec::new())
-}
\ No newline at end of file
+/// Counts `!important` per rule, per selector and per file: rules or selectors at the configured limit,
+/// files over the configured density and any `!important` in a component module that defines no
+/// utility classes (module classes are locally scoped, so there's nothing to win against).
+/// Utility stylesheets are exempt from the density and module checks.
+pub fn find_excessive_important_usage(rules: &[CssRule], config: &AnalysisConfig) -> Vec<StylesheetWarning> {
+    let mut warnings = Vec::new();
+    let limit = config.important_rule_limit.max(1);
+    
+    let mut rules_by_file: BTreeMap<&str, Vec<&CssRule>> = BTreeMap::new();
+    for rule in rules {
+        rules_by_file.entry(rule.file_path.as_str()).or_default().push(rule);
+    }
+    
+    for (file_path, file_rules) in rules_by_file {
+        let is_utility_sheet = defines_utility_classes(file_path, &file_rules);
+        let is_component_module = file_path.contains(".module.") && !is_utility_sheet;
+        let mut selector_counts: BTreeMap<&str, (usize, usize, bool)> = BTreeMap::new(); // (count, first line, already flagged)
+        let mut total_declarations = 0;
+        let mut total_important = 0;
+        
+        for rule in &file_rules {
+            let important: Vec<_> = rule.declarations.iter().filter(|d| d.important).collect();
+            total_declarations += rule.declarations.len();
+            total_important += important.len();
+            if important.is_empty() {
+                continue;
+            }
+            
+            let over_limit = important.len() >= limit;
+            let selector_entry = selector_counts.entry(&rule.selector).or_insert((0, important[0].line_number, false));
+            selector_entry.0 += important.len();
+            selector_entry.2 |= over_limit;
+            
+            if over_limit {
+                warnings.push(StylesheetWarning {
+                    file_path: file_path.to_string(),
+                    line_number: rule.start_line,
+                    selector: Some(rule.selector.clone()),
+                    warning_type: StylesheetWarningType::ImportantOverusedInRule,
+                    pattern: format!("{} of {} declarations in `{}` are !important", important.len(), rule.declarations.len(), rule.selector),
+                    suggestion: "Raise the selector's specificity or fix the rule it's fighting instead of forcing every declaration".to_string(),
+                    severity: if important.len() >= limit * 2 { WarningSeverity::High } else { WarningSeverity::Medium },
+                    competing_rule: None,
+                });
+            } else if is_component_module {
+                warnings.push(StylesheetWarning {
+                    file_path: file_path.to_string(),
+                    line_number: important[0].line_number,
+                    selector: Some(rule.selector.clone()),
+                    warning_type: StylesheetWarningType::ImportantInComponentModule,
+                    pattern: format!("{}: {} !important", important[0].property, important[0].value),
+                    suggestion: "CSS module classes are locally scoped - nothing in this module should need !important to win".to_string(),
+                    severity: WarningSeverity::Medium,
+                    competing_rule: None,
+                });
+            }
+        }
+        
+        // The same selector piling up !important across several rules (base + media queries...)
+        for (selector, (count, line_number, flagged)) in selector_counts {
+            if count >= limit && !flagged {
+                warnings.push(StylesheetWarning {
+                    file_path: file_path.to_string(),
+                    line_number,
+                    selector: Some(selector.to_string()),
+                    warning_type: StylesheetWarningType::ImportantOverusedOnSelector,
+                    pattern: format!("`{}` has {} !important declarations across its rules", selector, count),
+                    suggestion: "Consolidate the rules for this selector and drop the !important flags".to_string(),
+                    severity: if count >= limit * 2 { WarningSeverity::High } else { WarningSeverity::Medium },
+                    competing_rule: None,
+                });
+            }
+        }
+        
+        if !is_utility_sheet && total_declarations > 0 && total_important > 1 {
+            let density = total_important as f64 / total_declarations as f64;
+            if density > config.important_density {
+                warnings.push(StylesheetWarning {
+                    file_path: file_path.to_string(),
+                    line_number: 1,
+                    selector: None,
+                    warning_type: StylesheetWarningType::ImportantDensity,
+                    pattern: format!("{} of {} declarations are !important ({:.0}%)", total_important, total_declarations, density * 100.0),
+                    suggestion: format!("Keep !important under {:.0}% of declarations - reserve it for utilities and third-party overrides", config.important_density * 100.0),
+                    severity: if density > config.important_density * 2.0 { WarningSeverity::High } else { WarningSeverity::Medium },
+                    competing_rule: None,
+                });
+            }
+        }
+    }
+    
+    warnings
+        .into_iter()
+        .filter(|w| severity_level(&w.severity) >= severity_level(&config.complexity_threshold))
+        .collect()
+}
+
+/// Utility modules legitimately use !important: named like `utils.module.css` or defining `u-`/`util-` classes
+fn defines_utility_classes(file_path: &str, rules: &[&CssRule]) -> bool {
+    let file_name = Path::new(file_path).file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    if file_name.contains("util") || file_name.contains("helper") {
+        return true;
+    }
+    
+    rules.iter().any(|rule| {
+        rule.selector
+            .split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_' || c == '.'))
+            .any(|part| part.starts_with(".u-") || part.starts_with(".util"))
+    })
+}
@@ -0,0 +1,159 @@
+// src/changes.rs - Diff mode for pull request checks
+// `--since <ref>` asks the local `git` for the files changed since a ref, widens them to the stylesheets
+// changed components import and the components importing changed stylesheets, and keeps only the
+// findings in those files. Stylesheets a deleted or moved component imported count as changed, since
+// that is how most classes become unused. Usage is still worked out from the whole tree.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::analyzer::run_analysis;
+use crate::error::{self, Error};
+use crate::files::FsFiles;
+use crate::import_graph::{find_module_imports, is_stylesheet, ModuleGraph};
+use crate::js_parser::{normalize_path, resolve_style_import, should_process_js_file};
+use crate::progress::{Phase, PhaseTimer, ProgressReporter, SilentReporter, Verbosity};
+use crate::{AnalysisConfig, AnalysisResult};
+
+/// Analyze the whole tree under `path`, then keep only the findings touching files changed since `git_ref`
+pub fn analyze_changes(path: &Path, config: &AnalysisConfig, git_ref: &str) -> error::Result<AnalysisResult> {
+    analyze_changes_with_progress(path, config, git_ref, &SilentReporter)
+}
+
+/// `analyze_changes`, reporting each phase to `progress`
+pub fn analyze_changes_with_progress(path: &Path, config: &AnalysisConfig, git_ref: &str, progress: &dyn ProgressReporter) -> error::Result<AnalysisResult> {
+    let mut timer = PhaseTimer::new(progress);
+    timer.start(Phase::ChangedFiles);
+    let mut changed = changed_files(path, git_ref)?;
+    changed.extend(stylesheets_of_deleted_files(path, config, git_ref)?);
+    let affected = affected_files(&ModuleGraph::build(path, config)?, &changed);
+    timer.log(Verbosity::Verbose, format!("{} files changed since {}, {} affected", changed.len(), git_ref, affected.len()));
+
+    let mut result = run_analysis(&FsFiles::new(path), config, &mut timer)?;
+    retain_findings(&mut result, &affected);
+    timer.finish();
+    Ok(result)
+}
+
+/// Files changed since `git_ref` in the repository containing `path`: committed, staged and
+/// unstaged changes plus untracked files, as absolute paths. Deleted files are left out;
+/// a moved file counts as deleted at its old path and added at its new one.
+pub fn changed_files(path: &Path, git_ref: &str) -> error::Result<Vec<PathBuf>> {
+    let (root, commit) = resolve_ref(path, git_ref)?;
+    let diff = git(path, &["diff", "--name-only", "--no-renames", "--diff-filter=d", "--end-of-options", &commit, "--"])?;
+    let untracked = git(path, &["ls-files", "--others", "--exclude-standard", "--full-name"])?;
+
+    Ok(diff
+        .lines()
+        .chain(untracked.lines())
+        .filter(|line| !line.is_empty())
+        .map(|line| root.join(line))
+        .collect())
+}
+
+/// Files deleted (or moved away) since `git_ref` in the repository containing `path`, as absolute paths
+pub fn deleted_files(path: &Path, git_ref: &str) -> error::Result<Vec<PathBuf>> {
+    let (root, commit) = resolve_ref(path, git_ref)?;
+    Ok(deleted_paths(path, &commit)?.iter().map(|line| root.join(line)).collect())
+}
+
+/// The stylesheets still on disk that scripts deleted since `git_ref` imported, read from the scripts as they
+/// were at `git_ref`. Their classes may have lost their last user, so they count as changed.
+pub fn stylesheets_of_deleted_files(path: &Path, config: &AnalysisConfig, git_ref: &str) -> error::Result<Vec<PathBuf>> {
+    let (root, commit) = resolve_ref(path, git_ref)?;
+    let mut stylesheets = Vec::new();
+    for line in deleted_paths(path, &commit)? {
+        let script = root.join(&line);
+        if !should_process_js_file(&script, config) {
+            continue;
+        }
+        let content = git(path, &["show", &format!("{}:{}", commit, line)])?;
+        stylesheets.extend(
+            find_module_imports(&content)
+                .iter()
+                .filter(|source| is_stylesheet(Path::new(source)))
+                .filter_map(|source| resolve_style_import(&script, source)),
+        );
+    }
+    Ok(stylesheets)
+}
+
+/// The repository root and the commit `git_ref` names
+fn resolve_ref(path: &Path, git_ref: &str) -> error::Result<(PathBuf, String)> {
+    // The ref often comes from CI metadata; one starting with `-` would be parsed by git as an option
+    if git_ref.starts_with('-') {
+        return Err(Error::Git { args: git_ref.to_string(), message: "a ref can't start with '-'".to_string() });
+    }
+    let root = PathBuf::from(git(path, &["rev-parse", "--show-toplevel"])?.trim());
+    let commit = git(path, &["rev-parse", "--verify", "--end-of-options", &format!("{}^{{commit}}", git_ref)])?;
+    Ok((root, commit.trim().to_string()))
+}
+
+/// Paths, relative to the repository root, deleted since `commit`
+fn deleted_paths(path: &Path, commit: &str) -> error::Result<Vec<String>> {
+    let diff = git(path, &["diff", "--name-only", "--no-renames", "--diff-filter=D", "--end-of-options", commit, "--"])?;
+    Ok(diff.lines().filter(|line| !line.is_empty()).map(str::to_string).collect())
+}
+
+fn git(directory: &Path, args: &[&str]) -> error::Result<String> {
+    let failed = |message: String| Error::Git { args: args.join(" "), message };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .args(args)
+        .output()
+        .map_err(|e| failed(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(failed(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// The graph files among `changed`, plus the stylesheets changed scripts import and the scripts that
+/// import changed stylesheets. `changed` may be absolute; the result uses the graph's own paths.
+pub fn affected_files(graph: &ModuleGraph, changed: &[PathBuf]) -> HashSet<PathBuf> {
+    let changed: HashSet<PathBuf> = changed.iter().map(|file| canonical(file)).collect();
+    let changed_files: Vec<&PathBuf> = graph.files().filter(|file| changed.contains(&canonical(file))).collect();
+
+    let mut affected: HashSet<PathBuf> = HashSet::new();
+    for file in changed_files {
+        affected.insert(file.clone());
+        if is_stylesheet(file) {
+            affected.extend(graph.importers(file).filter(|importer| !is_stylesheet(importer)).cloned());
+        } else {
+            affected.extend(graph.imports(file).iter().filter(|import| is_stylesheet(import)).cloned());
+        }
+    }
+    affected
+}
+
+/// Drop every finding (and every used class) outside `files`
+pub fn retain_findings(result: &mut AnalysisResult, files: &HashSet<PathBuf>) {
+    let mut cache: HashMap<String, bool> = HashMap::new();
+    let mut touches = |file_path: &str| {
+        *cache
+            .entry(file_path.to_string())
+            .or_insert_with(|| files.contains(&normalize_path(Path::new(file_path))))
+    };
+
+    result.used_classes.retain(|class| touches(&class.file_path));
+    result.unused_classes.retain(|class| touches(&class.file_path));
+    result.dead_code_classes.retain(|class| touches(&class.file_path));
+    result.used_custom_properties.retain(|property| touches(&property.file_path));
+    result.unused_custom_properties.retain(|property| touches(&property.file_path));
+    result.complexity_warnings.retain(|warning| touches(&warning.file_path));
+    result.undefined_references.retain(|reference| touches(&reference.file_path));
+    result.duplicate_rules.retain(|group| group.occurrences.iter().any(|location| touches(&location.file_path)));
+    result.stylesheet_warnings.retain(|warning| touches(&warning.file_path));
+    result.unused_suppressions.retain(|suppression| touches(&suppression.file_path));
+    result.dead_components.retain(|component| touches(&component.file_path));
+    result.unreachable_stylesheets.retain(|stylesheet| touches(stylesheet));
+    result.unused_stylesheet_files.retain(|file| touches(&file.file_path));
+    // Safelist entries live in the config, not in any changed file
+    result.unused_safelist_entries.clear();
+}
+
+fn canonical(file: &Path) -> PathBuf {
+    file.canonicalize().unwrap_or_else(|_| normalize_path(file))
+}
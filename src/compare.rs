@@ -0,0 +1,140 @@
+// src/compare.rs - Trend history between two JSON reports
+// `rustbrother compare old.json new.json` reads two saved JSON reports and shows how the unused-CSS debt moved:
+// which unused classes appeared or were cleaned up, and how warning counts changed overall and per directory.
+
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fs;
+use std::path::Path;
+use crate::analyzer::directory_of;
+use crate::json_report::REPORT_SCHEMA_VERSION;
+use crate::{ComplexityWarningType, CountDelta, CssClass, DirectoryDelta, ReportComparison, WarningSeverity};
+
+/// The parts of a JSON report a comparison needs; anything else in the file is ignored
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ReportSnapshot {
+    /// Absent in reports written before the JSON report was versioned
+    pub schema_version: Option<String>,
+    pub unused_classes: Vec<CssClass>,
+    pub complexity_warnings: Vec<WarningSnapshot>,
+}
+
+/// A complexity warning as far as trends care: where it is, what it is and how bad
+#[derive(Debug, Clone, Deserialize)]
+pub struct WarningSnapshot {
+    pub file_path: String,
+    pub warning_type: ComplexityWarningType,
+    pub severity: WarningSeverity,
+}
+
+/// Load a report written by `--format json`
+pub fn load_report(path: &Path) -> anyhow::Result<ReportSnapshot> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read report {}: {}", path.display(), e))?;
+    let report: ReportSnapshot = serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid report {}: {}", path.display(), e))?;
+
+    if let Some(version) = &report.schema_version {
+        if major_version(version) != major_version(REPORT_SCHEMA_VERSION) {
+            anyhow::bail!("Unsupported report {}: schema version {} (expected {}.x)", path.display(), version, major_version(REPORT_SCHEMA_VERSION));
+        }
+    }
+    Ok(report)
+}
+
+fn major_version(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
+
+/// Compare an older report with a newer one
+pub fn compare_reports(old: &ReportSnapshot, new: &ReportSnapshot) -> ReportComparison {
+    let class_keys = |report: &ReportSnapshot| -> HashSet<(String, String)> {
+        report.unused_classes.iter().map(|class| (class.file_path.clone(), class.name.clone())).collect()
+    };
+    let (old_keys, new_keys) = (class_keys(old), class_keys(new));
+    let missing_from = |report: &ReportSnapshot, keys: &HashSet<(String, String)>| -> Vec<CssClass> {
+        report.unused_classes
+            .iter()
+            .filter(|class| !keys.contains(&(class.file_path.clone(), class.name.clone())))
+            .cloned()
+            .collect()
+    };
+
+    let severity_name = |warning: &WarningSnapshot| format!("{:?}", warning.severity);
+    let mut warnings_by_severity = count_deltas(old, new, severity_name);
+    for severity in ["High", "Medium", "Low"] {
+        if !warnings_by_severity.iter().any(|delta| delta.name == severity) {
+            warnings_by_severity.push(delta(severity, 0, 0));
+        }
+    }
+    warnings_by_severity.sort_by_key(|delta| ["High", "Medium", "Low"].iter().position(|s| *s == delta.name));
+
+    ReportComparison {
+        unused_classes: delta("unused_classes", old.unused_classes.len(), new.unused_classes.len()),
+        added_unused_classes: missing_from(new, &old_keys),
+        removed_unused_classes: missing_from(old, &new_keys),
+        warnings_by_severity,
+        warnings_by_type: count_deltas(old, new, |warning| warning_type_name(&warning.warning_type)),
+        directories: directory_deltas(old, new),
+    }
+}
+
+/// `DeepTemplateNesting`, or the name a custom rule gave its warnings
+fn warning_type_name(warning_type: &ComplexityWarningType) -> String {
+    match warning_type {
+        ComplexityWarningType::Custom(name) => name.clone(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Count warnings per key in both reports, keys in name order
+fn count_deltas(old: &ReportSnapshot, new: &ReportSnapshot, key: impl Fn(&WarningSnapshot) -> String) -> Vec<CountDelta> {
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for warning in &old.complexity_warnings {
+        counts.entry(key(warning)).or_default().0 += 1;
+    }
+    for warning in &new.complexity_warnings {
+        counts.entry(key(warning)).or_default().1 += 1;
+    }
+    counts.into_iter().map(|(name, (old, new))| delta(&name, old, new)).collect()
+}
+
+fn directory_deltas(old: &ReportSnapshot, new: &ReportSnapshot) -> Vec<DirectoryDelta> {
+    let count = |report: &ReportSnapshot, directory: &str| {
+        let unused = report.unused_classes.iter().filter(|class| directory_of(&class.file_path) == directory).count();
+        let warnings = report.complexity_warnings.iter().filter(|warning| directory_of(&warning.file_path) == directory).count();
+        (unused, warnings)
+    };
+
+    let directories: BTreeSet<String> = [old, new]
+        .iter()
+        .flat_map(|report| {
+            report.unused_classes
+                .iter()
+                .map(|class| directory_of(&class.file_path))
+                .chain(report.complexity_warnings.iter().map(|warning| directory_of(&warning.file_path)))
+        })
+        .collect();
+
+    directories
+        .into_iter()
+        .map(|directory| {
+            let ((old_unused, old_warnings), (new_unused, new_warnings)) = (count(old, &directory), count(new, &directory));
+            DirectoryDelta {
+                unused_classes: delta("unused_classes", old_unused, new_unused),
+                complexity_warnings: delta("complexity_warnings", old_warnings, new_warnings),
+                directory,
+            }
+        })
+        .collect()
+}
+
+fn delta(name: &str, old: usize, new: usize) -> CountDelta {
+    CountDelta {
+        name: name.to_string(),
+        old,
+        new,
+        change: new as i64 - old as i64,
+    }
+}
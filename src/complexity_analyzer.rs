@@ -1,168 +1,444 @@
 // src/complexity_analyzer.rs - FIXED VERSION
 
 use regex::Regex;
-use walkdir::WalkDir;
-use std::fs;
 use std::path::Path;
-use crate::{ComplexityWarning, ComplexityWarningType, WarningSeverity, AnalysisConfig};
+use crate::css_parser::{find_css_rules_in, split_selector_list, subject_and_ancestors};
+use crate::complexity_rules::RuleRegistry;
+use crate::error;
+use crate::files::{source_files, FileProvider, FsFiles};
+use crate::{ComplexityWarning, ComplexityWarningType, CssRule, RuleConfig, WarningSeverity, AnalysisConfig};
 
-/// Find complexity warnings in JavaScript/React files
-pub fn find_complexity_warnings(path: &Path, config: &AnalysisConfig) -> anyhow::Result<Vec<ComplexityWarning>> {
+/// Find complexity warnings in JavaScript/React files and stylesheets with the built-in rules
+pub fn find_complexity_warnings(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<ComplexityWarning>> {
+    find_complexity_warnings_with_rules(path, config, &RuleRegistry::default())
+}
+
+/// Same as `find_complexity_warnings`, reading from `files`
+pub fn find_complexity_warnings_in(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<Vec<ComplexityWarning>> {
+    find_complexity_warnings_with_rules_in(files, config, &RuleRegistry::default())
+}
+
+/// Find complexity warnings with a custom set of rules, e.g. the built-ins plus your own `ComplexityRule`s
+pub fn find_complexity_warnings_with_rules(path: &Path, config: &AnalysisConfig, registry: &RuleRegistry) -> error::Result<Vec<ComplexityWarning>> {
+    find_complexity_warnings_with_rules_in(&FsFiles::new(path), config, registry)
+}
+
+/// Same as `find_complexity_warnings_with_rules`, reading from `files`
+pub fn find_complexity_warnings_with_rules_in(files: &dyn FileProvider, config: &AnalysisConfig, registry: &RuleRegistry) -> error::Result<Vec<ComplexityWarning>> {
     let mut warnings = Vec::new();
     
     if !config.enable_complexity_warnings {
         return Ok(warnings);
     }
     
-    for entry in WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| should_process_js_file(e.path(), config))
-    {
-        let file_path = entry.path().to_string_lossy().to_string();
-        
-        if let Ok(content) = fs::read_to_string(entry.path()) {
-            let file_warnings = analyze_file_complexity(&content, &file_path, config);
-            warnings.extend(file_warnings);
-        }
+    for source in source_files(files, config, should_process_js_file) {
+        let source = source?;
+        let file = ScriptFile::new(&source.content, &source.file_path);
+        warnings.extend(registry.check_script(&file, config));
     }
     
+    let rules = find_css_rules_in(files, config)?;
+    warnings.extend(registry.check_stylesheets(&rules, config));
+    
     Ok(warnings)
 }
 
-/// Analyze a single file for complexity patterns
-fn analyze_file_complexity(content: &str, file_path: &str, config: &AnalysisConfig) -> Vec<ComplexityWarning> {
-    let mut warnings = Vec::new();
+/// Find maintainability problems in parsed stylesheet rules with the built-in rules: deep nesting,
+/// long descendant chains, class names assembled from `&` suffixes and oversized rule blocks
+pub fn find_stylesheet_complexity_warnings(rules: &[CssRule], config: &AnalysisConfig) -> Vec<ComplexityWarning> {
+    if !config.enable_complexity_warnings {
+        return Vec::new();
+    }
     
-    // Pattern 1: Dynamic class construction with template literals
-    warnings.extend(detect_dynamic_class_construction(content, file_path));
+    RuleRegistry::default().check_stylesheets(rules, config)
+}
+
+/// The rules worth checking: conditional copies (`@media` inside a rule) repeat their parent's selector,
+/// so only the rule itself is
+fn checked_rules(rules: &[CssRule]) -> impl Iterator<Item = (usize, &CssRule)> {
+    rules.iter().enumerate().filter(|&(index, _)| !is_conditional_copy(rules, index))
+}
+
+/// Flag rules where nesting first goes past the limit, with the depth their deepest descendant reaches
+pub(crate) fn detect_deep_selector_nesting(rules: &[CssRule], settings: &RuleConfig) -> Vec<ComplexityWarning> {
+    let max_levels = settings.threshold("max_levels", 4);
+    checked_rules(rules)
+        .filter(|(_, rule)| rule.depth == max_levels)
+        .map(|(index, rule)| deep_selector_nesting(rules, index, rule, max_levels))
+        .collect()
+}
+
+fn deep_selector_nesting(rules: &[CssRule], index: usize, rule: &CssRule, max_levels: usize) -> ComplexityWarning {
+    let deepest = rules[index..]
+        .iter()
+        .take_while(|r| r.file_path == rule.file_path && r.start_line <= rule.end_line)
+        .map(|r| r.depth)
+        .max()
+        .unwrap_or(rule.depth);
+    let levels = deepest + 1;
     
-    // Pattern 2: Complex conditional class assignment
-    warnings.extend(detect_conditional_class_assignment(content, file_path));
+    ComplexityWarning {
+        file_path: rule.file_path.clone(),
+        start_line: rule.start_line,
+        end_line: rule.end_line,
+        warning_type: ComplexityWarningType::DeepSelectorNesting,
+        pattern: format!("`{}` nested {} levels deep", rule.raw_selector, levels),
+        suggestion: format!("Keep nesting to {} levels or fewer - give the inner elements their own classes instead of reaching them through their parents", max_levels),
+        severity: if levels > max_levels + 2 { WarningSeverity::High } else { WarningSeverity::Medium },
+        rule_id: String::new(),
+    }
+}
+
+/// Flag selectors that reach their subject through a long chain of ancestors
+/// Rules without declarations, and rules already past the nesting limit, are left to the other checks
+pub(crate) fn detect_long_descendant_chain(rules: &[CssRule], settings: &RuleConfig) -> Vec<ComplexityWarning> {
+    let max_compounds = settings.threshold("max_compounds", 3);
+    let max_levels = settings.threshold("max_levels", 4);
+    checked_rules(rules)
+        .filter_map(|(_, rule)| long_descendant_chain(rule, max_compounds, max_levels))
+        .collect()
+}
+
+fn long_descendant_chain(rule: &CssRule, max_compounds: usize, max_levels: usize) -> Option<ComplexityWarning> {
+    if rule.declarations.is_empty() || rule.depth >= max_levels {
+        return None;
+    }
+    
+    let (complex, compounds) = split_selector_list(&rule.selector)
+        .into_iter()
+        .map(|complex| {
+            let (_, ancestors) = subject_and_ancestors(&complex);
+            let compounds = ancestors.split_whitespace().filter(|part| !matches!(*part, ">" | "+" | "~")).count() + 1;
+            (complex, compounds)
+        })
+        .max_by_key(|(_, compounds)| *compounds)?;
     
-    // Pattern 3: Deep template nesting
-    warnings.extend(detect_deep_template_nesting(content, file_path));
+    let severity = match compounds {
+        c if c > max_compounds + 3 => WarningSeverity::High,
+        c if c > max_compounds + 1 => WarningSeverity::Medium,
+        c if c > max_compounds => WarningSeverity::Low,
+        _ => return None,
+    };
     
-    // Pattern 4: Untrackable dynamic patterns
-    warnings.extend(detect_untrackable_patterns(content, file_path));
+    Some(ComplexityWarning {
+        file_path: rule.file_path.clone(),
+        start_line: rule.start_line,
+        end_line: rule.start_line,
+        warning_type: ComplexityWarningType::LongDescendantChain,
+        pattern: format!("`{}` chains {} selectors", complex, compounds),
+        suggestion: "Long chains couple styles to the markup structure. Target the element with one class".to_string(),
+        severity,
+        rule_id: String::new(),
+    })
+}
+
+/// Flag `&Suffix` selectors that glue a class name together from pieces, so the full name appears nowhere in the source
+/// Concatenating onto a name that was itself concatenated (`&_graphic { &Icon {} }`) is worse
+pub(crate) fn detect_selector_concatenation(rules: &[CssRule], _settings: &RuleConfig) -> Vec<ComplexityWarning> {
+    checked_rules(rules)
+        .filter(|(_, rule)| concatenates_parent(&rule.raw_selector))
+        .map(|(index, rule)| selector_concatenation(rules, index, rule))
+        .collect()
+}
+
+fn selector_concatenation(rules: &[CssRule], index: usize, rule: &CssRule) -> ComplexityWarning {
+    let stacked = parent_rule(rules, index).is_some_and(|parent| concatenates_parent(&parent.raw_selector));
     
-    // Filter by severity threshold
-    warnings.into_iter()
-        .filter(|w| severity_level(&w.severity) >= severity_level(&config.complexity_threshold))
+    ComplexityWarning {
+        file_path: rule.file_path.clone(),
+        start_line: rule.start_line,
+        end_line: rule.start_line,
+        warning_type: ComplexityWarningType::SelectorConcatenation,
+        pattern: format!("`{}` builds `{}`", rule.raw_selector, rule.selector),
+        suggestion: format!("Write the class out as `{}` so a search for it finds its definition", rule.selector),
+        severity: if stacked { WarningSeverity::Medium } else { WarningSeverity::Low },
+        rule_id: String::new(),
+    }
+}
+
+/// Flag rule blocks with too many declarations of their own
+pub(crate) fn detect_oversized_rule_block(rules: &[CssRule], settings: &RuleConfig) -> Vec<ComplexityWarning> {
+    let max_declarations = settings.threshold("max_declarations", 25);
+    checked_rules(rules)
+        .filter_map(|(_, rule)| oversized_rule_block(rule, max_declarations))
         .collect()
 }
 
-/// Detect dynamic class construction patterns - SIMPLIFIED AND FIXED
-fn detect_dynamic_class_construction(content: &str, file_path: &str) -> Vec<ComplexityWarning> {
+fn oversized_rule_block(rule: &CssRule, max_declarations: usize) -> Option<ComplexityWarning> {
+    let count = rule.declarations.len();
+    if count <= max_declarations {
+        return None;
+    }
+    
+    Some(ComplexityWarning {
+        file_path: rule.file_path.clone(),
+        start_line: rule.start_line,
+        end_line: rule.end_line,
+        warning_type: ComplexityWarningType::OversizedRuleBlock,
+        pattern: format!("`{}` has {} declarations (lines {}-{})", rule.raw_selector, count, rule.start_line, rule.end_line),
+        suggestion: "Split the block into smaller classes by concern (layout, typography, theme)".to_string(),
+        severity: if count > max_declarations * 2 { WarningSeverity::High } else { WarningSeverity::Medium },
+        rule_id: String::new(),
+    })
+}
+
+/// True when the selector appends to the parent name: `&_graphic`, `&Icon`, `&-large`
+fn concatenates_parent(raw_selector: &str) -> bool {
+    raw_selector.match_indices('&').any(|(position, _)| {
+        raw_selector[position + 1..]
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '-')
+    })
+}
+
+/// The rule a nested rule was written inside, if any
+fn parent_rule(rules: &[CssRule], index: usize) -> Option<&CssRule> {
+    let rule = &rules[index];
+    if rule.depth == 0 {
+        return None;
+    }
+    
+    rules[..index].iter().rev().find(|r| {
+        r.file_path == rule.file_path && r.depth + 1 == rule.depth && r.start_line <= rule.start_line && rule.end_line <= r.end_line
+    })
+}
+
+/// A rule repeated under an at-rule written inside it shares the original's selector and depth
+fn is_conditional_copy(rules: &[CssRule], index: usize) -> bool {
+    let rule = &rules[index];
+    !rule.conditions.is_empty()
+        && rules[..index]
+            .iter()
+            .any(|r| r.file_path == rule.file_path && r.selector == rule.selector && r.depth == rule.depth && r.start_line < rule.start_line && rule.end_line <= r.end_line)
+}
+
+/// A JS/TS file prepared for expression-level scanning, handed to every `ComplexityRule`
+/// `code()` is the content with comments and string contents blanked out (template `${}` expressions are kept),
+/// so brackets, `?` and `;` in it are always real syntax. Byte offsets in both match.
+pub struct ScriptFile<'a> {
+    content: &'a str,
+    code: String,
+    file_path: &'a str,
+    line_starts: Vec<usize>,
+    accesses: Vec<DynamicAccess>,
+}
+
+impl<'a> ScriptFile<'a> {
+    pub fn new(content: &'a str, file_path: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        let code = mask_literals(content);
+        let accesses = find_dynamic_accesses(&code);
+        ScriptFile { content, code, file_path, line_starts, accesses }
+    }
+    
+    pub fn file_path(&self) -> &str {
+        self.file_path
+    }
+    
+    pub fn content(&self) -> &str {
+        self.content
+    }
+    
+    /// The content with comments and string contents replaced by spaces
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+    
+    /// 1-based line of a byte offset
+    pub fn line_of(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|&start| start <= offset)
+    }
+    
+    /// The length in bytes of the longest source line a byte range touches
+    pub fn longest_line(&self, start: usize, end: usize) -> usize {
+        (self.line_of(start)..=self.line_of(end.saturating_sub(1).max(start)))
+            .map(|line| {
+                let end = self.line_starts.get(line).copied().unwrap_or(self.content.len());
+                self.content[self.line_starts[line - 1]..end].trim_end_matches(['\n', '\r']).len()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+    
+    /// The source text of a range with whitespace collapsed, as shown in a warning
+    pub fn snippet(&self, start: usize, end: usize) -> String {
+        self.content[start..end].split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+    
+    /// A warning spanning the lines of a byte range; the registry fills in `rule_id`
+    pub fn warning(&self, start: usize, end: usize, warning_type: ComplexityWarningType, pattern: String, suggestion: &str, severity: WarningSeverity) -> ComplexityWarning {
+        ComplexityWarning {
+            file_path: self.file_path.to_string(),
+            start_line: self.line_of(start),
+            end_line: self.line_of(end.saturating_sub(1).max(start)),
+            warning_type,
+            pattern,
+            suggestion: suggestion.to_string(),
+            severity,
+            rule_id: String::new(),
+        }
+    }
+}
+
+/// A `styles[...]` access: byte range of the whole access and of the index expression between the brackets
+struct DynamicAccess {
+    start: usize,
+    end: usize,
+    index: std::ops::Range<usize>,
+}
+
+/// Find every `styles[...]` access, however many lines its index expression spans
+fn find_dynamic_accesses(code: &str) -> Vec<DynamicAccess> {
+    let access_regex = Regex::new(r"\bstyles\s*\[").unwrap();
+    
+    access_regex
+        .find_iter(code)
+        .filter(|m| m.start() == 0 || !code[..m.start()].ends_with('.'))
+        .filter_map(|m| {
+            let close = matching_close(code, m.end() - 1)?;
+            Some(DynamicAccess { start: m.start(), end: close + 1, index: m.end()..close })
+        })
+        .collect()
+}
+
+/// Detect dynamic class construction patterns
+/// `medium_variables` and `high_variables` are the template variable counts that raise the severity
+pub(crate) fn detect_dynamic_class_construction(source: &ScriptFile, settings: &RuleConfig) -> Vec<ComplexityWarning> {
+    let medium_variables = settings.threshold("medium_variables", 2);
+    let high_variables = settings.threshold("high_variables", 3);
     let mut warnings = Vec::new();
     
-    // Single comprehensive regex for all styles[`...${...}...`] patterns
-    let dynamic_styles_regex = Regex::new(r"styles\[\s*`([^`]*)`\s*\]").unwrap();
-    
-    for (line_num, line) in content.lines().enumerate() {
-        for capture in dynamic_styles_regex.captures_iter(line) {
-            if let Some(template_content) = capture.get(1) {
-                let template = template_content.as_str();
-                let full_pattern = capture.get(0).unwrap().as_str();
-                
-                // Skip if it doesn't contain template variables
-                if !template.contains("${") {
-                    continue;
-                }
-                
-                // Count the number of variables
-                let variable_count = template.matches("${").count();
-                
-                let (warning_type, severity, suggestion) = match variable_count {
-                    3.. => (
+    for access in &source.accesses {
+        let template = source.content[access.index.clone()].trim();
+        let Some(template) = template.strip_prefix('`').and_then(|t| t.strip_suffix('`')) else {
+            continue;
+        };
+        
+        // Count the number of variables
+        let variable_count = template.matches("${").count();
+        
+        let (warning_type, severity, suggestion) = match variable_count {
+            0 => continue, // No variables, skip
+            n if n >= high_variables => (
+                ComplexityWarningType::MultiVariablePattern,
+                WarningSeverity::High,
+                "Multiple variables in template make static analysis very difficult. Consider CSS-in-JS with explicit variants or a class builder function"
+            ),
+            n if n >= medium_variables => {
+                // Check if it's the variant_size pattern like ${variant}_${size}
+                if template.matches('_').count() == 1 && template.contains("}_${") {
+                    (
                         ComplexityWarningType::MultiVariablePattern,
-                        WarningSeverity::High,
-                        "Multiple variables in template make static analysis very difficult. Consider CSS-in-JS with explicit variants or a class builder function".to_string()
-                    ),
-                    2 => {
-                        // Check if it's the variant_size pattern like ${variant}_${size}
-                        if template.matches('_').count() == 1 && template.contains("}_${") {
-                            (
-                                ComplexityWarningType::MultiVariablePattern,
-                                WarningSeverity::Medium,
-                                "The ${variable}_${variable} pattern is hard to analyze statically. Consider explicit class mapping: CLASS_MAP[variant][size]".to_string()
-                            )
-                        } else {
-                            (
-                                ComplexityWarningType::DynamicClassConstruction,
-                                WarningSeverity::Medium,
-                                "Multiple variables in template. Consider explicit class mapping for better maintainability".to_string()
-                            )
-                        }
-                    },
-                    1 => (
+                        WarningSeverity::Medium,
+                        "The ${variable}_${variable} pattern is hard to analyze statically. Consider explicit class mapping: CLASS_MAP[variant][size]"
+                    )
+                } else {
+                    (
                         ComplexityWarningType::DynamicClassConstruction,
-                        WarningSeverity::Low,
-                        "Single variable template. Consider using direct class references: styles.specificClassName".to_string()
-                    ),
-                    _ => continue, // No variables, skip
-                };
-                
-                warnings.push(ComplexityWarning {
-                    file_path: file_path.to_string(),
-                    line_number: line_num + 1,
-                    warning_type,
-                    pattern: full_pattern.to_string(),
-                    suggestion,
-                    severity,
-                });
-            }
-        }
+                        WarningSeverity::Medium,
+                        "Multiple variables in template. Consider explicit class mapping for better maintainability"
+                    )
+                }
+            },
+            _ => (
+                ComplexityWarningType::DynamicClassConstruction,
+                WarningSeverity::Low,
+                "Single variable template. Consider using direct class references: styles.specificClassName"
+            ),
+        };
+        
+        warnings.push(source.warning(access.start, access.end, warning_type, source.snippet(access.start, access.end), suggestion, severity));
     }
     
     warnings
 }
 
 /// Detect complex conditional class assignment
-fn detect_conditional_class_assignment(content: &str, file_path: &str) -> Vec<ComplexityWarning> {
+/// A ternary that picks a `styles[...]` class is flagged where it's written. Variables set from a ternary
+/// (or from other such variables) are followed, so `styles[`x_${variant}`]` is flagged when `variant` was picked by one.
+pub(crate) fn detect_conditional_class_assignment(source: &ScriptFile, _settings: &RuleConfig) -> Vec<ComplexityWarning> {
+    let accesses = &source.accesses;
+    const SUGGESTION: &str = "Consider using a function to handle conditional class logic: getClassName(condition, variant)";
     let mut warnings = Vec::new();
+    let mut flagged: Vec<std::ops::Range<usize>> = Vec::new();
+    // Variable name -> (where it was declared, line of the ternary it comes from)
+    let mut conditional_variables: Vec<(&str, usize, usize)> = Vec::new();
+    
+    for expression in class_expressions(source) {
+        let code = &source.code[expression.value.clone()];
+        let ternary = find_ternary(code).map(|offset| source.line_of(expression.value.start + offset));
+        let inherited = conditional_variables
+            .iter()
+            .find(|(name, declared, _)| *declared < expression.start && references_identifier(code, name))
+            .map(|&(_, _, line)| line);
+        
+        if let (Some(name), Some(line)) = (expression.name, ternary.or(inherited)) {
+            conditional_variables.push((name, expression.start, line));
+        }
+        
+        let has_dynamic_access = accesses.iter().any(|access| expression.value.contains(&access.start));
+        if ternary.is_some() && has_dynamic_access {
+            warnings.push(source.warning(
+                expression.start,
+                expression.value.end,
+                ComplexityWarningType::ConditionalClassAssignment,
+                source.snippet(expression.start, expression.value.end),
+                SUGGESTION,
+                WarningSeverity::Medium,
+            ));
+            flagged.push(expression.start..expression.value.end);
+        }
+    }
     
-    // Pattern: Complex ternary operations with styles
-    let complex_ternary_regex = Regex::new(
-        r"const\s+\w+\s*=\s*[^?]+\?\s*styles\["
-    ).unwrap();
-    
-    for (line_num, line) in content.lines().enumerate() {
-        if complex_ternary_regex.is_match(line) && line.contains("styles[") {
-            warnings.push(ComplexityWarning {
-                file_path: file_path.to_string(),
-                line_number: line_num + 1,
-                warning_type: ComplexityWarningType::ConditionalClassAssignment,
-                pattern: line.trim().to_string(),
-                suggestion: "Consider using a function to handle conditional class logic: getClassName(condition, variant)".to_string(),
-                severity: WarningSeverity::Medium,
-            });
+    for access in accesses {
+        if flagged.iter().any(|range| range.contains(&access.start)) {
+            continue;
+        }
+        
+        let index = &source.code[access.index.clone()];
+        let source_variable = conditional_variables
+            .iter()
+            .find(|(name, declared, _)| *declared < access.start && references_identifier(index, name));
+        
+        if let Some((name, _, line)) = source_variable {
+            warnings.push(source.warning(
+                access.start,
+                access.end,
+                ComplexityWarningType::ConditionalClassAssignment,
+                format!("{} (`{}` is picked by the ternary on line {})", source.snippet(access.start, access.end), name, line),
+                SUGGESTION,
+                WarningSeverity::Medium,
+            ));
         }
     }
     
     warnings
 }
 
-/// Detect deeply nested template patterns
-fn detect_deep_template_nesting(content: &str, file_path: &str) -> Vec<ComplexityWarning> {
+/// Detect deeply nested template patterns: template literals nested more than `max_nesting` deep,
+/// or templates with more than `max_expressions` expressions on a line longer than `max_length` characters
+pub(crate) fn detect_deep_template_nesting(source: &ScriptFile, settings: &RuleConfig) -> Vec<ComplexityWarning> {
+    let max_nesting = settings.threshold("max_nesting", 1);
+    let max_expressions = settings.threshold("max_expressions", 2);
+    let max_length = settings.threshold("max_length", 80);
     let mut warnings = Vec::new();
     
-    // Pattern: Template literals inside template literals or very long template expressions
-    for (line_num, line) in content.lines().enumerate() {
-        // Count backticks and template expressions
-        let backtick_count = line.matches('`').count();
-        let template_expr_count = line.matches("${").count();
+    for template in template_literals(&source.code) {
+        let pattern = source.snippet(template.start, template.end);
+        let long = template.interpolations > max_expressions && source.longest_line(template.start, template.end) > max_length;
         
-        // If we have multiple backticks or many template expressions on one line
-        if (backtick_count >= 4) || (template_expr_count >= 3 && line.len() > 80) {
-            warnings.push(ComplexityWarning {
-                file_path: file_path.to_string(),
-                line_number: line_num + 1,
-                warning_type: ComplexityWarningType::DeepTemplateNesting,
-                pattern: line.trim().to_string(),
-                suggestion: "Break complex template expressions into separate variables for clarity".to_string(),
-                severity: WarningSeverity::Medium,
-            });
+        if template.depth > max_nesting || long {
+            warnings.push(source.warning(
+                template.start,
+                template.end,
+                ComplexityWarningType::DeepTemplateNesting,
+                pattern,
+                "Break complex template expressions into separate variables for clarity",
+                WarningSeverity::Medium,
+            ));
         }
     }
     
@@ -170,44 +446,353 @@ fn detect_deep_template_nesting(content: &str, file_path: &str) -> Vec<Complexit
 }
 
 /// Detect patterns that are essentially untrackable by static analysis
-fn detect_untrackable_patterns(content: &str, file_path: &str) -> Vec<ComplexityWarning> {
+pub(crate) fn detect_untrackable_patterns(source: &ScriptFile, _settings: &RuleConfig) -> Vec<ComplexityWarning> {
     let mut warnings = Vec::new();
     
     // Pattern 1: Dynamic property access with computed strings
-    let computed_access_regex = Regex::new(r"styles\[\s*[a-zA-Z_][a-zA-Z0-9_]*\s*\+\s*[a-zA-Z_][a-zA-Z0-9_]*\s*\]").unwrap();
+    let computed_access_regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*\s*\+\s*[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
     
     // Pattern 2: Function calls that return class names
-    let function_call_regex = Regex::new(r"styles\[\s*[a-zA-Z_][a-zA-Z0-9_]*\([^)]*\)\s*\]").unwrap();
-    
-    for (line_num, line) in content.lines().enumerate() {
-        if computed_access_regex.is_match(line) {
-            warnings.push(ComplexityWarning {
-                file_path: file_path.to_string(),
-                line_number: line_num + 1,
-                warning_type: ComplexityWarningType::UntrackedDynamicPattern,
-                pattern: line.trim().to_string(),
-                suggestion: "Dynamic string concatenation makes static analysis impossible. Use template literals or explicit mapping".to_string(),
-                severity: WarningSeverity::High,
-            });
+    let function_call_regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*\([^)]*\)$").unwrap();
+    
+    for access in &source.accesses {
+        let index = source.snippet(access.index.start, access.index.end);
+        
+        let suggestion = if computed_access_regex.is_match(&index) {
+            "Dynamic string concatenation makes static analysis impossible. Use template literals or explicit mapping"
+        } else if function_call_regex.is_match(&index) {
+            "Function calls in class access make static analysis impossible. Consider explicit class mapping"
+        } else {
+            continue;
+        };
+        
+        warnings.push(source.warning(
+            access.start,
+            access.end,
+            ComplexityWarningType::UntrackedDynamicPattern,
+            source.snippet(access.start, access.end),
+            suggestion,
+            WarningSeverity::High,
+        ));
+    }
+    
+    warnings
+}
+
+/// An expression that builds a class name: a variable declaration, an assignment or a `className={...}` attribute
+/// `start` is where the statement begins, `value` the byte range of the expression itself
+struct ClassExpression<'a> {
+    name: Option<&'a str>,
+    start: usize,
+    value: std::ops::Range<usize>,
+}
+
+fn class_expressions<'a>(source: &'a ScriptFile) -> Vec<ClassExpression<'a>> {
+    let declaration_regex = Regex::new(r"(?m)(?:\b(?:const|let|var)\s+|^[ \t]*)([A-Za-z_$][\w$]*)\s*=[^=>]").unwrap();
+    let attribute_regex = Regex::new(r"\bclassName\s*=\s*\{").unwrap();
+    let code = source.code.as_str();
+    let mut expressions = Vec::new();
+    
+    for capture in declaration_regex.captures_iter(code) {
+        let whole = capture.get(0).unwrap();
+        let name = capture.get(1).unwrap();
+        let value_start = whole.end() - 1;
+        let value = value_start..expression_end(code, value_start);
+        
+        // `const Panel = (...) => { ... }` is a component and `x = { ... }` an object (or a JSX attribute), not a class name
+        if is_function(&code[value.clone()]) || code[value.clone()].trim_start().starts_with('{') {
+            continue;
         }
         
-        if function_call_regex.is_match(line) {
-            warnings.push(ComplexityWarning {
-                file_path: file_path.to_string(),
-                line_number: line_num + 1,
-                warning_type: ComplexityWarningType::UntrackedDynamicPattern,
-                pattern: line.trim().to_string(),
-                suggestion: "Function calls in class access make static analysis impossible. Consider explicit class mapping".to_string(),
-                severity: WarningSeverity::High,
-            });
+        expressions.push(ClassExpression {
+            name: Some(name.as_str()),
+            start: whole.start() + (whole.as_str().len() - whole.as_str().trim_start().len()),
+            value,
+        });
+    }
+    
+    for attribute in attribute_regex.find_iter(code) {
+        if let Some(close) = matching_close(code, attribute.end() - 1) {
+            expressions.push(ClassExpression { name: None, start: attribute.start(), value: attribute.end()..close });
         }
     }
     
-    warnings
+    expressions.sort_by_key(|expression| expression.start);
+    expressions
+}
+
+/// A top-level template literal: its byte range, how deeply templates nest inside it and its `${}` count
+struct TemplateLiteral {
+    start: usize,
+    end: usize,
+    depth: usize,
+    interpolations: usize,
+}
+
+fn template_literals(code: &str) -> Vec<TemplateLiteral> {
+    let bytes = code.as_bytes();
+    let mut templates = Vec::new();
+    let mut stack: Vec<u8> = Vec::new();
+    let mut current: Option<TemplateLiteral> = None;
+    
+    for (i, &byte) in bytes.iter().enumerate() {
+        match byte {
+            b'`' if stack.last() == Some(&b'`') => {
+                stack.pop();
+                if !stack.contains(&b'`') {
+                    if let Some(mut template) = current.take() {
+                        template.end = i + 1;
+                        templates.push(template);
+                    }
+                }
+            }
+            b'`' => {
+                stack.push(b'`');
+                let depth = stack.iter().filter(|&&b| b == b'`').count();
+                match current.as_mut() {
+                    Some(template) => template.depth = template.depth.max(depth),
+                    None => current = Some(TemplateLiteral { start: i, end: i, depth, interpolations: 0 }),
+                }
+            }
+            b'{' => {
+                if stack.last() == Some(&b'`') {
+                    if let Some(template) = current.as_mut() {
+                        template.interpolations += 1;
+                    }
+                }
+                stack.push(b'{');
+            }
+            b'(' | b'[' => stack.push(byte),
+            b'}' | b')' | b']' => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+    
+    templates
+}
+
+/// Blank out comments and the contents of string literals, keeping `${}` expressions inside templates,
+/// so the remaining brackets and operators are all real code. Offsets and line breaks are preserved.
+/// Strings end at a line break, so stray apostrophes in JSX text can't swallow the rest of the file.
+pub(crate) fn mask_literals(content: &str) -> String {
+    fn blank(masked: &mut String, ch: char) {
+        if ch == '\n' {
+            masked.push('\n');
+        } else {
+            masked.extend(std::iter::repeat_n(' ', ch.len_utf8()));
+        }
+    }
+    
+    let mut masked = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    // Brace depth of the code around each open `${`
+    let mut interpolations: Vec<usize> = Vec::new();
+    let mut braces = 0;
+    let mut in_template = false;
+    
+    while let Some(ch) = chars.next() {
+        if in_template {
+            match ch {
+                '`' => {
+                    in_template = false;
+                    masked.push('`');
+                }
+                '\\' => {
+                    blank(&mut masked, ch);
+                    if let Some(escaped) = chars.next() {
+                        blank(&mut masked, escaped);
+                    }
+                }
+                '$' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    masked.push_str("${");
+                    interpolations.push(braces);
+                    braces = 0;
+                    in_template = false;
+                }
+                _ => blank(&mut masked, ch),
+            }
+            continue;
+        }
+        
+        match ch {
+            '`' => {
+                in_template = true;
+                masked.push('`');
+            }
+            '\'' | '"' => {
+                masked.push(ch);
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    chars.next();
+                    if next == ch {
+                        masked.push(ch);
+                        break;
+                    }
+                    blank(&mut masked, next);
+                    if next == '\\' {
+                        if let Some(escaped) = chars.next() {
+                            blank(&mut masked, escaped);
+                        }
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                blank(&mut masked, ch);
+                while let Some(&next) = chars.peek() {
+                    if next == '\n' {
+                        break;
+                    }
+                    chars.next();
+                    blank(&mut masked, next);
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                blank(&mut masked, ch);
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    blank(&mut masked, next);
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            '{' => {
+                braces += 1;
+                masked.push('{');
+            }
+            '}' if braces == 0 && !interpolations.is_empty() => {
+                braces = interpolations.pop().unwrap_or(0);
+                in_template = true;
+                masked.push('}');
+            }
+            '}' => {
+                braces = braces.saturating_sub(1);
+                masked.push('}');
+            }
+            _ => masked.push(ch),
+        }
+    }
+    
+    masked
+}
+
+/// Index of the bracket closing the one at `open`
+fn matching_close(code: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, byte) in code.bytes().enumerate().skip(open) {
+        match byte {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Where the expression starting at `start` ends: a `;` or `,` outside brackets, the bracket closing an enclosing
+/// one, or a line break that doesn't continue the expression (no operator at the end of the line or the start of the next)
+fn expression_end(code: &str, start: usize) -> usize {
+    let bytes = code.as_bytes();
+    let mut stack: Vec<u8> = Vec::new();
+    
+    for i in start..bytes.len() {
+        match bytes[i] {
+            b'`' if stack.last() == Some(&b'`') => {
+                stack.pop();
+            }
+            b'`' | b'(' | b'[' | b'{' => stack.push(bytes[i]),
+            b')' | b']' | b'}' if stack.is_empty() => return i,
+            b')' | b']' | b'}' => {
+                stack.pop();
+            }
+            b';' | b',' if stack.is_empty() => return i,
+            b'\n' if stack.is_empty() => {
+                let before = code[start..i].trim_end();
+                let after = code[i..].trim_start();
+                let continued = before.ends_with(|c: char| "?:+-*/%=&|,.(".contains(c)) || after.starts_with(|c: char| "?:.+-*/%&|".contains(c));
+                if !before.is_empty() && !continued {
+                    return i;
+                }
+            }
+            _ => {}
+        }
+    }
+    
+    bytes.len()
+}
+
+/// True for a function expression: `function ...` or an arrow outside any brackets
+fn is_function(code: &str) -> bool {
+    let code = code.trim_start();
+    if code.starts_with("function") || code.starts_with("async") {
+        return true;
+    }
+    
+    let mut depth = 0;
+    let bytes = code.as_bytes();
+    (0..bytes.len()).any(|i| {
+        match bytes[i] {
+            b'(' | b'[' | b'{' => depth += 1,
+            b')' | b']' | b'}' => depth -= 1,
+            _ => {}
+        }
+        depth == 0 && bytes[i] == b'=' && bytes.get(i + 1) == Some(&b'>')
+    })
+}
+
+/// Offset of the first ternary `?` in masked code, ignoring `?.` and `??`
+fn find_ternary(code: &str) -> Option<usize> {
+    let bytes = code.as_bytes();
+    (0..bytes.len()).find(|&i| {
+        bytes[i] == b'?'
+            && bytes.get(i + 1).is_some_and(|&next| next != b'.' && next != b'?')
+            && (i == 0 || bytes[i - 1] != b'?')
+    })
+}
+
+/// True when `name` appears in masked code as an identifier rather than a property (`obj.name`) or part of a longer name
+fn references_identifier(code: &str, name: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+    code.match_indices(name).any(|(index, _)| {
+        let before = code[..index].chars().next_back();
+        let after = code[index + name.len()..].chars().next();
+        !before.is_some_and(|c| is_ident(c) || c == '.') && !after.is_some_and(is_ident)
+    })
+}
+
+/// `config` with every complexity rule enabled and no severity threshold
+/// Suppressions are matched against these warnings, so a comment silencing a warning the config
+/// filters out today isn't reported as unused.
+pub(crate) fn with_every_rule(config: &AnalysisConfig) -> AnalysisConfig {
+    let mut config = config.clone();
+    config.enable_complexity_warnings = true;
+    config.complexity_threshold = WarningSeverity::Low;
+    for settings in config.complexity_rules.values_mut() {
+        settings.enabled = true;
+    }
+    config
+}
+
+/// Whether `config` reports `warning`: warnings are on, its rule is enabled and it meets the threshold
+pub(crate) fn is_reported(warning: &ComplexityWarning, config: &AnalysisConfig) -> bool {
+    config.enable_complexity_warnings
+        && config.complexity_rules.get(&warning.rule_id).is_none_or(|settings| settings.enabled)
+        && severity_level(&warning.severity) >= severity_level(&config.complexity_threshold)
 }
 
 /// Convert severity to numeric level for comparison
-fn severity_level(severity: &WarningSeverity) -> u8 {
+pub(crate) fn severity_level(severity: &WarningSeverity) -> u8 {
     match severity {
         WarningSeverity::Low => 1,
         WarningSeverity::Medium => 2,
@@ -217,7 +802,7 @@ fn severity_level(severity: &WarningSeverity) -> u8 {
 
 /// Check if we should process this JavaScript/TypeScript file
 fn should_process_js_file(path: &Path, config: &AnalysisConfig) -> bool {
-    let is_js_file = path.extension().map_or(false, |ext| {
+    let is_js_file = path.extension().is_some_and(|ext| {
         matches!(ext.to_str(), Some("js") | Some("jsx") | Some("ts") | Some("tsx"))
     });
     
@@ -0,0 +1,149 @@
+// src/complexity_rules.rs - Complexity rule registry
+// Every complexity check is a rule with a stable ID. Config can switch rules off, override their severity
+// and suggestion and tune their thresholds; library users can register their own `ComplexityRule`s.
+
+use crate::complexity_analyzer::{
+    detect_conditional_class_assignment, detect_deep_selector_nesting, detect_deep_template_nesting,
+    detect_dynamic_class_construction, detect_long_descendant_chain, detect_oversized_rule_block,
+    detect_selector_concatenation, detect_untrackable_patterns, severity_level, ScriptFile,
+};
+use crate::{AnalysisConfig, ComplexityWarning, CssRule, RuleConfig};
+
+pub const DYNAMIC_CLASS_CONSTRUCTION: &str = "dynamic-class-construction";
+pub const CONDITIONAL_CLASS_ASSIGNMENT: &str = "conditional-class-assignment";
+pub const DEEP_TEMPLATE_NESTING: &str = "deep-template-nesting";
+pub const UNTRACKED_DYNAMIC_PATTERN: &str = "untracked-dynamic-pattern";
+pub const DEEP_SELECTOR_NESTING: &str = "deep-selector-nesting";
+pub const LONG_DESCENDANT_CHAIN: &str = "long-descendant-chain";
+pub const SELECTOR_CONCATENATION: &str = "selector-concatenation";
+pub const OVERSIZED_RULE_BLOCK: &str = "oversized-rule-block";
+
+/// A complexity check. Implement `check_script`, `check_stylesheet` or both; thresholds come from
+/// `settings.threshold(name, default)`, so users can tune them under the rule's ID in `complexity_rules`.
+/// `rule_id`, severity overrides and suggestion overrides are applied to the returned warnings by the registry.
+pub trait ComplexityRule: Send + Sync {
+    /// Stable ID used in config and reports, e.g. `deep-template-nesting`
+    fn id(&self) -> &str;
+
+    /// Check one JS/TS file
+    fn check_script(&self, _file: &ScriptFile, _settings: &RuleConfig) -> Vec<ComplexityWarning> {
+        Vec::new()
+    }
+
+    /// Check every parsed stylesheet rule, in source order
+    fn check_stylesheet(&self, _rules: &[CssRule], _settings: &RuleConfig) -> Vec<ComplexityWarning> {
+        Vec::new()
+    }
+}
+
+/// The rules `find_complexity_warnings_with_rules` runs. `RuleRegistry::default()` holds the built-ins.
+pub struct RuleRegistry {
+    rules: Vec<Box<dyn ComplexityRule>>,
+}
+
+impl RuleRegistry {
+    /// A registry with no rules at all
+    pub fn empty() -> Self {
+        RuleRegistry { rules: Vec::new() }
+    }
+
+    /// Add a rule; one with the same ID as an existing rule replaces it
+    pub fn register(&mut self, rule: Box<dyn ComplexityRule>) -> &mut Self {
+        match self.rules.iter().position(|existing| existing.id() == rule.id()) {
+            Some(index) => self.rules[index] = rule,
+            None => self.rules.push(rule),
+        }
+        self
+    }
+
+    pub fn ids(&self) -> Vec<&str> {
+        self.rules.iter().map(|rule| rule.id()).collect()
+    }
+
+    /// Run every enabled rule over one JS/TS file
+    pub fn check_script(&self, file: &ScriptFile, config: &AnalysisConfig) -> Vec<ComplexityWarning> {
+        self.run(config, |rule, settings| rule.check_script(file, settings))
+    }
+
+    /// Run every enabled rule over parsed stylesheet rules
+    pub fn check_stylesheets(&self, rules: &[CssRule], config: &AnalysisConfig) -> Vec<ComplexityWarning> {
+        let mut warnings = self.run(config, |rule, settings| rule.check_stylesheet(rules, settings));
+        warnings.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+        warnings
+    }
+
+    fn run(&self, config: &AnalysisConfig, check: impl Fn(&dyn ComplexityRule, &RuleConfig) -> Vec<ComplexityWarning>) -> Vec<ComplexityWarning> {
+        let defaults = RuleConfig::default();
+        let mut warnings = Vec::new();
+
+        for rule in &self.rules {
+            let settings = config.complexity_rules.get(rule.id()).unwrap_or(&defaults);
+            if !settings.enabled {
+                continue;
+            }
+
+            for mut warning in check(rule.as_ref(), settings) {
+                warning.rule_id = rule.id().to_string();
+                if let Some(severity) = &settings.severity {
+                    warning.severity = severity.clone();
+                }
+                if let Some(suggestion) = &settings.suggestion {
+                    warning.suggestion = suggestion.clone();
+                }
+                warnings.push(warning);
+            }
+        }
+
+        // Filter by severity threshold
+        warnings
+            .into_iter()
+            .filter(|w| severity_level(&w.severity) >= severity_level(&config.complexity_threshold))
+            .collect()
+    }
+}
+
+impl Default for RuleRegistry {
+    fn default() -> Self {
+        let mut registry = RuleRegistry::empty();
+        for rule in BUILTIN_RULES {
+            registry.register(Box::new(rule));
+        }
+        registry
+    }
+}
+
+type ScriptDetector = fn(&ScriptFile, &RuleConfig) -> Vec<ComplexityWarning>;
+type StylesheetDetector = fn(&[CssRule], &RuleConfig) -> Vec<ComplexityWarning>;
+
+/// A built-in rule: a detector function for scripts or for stylesheets
+#[derive(Clone, Copy)]
+struct BuiltinRule {
+    id: &'static str,
+    script: Option<ScriptDetector>,
+    stylesheet: Option<StylesheetDetector>,
+}
+
+impl ComplexityRule for BuiltinRule {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn check_script(&self, file: &ScriptFile, settings: &RuleConfig) -> Vec<ComplexityWarning> {
+        self.script.map(|detect| detect(file, settings)).unwrap_or_default()
+    }
+
+    fn check_stylesheet(&self, rules: &[CssRule], settings: &RuleConfig) -> Vec<ComplexityWarning> {
+        self.stylesheet.map(|detect| detect(rules, settings)).unwrap_or_default()
+    }
+}
+
+const BUILTIN_RULES: [BuiltinRule; 8] = [
+    BuiltinRule { id: DYNAMIC_CLASS_CONSTRUCTION, script: Some(detect_dynamic_class_construction), stylesheet: None },
+    BuiltinRule { id: CONDITIONAL_CLASS_ASSIGNMENT, script: Some(detect_conditional_class_assignment), stylesheet: None },
+    BuiltinRule { id: DEEP_TEMPLATE_NESTING, script: Some(detect_deep_template_nesting), stylesheet: None },
+    BuiltinRule { id: UNTRACKED_DYNAMIC_PATTERN, script: Some(detect_untrackable_patterns), stylesheet: None },
+    BuiltinRule { id: DEEP_SELECTOR_NESTING, script: None, stylesheet: Some(detect_deep_selector_nesting) },
+    BuiltinRule { id: LONG_DESCENDANT_CHAIN, script: None, stylesheet: Some(detect_long_descendant_chain) },
+    BuiltinRule { id: SELECTOR_CONCATENATION, script: None, stylesheet: Some(detect_selector_concatenation) },
+    BuiltinRule { id: OVERSIZED_RULE_BLOCK, script: None, stylesheet: Some(detect_oversized_rule_block) },
+];
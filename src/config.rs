@@ -0,0 +1,34 @@
+// src/config.rs - rustbrother.json config file handling
+// The config file is a serialized AnalysisConfig; any field left out keeps its default
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::AnalysisConfig;
+
+/// Name of the config file looked up from the analyzed directory upwards
+pub const CONFIG_FILE_NAME: &str = "rustbrother.json";
+
+/// Load an AnalysisConfig from a JSON config file
+pub fn load_config(path: &Path) -> anyhow::Result<AnalysisConfig> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read config {}: {}", path.display(), e))?;
+    let config: AnalysisConfig = serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("Invalid config {}: {}", path.display(), e))?;
+    Ok(AnalysisConfig { config_file: Some(path.to_path_buf()), ..config })
+}
+
+/// Find the nearest config file, starting at `start` and walking up through its parents
+pub fn find_config_file(start: &Path) -> Option<PathBuf> {
+    let start = start.canonicalize().ok()?;
+    let first_dir = if start.is_dir() { start.as_path() } else { start.parent()? };
+    
+    first_dir
+        .ancestors()
+        .map(|dir| dir.join(CONFIG_FILE_NAME))
+        .find(|candidate| candidate.is_file())
+}
+
+/// The config written by `rustbrother init`: every option with its default value
+pub fn starter_config() -> anyhow::Result<String> {
+    Ok(serde_json::to_string_pretty(&AnalysisConfig::default())? + "\n")
+}
@@ -2,38 +2,536 @@
 // This module handles parsing CSS and SCSS files to extract class definitions and custom properties
 
 use regex::Regex;
-use walkdir::WalkDir;
-use std::fs;
 use std::path::Path;
 use std::collections::HashSet;
-use crate::{CssClass, CustomProperty, AnalysisConfig};
+use crate::error;
+use crate::files::{source_files, FileProvider, FsFiles};
+use crate::{CssClass, CssDeclaration, CssRule, CustomProperty, AnalysisConfig, Specificity};
 
 /// Find all CSS classes defined in CSS/SCSS files
-pub fn find_css_classes(path: &Path, config: &AnalysisConfig) -> anyhow::Result<Vec<CssClass>> {
+pub fn find_css_classes(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<CssClass>> {
+    find_css_classes_in(&FsFiles::new(path), config)
+}
+
+/// Same as `find_css_classes`, reading from `files`
+pub fn find_css_classes_in(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<Vec<CssClass>> {
     let mut classes = Vec::new();
     
-    for entry in WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| should_process_css_file(e.path(), config))
-    {
-        let file_path = entry.path().to_string_lossy().to_string();
+    for file in source_files(files, config, should_process_css_file) {
+        let file = file?;
+        classes.extend(parse_stylesheet(&file.content, &file.file_path)?);
+    }
+    
+    Ok(classes)
+}
+
+/// Parse a single stylesheet's content, picking the SCSS or CSS parser from the file extension
+pub fn parse_stylesheet(content: &str, file_path: &str) -> error::Result<Vec<CssClass>> {
+    if is_scss_file(Path::new(file_path)) {
+        parse_scss_file(content, file_path)
+    } else {
+        parse_css_file(content, file_path)
+    }
+}
+
+/// Find every rule block in CSS/SCSS files
+pub fn find_css_rules(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<CssRule>> {
+    find_css_rules_in(&FsFiles::new(path), config)
+}
+
+/// Same as `find_css_rules`, reading from `files`
+pub fn find_css_rules_in(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<Vec<CssRule>> {
+    let mut rules = Vec::new();
+    
+    for file in source_files(files, config, should_process_css_file) {
+        let file = file?;
+        rules.extend(parse_rules(&file.content, &file.file_path));
+    }
+    
+    Ok(rules)
+}
+
+/// An open block while scanning a stylesheet
+enum Block {
+    /// A rule; `nested` is false for at-rules that only wrap the parent rule's declarations
+    Rule { index: usize, nested: bool },
+    /// An at-rule at the top level, e.g. `@media (...)` around whole rules
+    Condition(String),
+    /// Blocks whose contents aren't rules: `@keyframes`, `@font-face`, nested properties
+    Opaque,
+}
+
+/// Parse every rule block in a stylesheet, in source order
+/// SCSS nesting is resolved (`&` is replaced by the parent selector, other nested selectors become
+/// descendants) and at-rules inside a rule produce a conditional copy of that rule
+pub fn parse_rules(content: &str, file_path: &str) -> Vec<CssRule> {
+    scan_rules(content, file_path).0
+}
+
+/// Lines and messages of the brace mismatches in a stylesheet: a `}` closing nothing, or the outermost
+/// `{` left open at the end of the file
+pub fn find_syntax_errors(content: &str, file_path: &str) -> Vec<(usize, String)> {
+    scan_rules(content, file_path).1
+}
+
+fn scan_rules(content: &str, file_path: &str) -> (Vec<CssRule>, Vec<(usize, String)>) {
+    let scss = is_scss_file(Path::new(file_path));
+    let mut rules: Vec<CssRule> = Vec::new();
+    let mut stack: Vec<Block> = Vec::new();
+    let mut open_lines: Vec<usize> = Vec::new();
+    let mut errors = Vec::new();
+    
+    let mut buffer = String::new();
+    let mut buffer_line = 0;
+    let mut line = 1;
+    let mut quote: Option<char> = None;
+    let mut parens = 0usize;
+    let mut interpolation = 0usize;
+    
+    let mut chars = content.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '\n' {
+            line += 1;
+        }
         
-        if let Ok(content) = fs::read_to_string(entry.path()) {
-            let file_classes = if is_scss_file(entry.path()) {
-                parse_scss_file(&content, &file_path)?
-            } else {
-                parse_css_file(&content, &file_path)?
-            };
-            classes.extend(file_classes);
+        if let Some(open) = quote {
+            buffer.push(ch);
+            if ch == open {
+                quote = None;
+            }
+            continue;
+        }
+        
+        match ch {
+            '/' if chars.peek() == Some(&'*') => {
+                // Block comment - keep the line count moving
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        line += 1;
+                    }
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            '/' if scss && parens == 0 && chars.peek() == Some(&'/') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        line += 1;
+                        break;
+                    }
+                }
+            }
+            '"' | '\'' => {
+                quote = Some(ch);
+                buffer.push(ch);
+            }
+            '(' => {
+                parens += 1;
+                buffer.push(ch);
+            }
+            ')' => {
+                parens = parens.saturating_sub(1);
+                buffer.push(ch);
+            }
+            '#' if chars.peek() == Some(&'{') => {
+                chars.next();
+                interpolation += 1;
+                buffer.push_str("#{");
+            }
+            '}' if interpolation > 0 => {
+                interpolation -= 1;
+                buffer.push(ch);
+            }
+            '{' if parens == 0 => {
+                let prelude = collapse_whitespace(&buffer);
+                buffer.clear();
+                let block = open_block(&mut rules, &stack, prelude, file_path, buffer_line);
+                stack.push(block);
+                open_lines.push(line);
+            }
+            ';' if parens == 0 => {
+                add_declaration(&mut rules, &stack, &buffer, buffer_line);
+                buffer.clear();
+            }
+            '}' => {
+                // The last declaration in a block doesn't need a `;`
+                add_declaration(&mut rules, &stack, &buffer, buffer_line);
+                buffer.clear();
+                if open_lines.pop().is_none() {
+                    errors.push((line, String::from("unexpected `}` closes no block")));
+                }
+                if let Some(Block::Rule { index, .. }) = stack.pop() {
+                    rules[index].end_line = line;
+                }
+            }
+            _ => {
+                if buffer.trim().is_empty() && !ch.is_whitespace() {
+                    buffer_line = line;
+                }
+                buffer.push(ch);
+            }
         }
     }
     
-    Ok(classes)
+    if let Some(line) = open_lines.first() {
+        errors.push((*line, String::from("`{` is never closed")));
+    }
+    (rules, errors)
+}
+
+/// Decide what a `{` opens and register the rule if it's one
+fn open_block(rules: &mut Vec<CssRule>, stack: &[Block], prelude: String, file_path: &str, start_line: usize) -> Block {
+    if stack.iter().any(|block| matches!(block, Block::Opaque)) || prelude.is_empty() || prelude.ends_with(':') {
+        return Block::Opaque;
+    }
+    
+    let parent = stack.iter().rev().find_map(|block| match block {
+        Block::Rule { index, .. } => Some(*index),
+        _ => None,
+    });
+    let depth = stack.iter().filter(|block| matches!(block, Block::Rule { nested: true, .. })).count();
+    
+    if prelude.starts_with('@') {
+        let name = prelude.split(|c: char| c.is_whitespace() || c == '(').next().unwrap_or("");
+        if name.ends_with("keyframes") || name == "@font-face" || name == "@page" {
+            return Block::Opaque;
+        }
+        
+        // `.x { @media (...) { ... } }` - the declarations still belong to `.x`
+        return match parent {
+            Some(parent) => {
+                let mut rule = rules[parent].clone();
+                rule.conditions.push(prelude);
+                rule.start_line = start_line;
+                rule.declarations.clear();
+                rules.push(rule);
+                Block::Rule { index: rules.len() - 1, nested: false }
+            }
+            None => Block::Condition(prelude),
+        };
+    }
+    
+    let parent_selectors = parent.map(|index| split_selector_list(&rules[index].selector)).unwrap_or_default();
+    let selector = expand_selector(&parent_selectors, &prelude).join(", ");
+    let conditions = match parent {
+        Some(parent) => rules[parent].conditions.clone(),
+        None => stack
+            .iter()
+            .filter_map(|block| match block {
+                Block::Condition(condition) => Some(condition.clone()),
+                _ => None,
+            })
+            .collect(),
+    };
+    
+    rules.push(CssRule {
+        specificity: selector_specificity(&selector),
+        selector,
+        raw_selector: prelude,
+        file_path: file_path.to_string(),
+        start_line,
+        end_line: start_line,
+        depth,
+        conditions,
+        declarations: Vec::new(),
+    });
+    Block::Rule { index: rules.len() - 1, nested: true }
+}
+
+/// Record `property: value` in the innermost open rule
+/// SCSS variables, `@include`/`@extend` and anything outside a rule are ignored
+fn add_declaration(rules: &mut [CssRule], stack: &[Block], text: &str, line_number: usize) {
+    let Some(Block::Rule { index, .. }) = stack.last() else { return };
+    let text = collapse_whitespace(text);
+    let Some((property, value)) = text.split_once(':') else { return };
+    let property = property.trim();
+    
+    let is_property = property.strip_prefix("--").unwrap_or(property).chars().enumerate().all(|(i, c)| {
+        c.is_ascii_alphabetic() || (i > 0 && (c.is_ascii_digit() || c == '-' || c == '_'))
+    });
+    if property.is_empty() || !is_property {
+        return;
+    }
+    
+    // `!important` (or `! important`, any case) at the end of the value
+    let value = value.trim();
+    let lowercase = value.to_ascii_lowercase();
+    let important_at = lowercase
+        .strip_suffix("important")
+        .map(str::trim_end)
+        .and_then(|rest| rest.strip_suffix('!'))
+        .map(str::len);
+    let (value, important) = match important_at {
+        Some(position) => (value[..position].trim_end().to_string(), true),
+        None => (value.to_string(), false),
+    };
+    
+    rules[*index].declarations.push(CssDeclaration {
+        property: property.to_string(),
+        value,
+        important,
+        line_number,
+    });
+}
+
+/// Expand a (possibly comma separated) nested selector against its parent selectors
+fn expand_selector(parents: &[String], selector: &str) -> Vec<String> {
+    let parts = split_selector_list(selector);
+    if parents.is_empty() {
+        return parts.into_iter().map(|part| part.replace('&', "")).collect();
+    }
+    
+    parents
+        .iter()
+        .flat_map(|parent| {
+            parts.iter().map(move |part| {
+                if part.contains('&') {
+                    part.replace('&', parent)
+                } else {
+                    format!("{} {}", parent, part)
+                }
+            })
+        })
+        .collect()
+}
+
+/// Specificity of a selector; for a selector list, the highest of its selectors
+/// `:is()`, `:not()` and `:has()` take their most specific argument, `:where()` counts nothing
+pub fn selector_specificity(selector: &str) -> Specificity {
+    split_selector_list(selector)
+        .iter()
+        .map(|complex| {
+            selector_tokens(complex)
+                .iter()
+                .fold(Specificity::default(), |total, token| match token {
+                    SelectorToken::Simple(simple) => add_specificity(total, simple_specificity(simple)),
+                    SelectorToken::Combinator(_) => total,
+                })
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+/// The simple selectors of a complex selector's subject (its last compound) and the
+/// ancestor part before it: `.card > .title:hover` -> ([".title", ":hover"], ".card >")
+pub(crate) fn subject_and_ancestors(complex: &str) -> (Vec<String>, String) {
+    let tokens = selector_tokens(complex);
+    let subject_start = tokens
+        .iter()
+        .rposition(|token| matches!(token, SelectorToken::Combinator(_)))
+        .map_or(0, |index| index + 1);
+    
+    let subject = tokens[subject_start..]
+        .iter()
+        .filter_map(|token| match token {
+            SelectorToken::Simple(simple) => Some(simple.clone()),
+            SelectorToken::Combinator(_) => None,
+        })
+        .collect();
+    let ancestors = tokens[..subject_start]
+        .iter()
+        .map(|token| match token {
+            SelectorToken::Simple(simple) => simple.clone(),
+            SelectorToken::Combinator(' ') => " ".to_string(),
+            SelectorToken::Combinator(combinator) => format!(" {} ", combinator),
+        })
+        .collect::<String>();
+    
+    (subject, collapse_whitespace(&ancestors))
+}
+
+/// A piece of a complex selector: a simple selector (`.a`, `#b`, `div`, `[x]`, `:hover`) or a combinator
+#[derive(Debug, PartialEq)]
+enum SelectorToken {
+    Simple(String),
+    Combinator(char),
+}
+
+fn selector_tokens(selector: &str) -> Vec<SelectorToken> {
+    let chars: Vec<char> = selector.trim().chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    
+    let is_ident = |c: char| c.is_alphanumeric() || c == '-' || c == '_' || c == '\\' || !c.is_ascii();
+    
+    while i < chars.len() {
+        let start = i;
+        match chars[i] {
+            ' ' | '>' | '+' | '~' => {
+                // Collapse `a  >  b` into one combinator; plain whitespace is the descendant combinator
+                let mut combinator = ' ';
+                while i < chars.len() && matches!(chars[i], ' ' | '>' | '+' | '~') {
+                    if chars[i] != ' ' {
+                        combinator = chars[i];
+                    }
+                    i += 1;
+                }
+                if !tokens.is_empty() {
+                    tokens.push(SelectorToken::Combinator(combinator));
+                }
+                continue;
+            }
+            '#' if chars.get(i + 1) == Some(&'{') => {
+                // SCSS interpolation - contributes no specificity we can know
+                while i < chars.len() && chars[i] != '}' {
+                    i += 1;
+                }
+                i += 1;
+                continue;
+            }
+            '[' => {
+                while i < chars.len() && chars[i] != ']' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            ':' => {
+                i += 1;
+                if chars.get(i) == Some(&':') {
+                    i += 1;
+                }
+                while i < chars.len() && is_ident(chars[i]) {
+                    i += 1;
+                }
+                if chars.get(i) == Some(&'(') {
+                    let mut depth = 0;
+                    while i < chars.len() {
+                        match chars[i] {
+                            '(' => depth += 1,
+                            ')' => depth -= 1,
+                            _ => {}
+                        }
+                        i += 1;
+                        if depth == 0 {
+                            break;
+                        }
+                    }
+                }
+            }
+            '.' | '#' | '%' => {
+                i += 1;
+                while i < chars.len() && is_ident(chars[i]) {
+                    i += 1;
+                }
+            }
+            '*' => i += 1,
+            c if is_ident(c) => {
+                while i < chars.len() && is_ident(chars[i]) {
+                    i += 1;
+                }
+            }
+            _ => {
+                i += 1;
+                continue;
+            }
+        }
+        tokens.push(SelectorToken::Simple(chars[start..i].iter().collect()));
+    }
+    
+    // A trailing combinator (`.a >` from a broken selector) isn't meaningful
+    while matches!(tokens.last(), Some(SelectorToken::Combinator(_))) {
+        tokens.pop();
+    }
+    tokens
+}
+
+fn simple_specificity(simple: &str) -> Specificity {
+    let mut specificity = Specificity::default();
+    
+    if simple.starts_with("::") {
+        specificity.elements = 1;
+    } else if let Some(pseudo) = simple.strip_prefix(':') {
+        let (name, arguments) = match pseudo.split_once('(') {
+            Some((name, rest)) => (name.to_ascii_lowercase(), Some(rest.strip_suffix(')').unwrap_or(rest))),
+            None => (pseudo.to_ascii_lowercase(), None),
+        };
+        match (name.as_str(), arguments) {
+            ("where", _) => {}
+            ("is" | "not" | "has" | "matches" | "any", Some(arguments)) => specificity = selector_specificity(arguments),
+            ("before" | "after" | "first-line" | "first-letter", None) => specificity.elements = 1,
+            _ => specificity.classes = 1,
+        }
+    } else if simple.starts_with('#') {
+        specificity.ids = 1;
+    } else if simple.starts_with('.') || simple.starts_with('[') || simple.starts_with('%') {
+        specificity.classes = 1;
+    } else if simple != "*" {
+        specificity.elements = 1;
+    }
+    
+    specificity
+}
+
+fn add_specificity(a: Specificity, b: Specificity) -> Specificity {
+    Specificity {
+        ids: a.ids + b.ids,
+        classes: a.classes + b.classes,
+        elements: a.elements + b.elements,
+    }
+}
+
+/// Split a selector list on top-level commas, keeping `:is(.a, .b)` intact
+pub(crate) fn split_selector_list(selector: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut parens = 0usize;
+    
+    for ch in selector.chars() {
+        match ch {
+            '(' => parens += 1,
+            ')' => parens = parens.saturating_sub(1),
+            ',' if parens == 0 => {
+                parts.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(ch);
+    }
+    parts.push(current.trim().to_string());
+    
+    parts.into_iter().filter(|part| !part.is_empty()).collect()
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Find the lines spanned by the rule whose selector starts on `line_number` (1-based)
+/// Returns the first and last line of the block, including the closing brace
+pub fn rule_block_range(content: &str, line_number: usize) -> Option<(usize, usize)> {
+    let mut depth = 0;
+    let mut opened = false;
+    
+    for (index, line) in content.lines().enumerate().skip(line_number.checked_sub(1)?) {
+        for ch in line.chars() {
+            match ch {
+                '{' => {
+                    depth += 1;
+                    opened = true;
+                }
+                '}' if opened => depth -= 1,
+                // A `;` before any `{` means this line is a declaration, not a rule
+                ';' if !opened => return None,
+                _ => {}
+            }
+        }
+        
+        if opened && depth <= 0 {
+            return Some((line_number, index + 1));
+        }
+    }
+    
+    None
 }
 
 /// Parse SCSS files with proper nesting support
-fn parse_scss_file(content: &str, file_path: &str) -> anyhow::Result<Vec<CssClass>> {
+fn parse_scss_file(content: &str, file_path: &str) -> error::Result<Vec<CssClass>> {
     let mut classes = Vec::new();
     let lines: Vec<&str> = content.lines().collect();
     let expanded_selectors = expand_scss_nesting(&lines);
@@ -45,6 +543,7 @@ fn parse_scss_file(content: &str, file_path: &str) -> anyhow::Result<Vec<CssClas
                 name: class_name,
                 file_path: file_path.to_string(),
                 line_number: line_number + 1,
+                evidence: Vec::new(),
             });
         }
     }
@@ -102,16 +601,16 @@ fn expand_scss_nesting(lines: &[&str]) -> Vec<(usize, String)> {
 
 /// Build full selector from nesting stack and current selector - FIXED VERSION
 fn build_full_selector(stack: &[String], current: &str) -> String {
-    if current.starts_with('&') {
+    if let Some(suffix) = current.strip_prefix('&') {
         // Handle & reference - preserve exact concatenation
         if let Some(parent) = stack.last() {
             // Simply concatenate parent + current without &
             // e.g., "panel" + "&_outline" = "panel_outline"
             // e.g., "panel_graphic" + "&Icon" = "panel_graphicIcon"
-            format!("{}{}", parent, &current[1..])
+            format!("{}{}", parent, suffix)
         } else {
             // If no parent, just remove the &
-            current[1..].to_string()
+            suffix.to_string()
         }
     } else if stack.is_empty() {
         // Root level selector
@@ -149,9 +648,9 @@ fn extract_selector_from_line(line: &str) -> Option<String> {
 }
 
 /// Parse regular CSS files
-fn parse_css_file(content: &str, file_path: &str) -> anyhow::Result<Vec<CssClass>> {
+fn parse_css_file(content: &str, file_path: &str) -> error::Result<Vec<CssClass>> {
     let mut classes = Vec::new();
-    let class_regex = Regex::new(r"\.([a-zA-Z][a-zA-Z0-9_-]*)\s*[{:,]")?;
+    let class_regex = Regex::new(r"\.([a-zA-Z][a-zA-Z0-9_-]*)\s*[{:,]").unwrap();
     
     for (line_num, line) in content.lines().enumerate() {
         if is_comment_or_empty(line) {
@@ -164,6 +663,7 @@ fn parse_css_file(content: &str, file_path: &str) -> anyhow::Result<Vec<CssClass
                     name: class_name.as_str().to_string(),
                     file_path: file_path.to_string(),
                     line_number: line_num + 1,
+                    evidence: Vec::new(),
                 });
             }
         }
@@ -173,28 +673,26 @@ fn parse_css_file(content: &str, file_path: &str) -> anyhow::Result<Vec<CssClass
 }
 
 /// Find CSS custom properties (CSS variables) in stylesheets
-pub fn find_custom_properties(path: &Path, config: &AnalysisConfig) -> anyhow::Result<Vec<CustomProperty>> {
+pub fn find_custom_properties(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<CustomProperty>> {
+    find_custom_properties_in(&FsFiles::new(path), config)
+}
+
+/// Same as `find_custom_properties`, reading from `files`
+pub fn find_custom_properties_in(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<Vec<CustomProperty>> {
     let mut properties = Vec::new();
-    let property_regex = Regex::new(r"(--[a-zA-Z][a-zA-Z0-9_-]*)\s*:\s*([^;]+);")?;
-    
-    for entry in WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| should_process_css_file(e.path(), config))
-    {
-        let file_path = entry.path().to_string_lossy().to_string();
-        
-        if let Ok(content) = fs::read_to_string(entry.path()) {
-            for (line_num, line) in content.lines().enumerate() {
-                for capture in property_regex.captures_iter(line) {
-                    if let (Some(name), Some(value)) = (capture.get(1), capture.get(2)) {
-                        properties.push(CustomProperty {
-                            name: name.as_str().to_string(),
-                            value: value.as_str().trim().to_string(),
-                            file_path: file_path.to_string(),
-                            line_number: line_num + 1,
-                        });
-                    }
+    let property_regex = Regex::new(r"(--[a-zA-Z][a-zA-Z0-9_-]*)\s*:\s*([^;]+);").unwrap();
+    
+    for file in source_files(files, config, should_process_css_file) {
+        let file = file?;
+        for (line_num, line) in file.content.lines().enumerate() {
+            for capture in property_regex.captures_iter(line) {
+                if let (Some(name), Some(value)) = (capture.get(1), capture.get(2)) {
+                    properties.push(CustomProperty {
+                        name: name.as_str().to_string(),
+                        value: value.as_str().trim().to_string(),
+                        file_path: file.file_path.clone(),
+                        line_number: line_num + 1,
+                    });
                 }
             }
         }
@@ -204,20 +702,19 @@ pub fn find_custom_properties(path: &Path, config: &AnalysisConfig) -> anyhow::R
 }
 
 /// Find where CSS custom properties are used with var() function
-pub fn find_custom_property_usage(path: &Path, config: &AnalysisConfig) -> anyhow::Result<HashSet<String>> {
+pub fn find_custom_property_usage(path: &Path, config: &AnalysisConfig) -> error::Result<HashSet<String>> {
+    find_custom_property_usage_in(&FsFiles::new(path), config)
+}
+
+/// Same as `find_custom_property_usage`, reading from `files`
+pub fn find_custom_property_usage_in(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<HashSet<String>> {
     let mut used_properties = HashSet::new();
-    let var_regex = Regex::new(r"var\(\s*(--[a-zA-Z][a-zA-Z0-9_-]*)\s*\)")?;
-    
-    for entry in WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| should_process_css_file(e.path(), config))
-    {
-        if let Ok(content) = fs::read_to_string(entry.path()) {
-            for capture in var_regex.captures_iter(&content) {
-                if let Some(property_name) = capture.get(1) {
-                    used_properties.insert(property_name.as_str().to_string());
-                }
+    let var_regex = Regex::new(r"var\(\s*(--[a-zA-Z][a-zA-Z0-9_-]*)\s*\)").unwrap();
+    
+    for file in source_files(files, config, should_process_css_file) {
+        for capture in var_regex.captures_iter(&file?.content) {
+            if let Some(property_name) = capture.get(1) {
+                used_properties.insert(property_name.as_str().to_string());
             }
         }
     }
@@ -237,7 +734,7 @@ fn extract_classes_from_selector(selector: &str) -> Vec<String> {
 
 // Helper functions
 fn is_scss_file(path: &Path) -> bool {
-    path.extension().map_or(false, |ext| ext == "scss" || ext == "sass")
+    path.extension().is_some_and(|ext| ext == "scss" || ext == "sass")
 }
 
 fn is_comment_or_empty(line: &str) -> bool {
@@ -257,8 +754,8 @@ fn contains_class_selector(selector: &str) -> bool {
     selector.contains('.')
 }
 
-fn should_process_css_file(path: &Path, config: &AnalysisConfig) -> bool {
-    let is_css_file = path.extension().map_or(false, |ext| {
+pub(crate) fn should_process_css_file(path: &Path, config: &AnalysisConfig) -> bool {
+    let is_css_file = path.extension().is_some_and(|ext| {
         matches!(ext.to_str(), Some("css") | Some("scss") | Some("sass"))
     });
     
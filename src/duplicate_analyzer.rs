@@ -0,0 +1,217 @@
+// src/duplicate_analyzer.rs - Copy-pasted rule bodies across stylesheets
+// Normalizes declaration blocks and groups identical and near-identical rules so they can be
+// replaced with one shared utility class
+
+use regex::Regex;
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use crate::css_parser::find_css_rules;
+use crate::error;
+use crate::{AnalysisConfig, CssDeclaration, CssRule, DuplicateRuleGroup, RuleLocation};
+
+/// Well-known utility bodies: (name, declarations that must all be shared)
+const KNOWN_UTILITIES: &[(&str, &[&str])] = &[
+    ("visually-hidden", &["position: absolute", "width: 1px", "height: 1px", "overflow: hidden"]),
+    ("truncate", &["overflow: hidden", "text-overflow: ellipsis", "white-space: nowrap"]),
+    ("absolute-fill", &["position: absolute", "top: 0", "right: 0", "bottom: 0", "left: 0"]),
+    ("list-reset", &["list-style: none", "margin: 0", "padding: 0"]),
+    ("flex-center", &["display: flex", "align-items: center", "justify-content: center"]),
+    ("inline-flex-center", &["display: inline-flex", "align-items: center", "justify-content: center"]),
+    ("flex-column", &["display: flex", "flex-direction: column"]),
+];
+
+/// Find duplicated rule bodies in every stylesheet under `path`
+pub fn find_duplicate_rules(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<DuplicateRuleGroup>> {
+    let rules = find_css_rules(path, config)?;
+    Ok(group_duplicate_rules(&rules, config))
+}
+
+/// Group rules whose normalized declaration blocks are identical, or overlap by at least
+/// `config.duplicate_similarity`. Groups are sorted by the bytes a shared utility would save.
+pub fn group_duplicate_rules(rules: &[CssRule], config: &AnalysisConfig) -> Vec<DuplicateRuleGroup> {
+    // Step 1: Bucket rules by their exact normalized body
+    let mut bodies: Vec<(BTreeSet<String>, Vec<&CssRule>)> = Vec::new();
+    let mut body_index: HashMap<BTreeSet<String>, usize> = HashMap::new();
+
+    for rule in rules {
+        let body: BTreeSet<String> = rule.declarations.iter().map(normalize_declaration).collect();
+        if body.len() < config.duplicate_min_declarations.max(1) {
+            continue;
+        }
+
+        match body_index.get(&body) {
+            Some(&index) => bodies[index].1.push(rule),
+            None => {
+                body_index.insert(body.clone(), bodies.len());
+                bodies.push((body, vec![rule]));
+            }
+        }
+    }
+
+    // Step 2: Merge distinct bodies that are similar enough into clusters
+    let mut cluster: Vec<usize> = (0..bodies.len()).collect();
+    if config.duplicate_similarity < 1.0 {
+        for i in 0..bodies.len() {
+            for j in (i + 1)..bodies.len() {
+                if similarity(&bodies[i].0, &bodies[j].0) >= config.duplicate_similarity {
+                    let (from, to) = (find_root(&mut cluster, j), find_root(&mut cluster, i));
+                    cluster[from] = to;
+                }
+            }
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<usize>> = HashMap::new();
+    for index in 0..bodies.len() {
+        let root = find_root(&mut cluster, index);
+        clusters.entry(root).or_default().push(index);
+    }
+
+    // Step 3: Every cluster with more than one rule is a duplicate group
+    let mut groups = Vec::new();
+    for members in clusters.into_values() {
+        match duplicate_group(&bodies, &members, config) {
+            Some(group) => groups.push(group),
+            // The cluster as a whole shares too little - fall back to its exact copies
+            None => groups.extend(members.iter().filter_map(|&member| duplicate_group(&bodies, &[member], config))),
+        }
+    }
+
+    groups.sort_by(|a, b| {
+        b.estimated_bytes_saved
+            .cmp(&a.estimated_bytes_saved)
+            .then_with(|| a.suggested_name.cmp(&b.suggested_name))
+            .then_with(|| (&a.occurrences[0].file_path, a.occurrences[0].start_line).cmp(&(&b.occurrences[0].file_path, b.occurrences[0].start_line)))
+    });
+    groups
+}
+
+/// Build the group for a set of bodies, if they share enough declarations across at least two rules
+fn duplicate_group(bodies: &[(BTreeSet<String>, Vec<&CssRule>)], members: &[usize], config: &AnalysisConfig) -> Option<DuplicateRuleGroup> {
+    let shared: BTreeSet<String> = members
+        .iter()
+        .map(|&index| bodies[index].0.clone())
+        .reduce(|shared, body| shared.intersection(&body).cloned().collect())?;
+    let copies: Vec<&CssRule> = members.iter().flat_map(|&index| bodies[index].1.iter().copied()).collect();
+
+    if copies.len() < 2 || shared.len() < config.duplicate_min_declarations.max(1) {
+        return None;
+    }
+
+    let declarations: Vec<String> = shared.into_iter().collect();
+    let body_bytes: usize = declarations.iter().map(|declaration| declaration.len() + 1).sum();
+
+    let mut occurrences: Vec<RuleLocation> = copies
+        .iter()
+        .map(|rule| RuleLocation {
+            selector: rule.selector.clone(),
+            file_path: rule.file_path.clone(),
+            start_line: rule.start_line,
+            end_line: rule.end_line,
+        })
+        .collect();
+    occurrences.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+
+    Some(DuplicateRuleGroup {
+        suggested_name: suggest_utility_name(&declarations, &copies),
+        identical: members.len() == 1,
+        estimated_bytes_saved: body_bytes * (copies.len() - 1),
+        declarations,
+        occurrences,
+    })
+}
+
+/// Normalize a declaration so formatting differences don't hide a copy:
+/// lowercase property, collapsed whitespace, no space after commas, lowercase values
+/// (except strings and urls) and `0px` -> `0`
+fn normalize_declaration(declaration: &CssDeclaration) -> String {
+    let mut value = declaration.value.split_whitespace().collect::<Vec<_>>().join(" ").replace(", ", ",");
+    if !value.contains(['"', '\'']) && !value.contains("url(") {
+        value = value.to_lowercase();
+    }
+
+    let zero_units = Regex::new(r"\b0(?:px|em|rem)\b").unwrap();
+    let value = zero_units.replace_all(&value, "0");
+
+    let important = if declaration.important { " !important" } else { "" };
+    format!("{}: {}{}", declaration.property.to_lowercase(), value, important)
+}
+
+/// Jaccard similarity of two declaration sets
+fn similarity(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+    let shared = a.intersection(b).count();
+    let total = a.union(b).count();
+    if total == 0 {
+        0.0
+    } else {
+        shared as f64 / total as f64
+    }
+}
+
+fn find_root(cluster: &mut [usize], index: usize) -> usize {
+    let mut root = index;
+    while cluster[root] != root {
+        root = cluster[root];
+    }
+    cluster[index] = root;
+    root
+}
+
+/// Name the shared utility: a well-known pattern, else the words every copy's class name ends with
+/// (`cardFooter` and `toolbarFooter` -> `footer`), else the properties it sets
+fn suggest_utility_name(declarations: &[String], copies: &[&CssRule]) -> String {
+    if let Some((name, _)) = KNOWN_UTILITIES
+        .iter()
+        .find(|(_, required)| required.iter().all(|r| declarations.iter().any(|d| d == r)))
+    {
+        return name.to_string();
+    }
+
+    let class_regex = Regex::new(r"\.([a-zA-Z][a-zA-Z0-9_-]*)").unwrap();
+    let shared_suffix = copies
+        .iter()
+        .map(|rule| {
+            class_regex
+                .captures_iter(&rule.selector)
+                .last()
+                .map(|last| class_name_words(&last[1]))
+                .unwrap_or_default()
+        })
+        .reduce(|suffix, words| {
+            let common = suffix.iter().rev().zip(words.iter().rev()).take_while(|(a, b)| a == b).count();
+            suffix[suffix.len() - common..].to_vec()
+        })
+        .unwrap_or_default();
+    if !shared_suffix.is_empty() {
+        return shared_suffix.join("-");
+    }
+
+    let properties: Vec<&str> = declarations
+        .iter()
+        .filter_map(|declaration| declaration.split(':').next())
+        .filter(|property| !property.starts_with("--"))
+        .take(2)
+        .collect();
+    format!("u-{}", if properties.is_empty() { "shared".to_string() } else { properties.join("-") })
+}
+
+/// Split a class name into lowercase words: `panel_footerLink` -> `panel`, `footer`, `link`
+fn class_name_words(name: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut current = String::new();
+    for ch in name.chars() {
+        if ch == '-' || ch == '_' || (ch.is_ascii_uppercase() && !current.is_empty()) {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            if ch == '-' || ch == '_' {
+                continue;
+            }
+        }
+        current.push(ch.to_ascii_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
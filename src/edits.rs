@@ -0,0 +1,393 @@
+// src/edits.rs - Edits queued from the terminal UI
+// `rustbrother tui` doesn't touch any file while browsing: classes marked as safelisted and rule blocks queued for
+// deletion collect here, are shown as a unified diff and are only written when the user confirms on exit.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::fixer::{remove_rules, RuleRemoval};
+use crate::SafelistEntry;
+
+/// Lines of unchanged context around each change in a diff
+const DIFF_CONTEXT: usize = 3;
+
+/// Safelist additions for the config file and rule blocks to delete from stylesheets
+#[derive(Debug, Clone)]
+pub struct PendingEdits {
+    pub config_path: PathBuf,
+    pub safelist: Vec<SafelistEntry>,
+    pub removals: Vec<RuleRemoval>,
+}
+
+/// One file's content before and after the pending edits
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub path: PathBuf,
+    pub old: String,
+    pub new: String,
+}
+
+impl PendingEdits {
+    /// No edits yet; safelist additions will go to `config_path`, which may not exist yet
+    pub fn new(config_path: PathBuf) -> Self {
+        PendingEdits { config_path, safelist: Vec::new(), removals: Vec::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.safelist.is_empty() && self.removals.is_empty()
+    }
+
+    /// Queue `entry` for the safelist, or unqueue it; returns whether it is queued now
+    pub fn toggle_safelist(&mut self, entry: SafelistEntry) -> bool {
+        toggle(&mut self.safelist, entry)
+    }
+
+    /// Whether a safelist entry for `class` in the stylesheet `file_path` is queued
+    pub fn is_safelisted(&self, file_path: &str, class: &str) -> bool {
+        let entry = self.safelist_entry(file_path, class);
+        self.safelist.contains(&entry)
+    }
+
+    /// The safelist entry for one class, scoped to its stylesheet so same-named classes elsewhere stay checked
+    /// The path is relative to the config file's directory when the stylesheet is inside it.
+    pub fn safelist_entry(&self, file_path: &str, class: &str) -> SafelistEntry {
+        let directory = self.config_path.parent().unwrap_or(Path::new(""));
+        let path = Path::new(file_path)
+            .strip_prefix(directory)
+            .ok()
+            .filter(|relative| !relative.as_os_str().is_empty())
+            .map(|relative| relative.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.to_string());
+        SafelistEntry { class: class.to_string(), paths: vec![path] }
+    }
+
+    /// Queue a rule block for deletion, or unqueue it; returns whether it is queued now
+    pub fn toggle_removal(&mut self, removal: RuleRemoval) -> bool {
+        toggle(&mut self.removals, removal)
+    }
+
+    /// The content of every file the edits touch, before and after, config file first
+    pub fn file_changes(&self) -> anyhow::Result<Vec<FileChange>> {
+        let mut changes = Vec::new();
+
+        if !self.safelist.is_empty() {
+            let old = if self.config_path.exists() { read(&self.config_path)? } else { String::new() };
+            let new = add_to_safelist(&old, &self.safelist)
+                .map_err(|e| anyhow::anyhow!("Invalid config {}: {}", self.config_path.display(), e))?;
+            changes.push(FileChange { path: self.config_path.clone(), old, new });
+        }
+
+        let mut by_file: BTreeMap<&str, Vec<&RuleRemoval>> = BTreeMap::new();
+        for removal in &self.removals {
+            by_file.entry(removal.file_path.as_str()).or_default().push(removal);
+        }
+        for (file_path, removals) in by_file {
+            let old = read(Path::new(file_path))?;
+            let new = remove_rules(&old, &removals);
+            changes.push(FileChange { path: PathBuf::from(file_path), old, new });
+        }
+
+        Ok(changes)
+    }
+
+    /// A unified diff of every pending edit
+    pub fn diff(&self) -> anyhow::Result<String> {
+        Ok(self
+            .file_changes()?
+            .iter()
+            .map(|change| unified_diff(&change.path.to_string_lossy(), &change.old, &change.new))
+            .collect())
+    }
+
+    /// Write every pending edit, returning the number of files written
+    pub fn apply(&self) -> anyhow::Result<usize> {
+        let changes = self.file_changes()?;
+        for change in &changes {
+            fs::write(&change.path, &change.new)
+                .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", change.path.display(), e))?;
+        }
+        Ok(changes.len())
+    }
+}
+
+fn toggle<T: PartialEq>(items: &mut Vec<T>, item: T) -> bool {
+    match items.iter().position(|existing| *existing == item) {
+        Some(index) => {
+            items.remove(index);
+            false
+        }
+        None => {
+            items.push(item);
+            true
+        }
+    }
+}
+
+fn read(path: &Path) -> anyhow::Result<String> {
+    fs::read_to_string(path).map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))
+}
+
+/// Append `entries` to the `safelist` of a config file's JSON, skipping entries already listed and classes an
+/// entry without `paths` already lists. Entries without `paths` are written as plain names.
+/// The entries are spliced into the text, so every other byte of the file stays as it was; an empty
+/// `config_json` starts a new config.
+pub fn add_to_safelist(config_json: &str, entries: &[SafelistEntry]) -> anyhow::Result<String> {
+    let config: serde_json::Value = if config_json.trim().is_empty() {
+        serde_json::json!({})
+    } else {
+        serde_json::from_str(config_json)?
+    };
+    let Some(settings) = config.as_object() else {
+        anyhow::bail!("expected a JSON object");
+    };
+    let listed: Vec<SafelistEntry> = match settings.get("safelist") {
+        Some(entries @ serde_json::Value::Array(_)) => serde_json::from_value(entries.clone())
+            .map_err(|e| anyhow::anyhow!("Invalid `safelist`: {}", e))?,
+        Some(_) => anyhow::bail!("`safelist` is not a list"),
+        None => Vec::new(),
+    };
+
+    let mut additions: Vec<String> = Vec::new();
+    for entry in entries {
+        let is_listed = listed.iter().any(|listed| {
+            listed == entry || (listed.paths.is_empty() && listed.class.trim_start_matches('.') == entry.class.trim_start_matches('.'))
+        });
+        let entry = entry_json(entry)?;
+        if !is_listed && !additions.contains(&entry) {
+            additions.push(entry);
+        }
+    }
+    if additions.is_empty() {
+        return Ok(config_json.to_string());
+    }
+
+    let Some(members) = object_members(config_json).filter(|members| !members.is_empty()) else {
+        let entries: Vec<String> = additions.iter().map(|entry| format!("    {}", entry)).collect();
+        return Ok(format!("{{\n  \"safelist\": [\n{}\n  ]\n}}\n", entries.join(",\n")));
+    };
+    let mut updated = config_json.to_string();
+
+    if let Some(safelist) = members.iter().find(|member| member.key == "safelist") {
+        let elements = array_elements(config_json, safelist.value_start);
+        match (elements.first(), elements.last()) {
+            (Some(&(first, _)), Some(&(_, last_end))) => {
+                let separator = match line_indent(config_json, safelist.value_start, first) {
+                    Some(indent) => format!(",\n{}", indent),
+                    None => String::from(", "),
+                };
+                updated.insert_str(last_end, &additions.iter().map(|entry| format!("{}{}", separator, entry)).collect::<String>());
+            }
+            _ => updated.replace_range(safelist.value_start..safelist.value_end, &format!("[{}]", additions.join(", "))),
+        }
+    } else {
+        let first = &members[0];
+        let object_start = config_json[..first.key_start].rfind('{').unwrap_or(0);
+        let inserted = match line_indent(config_json, object_start, first.key_start) {
+            Some(indent) => {
+                let entries: Vec<String> = additions.iter().map(|entry| format!("{}{}{}", indent, indent, entry)).collect();
+                format!(",\n{}\"safelist\": [\n{}\n{}]", indent, entries.join(",\n"), indent)
+            }
+            None => format!(", \"safelist\": [{}]", additions.join(", ")),
+        };
+        updated.insert_str(members[members.len() - 1].value_end, &inserted);
+    }
+
+    Ok(updated)
+}
+
+/// One safelist entry as JSON: a plain name, or `{ "class": ..., "paths": [...] }` on one line when scoped
+fn entry_json(entry: &SafelistEntry) -> anyhow::Result<String> {
+    if entry.paths.is_empty() {
+        return Ok(serde_json::to_string(&entry.class)?);
+    }
+    let paths: Vec<String> = entry.paths.iter().map(serde_json::to_string).collect::<Result<_, _>>()?;
+    Ok(format!("{{ \"class\": {}, \"paths\": [{}] }}", serde_json::to_string(&entry.class)?, paths.join(", ")))
+}
+
+
+/// A top-level key of a JSON object and the byte range of its value
+struct Member {
+    key: String,
+    key_start: usize,
+    value_start: usize,
+    value_end: usize,
+}
+
+/// The members of the top-level object of already validated JSON
+fn object_members(json: &str) -> Option<Vec<Member>> {
+    let bytes = json.as_bytes();
+    let mut index = skip_whitespace(bytes, 0);
+    if bytes.get(index) != Some(&b'{') {
+        return None;
+    }
+    index += 1;
+
+    let mut members = Vec::new();
+    loop {
+        index = skip_whitespace(bytes, index);
+        match bytes.get(index)? {
+            b'}' => return Some(members),
+            b',' => index += 1,
+            b'"' => {
+                let key_end = string_end(bytes, index);
+                let key = serde_json::from_str(&json[index..key_end]).ok()?;
+                let value_start = skip_whitespace(bytes, skip_whitespace(bytes, key_end) + 1);
+                let value_end = value_end(json, value_start);
+                members.push(Member { key, key_start: index, value_start, value_end });
+                index = value_end;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// The byte ranges of the elements of the array starting at `open`
+fn array_elements(json: &str, open: usize) -> Vec<(usize, usize)> {
+    let bytes = json.as_bytes();
+    let mut elements = Vec::new();
+    let mut index = open + 1;
+    loop {
+        index = skip_whitespace(bytes, index);
+        match bytes.get(index) {
+            None | Some(b']') => return elements,
+            Some(b',') => index += 1,
+            Some(_) => {
+                let end = value_end(json, index);
+                elements.push((index, end));
+                index = end;
+            }
+        }
+    }
+}
+
+/// The end of the JSON value starting at `start`, without trailing whitespace
+fn value_end(json: &str, start: usize) -> usize {
+    let bytes = json.as_bytes();
+    let mut depth = 0;
+    let mut index = start;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'"' => {
+                index = string_end(bytes, index);
+                if depth == 0 {
+                    return index;
+                }
+                continue;
+            }
+            b'[' | b'{' => depth += 1,
+            b']' | b'}' if depth <= 1 => return if depth == 0 { trim_end(json, start, index) } else { index + 1 },
+            b']' | b'}' => depth -= 1,
+            b',' if depth == 0 => return trim_end(json, start, index),
+            _ => {}
+        }
+        index += 1;
+    }
+    trim_end(json, start, index)
+}
+
+fn trim_end(json: &str, start: usize, end: usize) -> usize {
+    start + json[start..end].trim_end().len()
+}
+
+/// The offset just past the string literal starting at `start`
+fn string_end(bytes: &[u8], start: usize) -> usize {
+    let mut index = start + 1;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'\\' => index += 2,
+            b'"' => return index + 1,
+            _ => index += 1,
+        }
+    }
+    bytes.len()
+}
+
+fn skip_whitespace(bytes: &[u8], mut index: usize) -> usize {
+    while bytes.get(index).is_some_and(|byte| byte.is_ascii_whitespace()) {
+        index += 1;
+    }
+    index
+}
+
+/// The indentation of the line `item` starts on, when it's on a later line than `open`
+fn line_indent(json: &str, open: usize, item: usize) -> Option<&str> {
+    let line_start = json[..item].rfind('\n')? + 1;
+    (line_start > open).then(|| &json[line_start..item])
+}
+
+/// A unified diff of `old` and `new` labelled with `path`; empty when they are equal
+pub fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    if old == new {
+        return String::new();
+    }
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let ops = diff_lines(&old_lines, &new_lines);
+
+    let mut diff = format!("--- a/{}\n+++ b/{}\n", path, path);
+    let changed: Vec<usize> = ops.iter().enumerate().filter(|(_, op)| !matches!(op, DiffOp::Same(..))).map(|(i, _)| i).collect();
+
+    // Group changes whose context overlaps into hunks
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for index in changed {
+        let start = index.saturating_sub(DIFF_CONTEXT);
+        let end = (index + DIFF_CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    for (start, end) in hunks {
+        // Line numbers where the hunk starts in each file
+        let old_start = ops[..start].iter().filter(|op| !matches!(op, DiffOp::Added(_))).count() + 1;
+        let new_start = ops[..start].iter().filter(|op| !matches!(op, DiffOp::Removed(_))).count() + 1;
+        let old_count = ops[start..end].iter().filter(|op| !matches!(op, DiffOp::Added(_))).count();
+        let new_count = ops[start..end].iter().filter(|op| !matches!(op, DiffOp::Removed(_))).count();
+        diff.push_str(&format!("@@ -{},{} +{},{} @@\n", old_start, old_count, new_start, new_count));
+        for op in &ops[start..end] {
+            let (prefix, line) = match op {
+                DiffOp::Same(line) => (' ', line),
+                DiffOp::Removed(line) => ('-', line),
+                DiffOp::Added(line) => ('+', line),
+            };
+            diff.push(prefix);
+            diff.push_str(line);
+            diff.push('\n');
+        }
+    }
+    diff
+}
+
+enum DiffOp<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Line diff from the longest common subsequence; config files and stylesheets are small enough for O(n·m)
+fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffOp<'a>> {
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push(DiffOp::Same(old[i]));
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            ops.push(DiffOp::Removed(old[i]));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Added(new[j]));
+            j += 1;
+        }
+    }
+    ops
+}
@@ -0,0 +1,64 @@
+// src/error.rs - Typed errors of the library
+// The finders and `analyze_*` entry points return `error::Result`, so callers can match on what went wrong:
+// a file that couldn't be read or parsed, strict mode, an invalid safelist pattern or a failed `git` call.
+
+use std::path::PathBuf;
+use crate::{DiagnosticKind, FileDiagnostic};
+
+/// The result of the finders and `analyze_*` entry points
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// A directory entry couldn't be listed, e.g. a directory without read permission
+    #[error("Failed to walk {}: {source}", path.display())]
+    Walk { path: PathBuf, source: walkdir::Error },
+
+    #[error("Failed to read {}: {source}", path.display())]
+    Read { path: PathBuf, source: std::io::Error },
+
+    #[error("{} is not valid UTF-8", path.display())]
+    NonUtf8 { path: PathBuf },
+
+    #[error("Failed to parse {} at line {line}: {message}", path.display())]
+    Parse { path: PathBuf, line: usize, message: String },
+
+    /// A `/regex/` safelist entry that doesn't compile
+    #[error("Invalid safelist pattern {pattern}: {source}")]
+    Safelist { pattern: String, source: regex::Error },
+
+    /// `git` couldn't be run or failed, in diff mode
+    #[error("git {args} failed: {message}")]
+    Git { args: String, message: String },
+
+    /// Strict mode found files that couldn't be analyzed
+    #[error("{} file(s) could not be analyzed (strict mode): {}", diagnostics.len(), summarize(diagnostics))]
+    Strict { diagnostics: Vec<FileDiagnostic> },
+}
+
+impl Error {
+    /// The per-file diagnostic this error is reported as when it isn't fatal
+    pub fn to_diagnostic(&self) -> Option<FileDiagnostic> {
+        let (path, kind, line_number) = match self {
+            Error::Walk { path, .. } => (path, DiagnosticKind::WalkError, None),
+            Error::Read { path, .. } => (path, DiagnosticKind::Unreadable, None),
+            Error::NonUtf8 { path } => (path, DiagnosticKind::NonUtf8, None),
+            Error::Parse { path, line, .. } => (path, DiagnosticKind::ParseError, Some(*line)),
+            Error::Safelist { .. } | Error::Git { .. } | Error::Strict { .. } => return None,
+        };
+        Some(FileDiagnostic {
+            file_path: path.to_string_lossy().to_string(),
+            kind,
+            line_number,
+            message: self.to_string(),
+        })
+    }
+}
+
+fn summarize(diagnostics: &[FileDiagnostic]) -> String {
+    let mut messages: Vec<&str> = diagnostics.iter().take(3).map(|d| d.message.as_str()).collect();
+    if diagnostics.len() > messages.len() {
+        messages.push("...");
+    }
+    messages.join("; ")
+}
@@ -0,0 +1,229 @@
+// src/files.rs - Where the source files of a tree come from
+// The analysis reads files through a `FileProvider`: `FsFiles` walks a directory on disk, `MemoryFiles` holds
+// contents a build tool already has in memory and `OverlayFiles` lays such contents over another provider.
+// Every finder walks the provider through `source_files`, so unreadable directories, unreadable files and
+// non-UTF-8 files are handled the same way everywhere: skipped by default and recorded as `FileDiagnostic`s by
+// `find_file_diagnostics`, or a `rustbrother::Error` with `strict` on.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+use crate::css_parser::{find_syntax_errors, should_process_css_file};
+use crate::error::Error;
+use crate::js_parser::{normalize_path, should_process_js_file};
+use crate::{AnalysisConfig, FileDiagnostic};
+
+/// A set of source files the analysis can list and read
+pub trait FileProvider {
+    /// Every file, in a stable order; problems listing them come back as errors
+    fn files(&self) -> Box<dyn Iterator<Item = Result<PathBuf, Error>> + '_>;
+
+    /// The UTF-8 contents of one file
+    fn read(&self, path: &Path) -> Result<String, Error>;
+
+    /// Whether `path` is a file `read` can return; imports are resolved with it
+    fn is_file(&self, path: &Path) -> bool;
+
+    /// Size of a file in bytes
+    fn size(&self, path: &Path) -> Option<u64> {
+        self.read(path).ok().map(|content| content.len() as u64)
+    }
+}
+
+/// The files under a directory on disk
+/// Imports are resolved against the whole disk, so stylesheets imported from outside the directory are still found.
+pub struct FsFiles {
+    root: PathBuf,
+}
+
+impl FsFiles {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        FsFiles { root: root.into() }
+    }
+}
+
+impl FileProvider for FsFiles {
+    fn files(&self) -> Box<dyn Iterator<Item = Result<PathBuf, Error>> + '_> {
+        Box::new(WalkDir::new(&self.root).into_iter().filter_map(move |entry| match entry {
+            Ok(entry) if entry.file_type().is_dir() => None,
+            Ok(entry) => Some(Ok(entry.into_path())),
+            Err(source) => Some(Err(Error::Walk { path: source.path().unwrap_or(&self.root).to_path_buf(), source })),
+        }))
+    }
+
+    fn read(&self, path: &Path) -> Result<String, Error> {
+        let bytes = fs::read(path).map_err(|source| Error::Read { path: path.to_path_buf(), source })?;
+        String::from_utf8(bytes).map_err(|_| Error::NonUtf8 { path: path.to_path_buf() })
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn size(&self, path: &Path) -> Option<u64> {
+        fs::metadata(path).ok().map(|metadata| metadata.len())
+    }
+}
+
+/// Files held in memory, by path
+/// Paths are normalized (`./src/../src/App.tsx` is `src/App.tsx`) and findings report them as given.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryFiles {
+    files: BTreeMap<PathBuf, String>,
+}
+
+impl MemoryFiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file, replacing any file at the same path
+    pub fn insert(&mut self, path: impl AsRef<Path>, content: impl Into<String>) {
+        self.files.insert(normalize_path(path.as_ref()), content.into());
+    }
+}
+
+impl<P: AsRef<Path>, S: Into<String>> FromIterator<(P, S)> for MemoryFiles {
+    fn from_iter<I: IntoIterator<Item = (P, S)>>(iter: I) -> Self {
+        let mut files = MemoryFiles::new();
+        for (path, content) in iter {
+            files.insert(path, content);
+        }
+        files
+    }
+}
+
+impl FileProvider for MemoryFiles {
+    fn files(&self) -> Box<dyn Iterator<Item = Result<PathBuf, Error>> + '_> {
+        Box::new(self.files.keys().cloned().map(Ok))
+    }
+
+    fn read(&self, path: &Path) -> Result<String, Error> {
+        self.files.get(&normalize_path(path)).cloned().ok_or_else(|| Error::Read {
+            path: path.to_path_buf(),
+            source: std::io::Error::new(std::io::ErrorKind::NotFound, "not among the provided files"),
+        })
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.files.contains_key(&normalize_path(path))
+    }
+}
+
+/// The files of `base`, with the contents of those `overlay` also holds taken from `overlay`
+/// The language server analyzes the workspace on disk this way, seeing the editor's unsaved buffers.
+pub struct OverlayFiles<'a> {
+    base: &'a dyn FileProvider,
+    overlay: &'a MemoryFiles,
+}
+
+impl<'a> OverlayFiles<'a> {
+    pub fn new(base: &'a dyn FileProvider, overlay: &'a MemoryFiles) -> Self {
+        OverlayFiles { base, overlay }
+    }
+}
+
+impl FileProvider for OverlayFiles<'_> {
+    fn files(&self) -> Box<dyn Iterator<Item = Result<PathBuf, Error>> + '_> {
+        self.base.files()
+    }
+
+    fn read(&self, path: &Path) -> Result<String, Error> {
+        if self.overlay.is_file(path) {
+            self.overlay.read(path)
+        } else {
+            self.base.read(path)
+        }
+    }
+
+    fn is_file(&self, path: &Path) -> bool {
+        self.overlay.is_file(path) || self.base.is_file(path)
+    }
+
+    fn size(&self, path: &Path) -> Option<u64> {
+        if self.overlay.is_file(path) {
+            self.overlay.size(path)
+        } else {
+            self.base.size(path)
+        }
+    }
+}
+
+/// A file read from the tree
+pub struct SourceFile {
+    pub path: PathBuf,
+    /// `path` as a string, the form findings use
+    pub file_path: String,
+    pub content: String,
+}
+
+/// Every file of `files` that `filter` accepts, with the problems met along the way as errors
+pub fn walk_source_files<'a>(
+    files: &'a dyn FileProvider,
+    config: &'a AnalysisConfig,
+    filter: impl Fn(&Path, &AnalysisConfig) -> bool + 'a,
+) -> impl Iterator<Item = Result<SourceFile, Error>> + 'a {
+    files.files().filter_map(move |entry| match entry {
+        Ok(path) if filter(&path, config) => Some(files.read(&path).map(|content| SourceFile {
+            file_path: path.to_string_lossy().to_string(),
+            path,
+            content,
+        })),
+        Ok(_) => None,
+        Err(Error::Walk { path, .. }) if is_ignored(&path, config) => None,
+        Err(error) => Some(Err(error)),
+    })
+}
+
+/// `walk_source_files` for the finders: problems are skipped, or fail the walk when `config.strict` is on
+pub(crate) fn source_files<'a>(
+    files: &'a dyn FileProvider,
+    config: &'a AnalysisConfig,
+    filter: impl Fn(&Path, &AnalysisConfig) -> bool + 'a,
+) -> impl Iterator<Item = Result<SourceFile, Error>> + 'a {
+    walk_source_files(files, config, filter).filter_map(move |file| match file {
+        Ok(file) => Some(Ok(file)),
+        Err(error) if config.strict => Some(Err(error)),
+        Err(_) => None,
+    })
+}
+
+/// Read one file from disk as UTF-8
+pub fn read_source_file(path: &Path) -> Result<SourceFile, Error> {
+    let content = FsFiles::new(path).read(path)?;
+    Ok(SourceFile { path: path.to_path_buf(), file_path: path.to_string_lossy().to_string(), content })
+}
+
+/// Every script and stylesheet under `path` the analysis couldn't fully use: unreadable directories and
+/// files, non-UTF-8 files and stylesheets with unbalanced braces
+pub fn find_file_diagnostics(path: &Path, config: &AnalysisConfig) -> Vec<FileDiagnostic> {
+    find_file_diagnostics_in(&FsFiles::new(path), config)
+}
+
+/// `find_file_diagnostics` over any set of files
+pub fn find_file_diagnostics_in(files: &dyn FileProvider, config: &AnalysisConfig) -> Vec<FileDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for file in walk_source_files(files, config, |file, config| should_process_js_file(file, config) || should_process_css_file(file, config)) {
+        match file {
+            Ok(file) if should_process_css_file(&file.path, config) => {
+                diagnostics.extend(
+                    find_syntax_errors(&file.content, &file.file_path)
+                        .into_iter()
+                        .filter_map(|(line, message)| Error::Parse { path: file.path.clone(), line, message }.to_diagnostic()),
+                );
+            }
+            Ok(_) => {}
+            Err(error) => diagnostics.extend(error.to_diagnostic()),
+        }
+    }
+
+    diagnostics.sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
+    diagnostics
+}
+
+fn is_ignored(path: &Path, config: &AnalysisConfig) -> bool {
+    let path_str = path.to_string_lossy();
+    config.ignore_patterns.iter().any(|pattern| path_str.contains(pattern))
+}
@@ -0,0 +1,99 @@
+// src/fixer.rs - Removing unused rules from stylesheets
+// Used by the `fix` command to purge rule blocks whose classes are all unused
+
+use std::collections::HashMap;
+use std::fs;
+use serde::{Deserialize, Serialize};
+use crate::css_parser::rule_block_range;
+use crate::{AnalysisResult, CssClass};
+
+/// A rule block that can be deleted because none of its classes are used
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuleRemoval {
+    pub file_path: String,
+    pub class_name: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Plan the removal of every unused class's rule block
+/// Blocks that still contain a used class (e.g. a used nested `&_variant`) are kept,
+/// and nested unused blocks are folded into their unused parent
+pub fn plan_unused_rule_removals(result: &AnalysisResult) -> anyhow::Result<Vec<RuleRemoval>> {
+    let mut contents: HashMap<&str, String> = HashMap::new();
+    let mut removals: Vec<RuleRemoval> = Vec::new();
+    
+    for class in &result.unused_classes {
+        if !contents.contains_key(class.file_path.as_str()) {
+            contents.insert(&class.file_path, fs::read_to_string(&class.file_path)?);
+        }
+        removals.extend(plan_rule_removal(result, class, &contents[class.file_path.as_str()]));
+    }
+    
+    // Drop blocks already covered by a larger removal in the same file
+    let snapshot = removals.clone();
+    removals.retain(|removal| {
+        !snapshot.iter().any(|other| {
+            other.file_path == removal.file_path
+                && other.start_line <= removal.start_line
+                && other.end_line >= removal.end_line
+                && (other.start_line, other.end_line) != (removal.start_line, removal.end_line)
+        })
+    });
+    removals.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+    removals.dedup_by(|a, b| a.file_path == b.file_path && a.start_line == b.start_line);
+    
+    Ok(removals)
+}
+
+/// Plan the removal of one class's rule block, given its stylesheet's `content`
+/// None when the block can't be found or still contains a used class
+pub fn plan_rule_removal(result: &AnalysisResult, class: &CssClass, content: &str) -> Option<RuleRemoval> {
+    let (start_line, end_line) = rule_block_range(content, class.line_number)?;
+    
+    let contains_used_class = result.used_classes.iter().any(|used| {
+        used.file_path == class.file_path && used.line_number >= start_line && used.line_number <= end_line
+    });
+    if contains_used_class {
+        return None;
+    }
+    
+    Some(RuleRemoval {
+        file_path: class.file_path.clone(),
+        class_name: class.name.clone(),
+        start_line,
+        end_line,
+    })
+}
+
+/// Delete the given line ranges (1-based, inclusive) from a stylesheet's content
+pub fn remove_rules(content: &str, removals: &[&RuleRemoval]) -> String {
+    let mut result = String::with_capacity(content.len());
+    
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        let removed = removals
+            .iter()
+            .any(|removal| line_number >= removal.start_line && line_number <= removal.end_line);
+        if !removed {
+            result.push_str(line);
+        }
+    }
+    
+    result
+}
+
+/// Write the removals back to disk, returning the number of files changed
+pub fn apply_rule_removals(removals: &[RuleRemoval]) -> anyhow::Result<usize> {
+    let mut by_file: HashMap<&str, Vec<&RuleRemoval>> = HashMap::new();
+    for removal in removals {
+        by_file.entry(removal.file_path.as_str()).or_default().push(removal);
+    }
+    
+    for (file_path, file_removals) in &by_file {
+        let content = fs::read_to_string(file_path)?;
+        fs::write(file_path, remove_rules(&content, file_removals))?;
+    }
+    
+    Ok(by_file.len())
+}
@@ -0,0 +1,305 @@
+// src/import_graph.rs - Module graph over JS/TS files and stylesheets
+// Follows relative `import`/`export ... from`/`require()`/`import()` edges between scripts, stylesheet imports
+// from scripts and `@import`/`@use`/`@forward` between stylesheets, starting at the configured entry points.
+// Components (JSX files and scripts with styles) nothing reachable imports are dead; stylesheets nothing reachable
+// loads are unreachable.
+
+use regex::Regex;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use crate::complexity_analyzer::mask_literals;
+use crate::css_parser::should_process_css_file;
+use crate::error;
+use crate::files::{source_files, FileProvider, FsFiles, SourceFile};
+use crate::js_parser::{normalize_path, resolve_style_import_in, should_process_js_file};
+use crate::{AnalysisConfig, AnalysisResult, CssClass, DeadComponent, UsageKind};
+
+/// Extensions tried, in order, for imports written without one
+const SCRIPT_EXTENSIONS: &[&str] = &["tsx", "ts", "jsx", "js"];
+const STYLESHEET_EXTENSIONS: &[&str] = &["scss", "sass", "css"];
+
+/// `import ... from`/`export ... from`, side-effect `import '...'`, and `require()`/`import()` calls
+static MODULE_IMPORT_REGEXES: LazyLock<[Regex; 3]> = LazyLock::new(|| {
+    [
+        Regex::new(r#"\b(?:import|export)\s[^'";]*?\bfrom\s*['"]([^'"]+)['"]"#).unwrap(),
+        Regex::new(r#"\bimport\s*['"]([^'"]+)['"]"#).unwrap(),
+        Regex::new(r#"\b(?:import|require)\s*\(\s*['"]([^'"]+)['"]\s*\)"#).unwrap(),
+    ]
+});
+static STYLESHEET_RULE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"@(?:import|use|forward)\s+([^;{]+)").unwrap());
+static STYLESHEET_SOURCE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"url\(\s*['"]?([^'")]+)['"]?\s*\)|['"]([^'"]+)['"]"#).unwrap());
+
+/// Every JS/TS file and stylesheet under the analyzed directory, with the files each one imports
+pub struct ModuleGraph {
+    imports: BTreeMap<PathBuf, Vec<PathBuf>>,
+    /// Stylesheet sources scripts import that don't resolve to a file: path aliases, `baseUrl` paths and packages
+    unresolved_stylesheet_imports: Vec<String>,
+}
+
+impl ModuleGraph {
+    /// Read and resolve the imports of every script and stylesheet under `path`
+    pub fn build(path: &Path, config: &AnalysisConfig) -> error::Result<Self> {
+        Self::build_in(&FsFiles::new(path), config)
+    }
+
+    /// Same as `build`, reading from `files`
+    pub fn build_in(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<Self> {
+        let mut imports = BTreeMap::new();
+        let mut unresolved_stylesheet_imports = Vec::new();
+
+        for source in source_files(files, config, |file, config| should_process_js_file(file, config) || should_process_css_file(file, config)) {
+            let SourceFile { path: file, content, .. } = source?;
+            let file = file.as_path();
+            let resolved: Vec<PathBuf> = if should_process_js_file(file, config) {
+                find_module_imports(&content)
+                    .iter()
+                    .filter_map(|source| {
+                        if is_stylesheet(Path::new(source)) {
+                            let resolved = resolve_style_import_in(files, file, source).map(|resolved| normalize_path(&resolved));
+                            if resolved.is_none() {
+                                unresolved_stylesheet_imports.push(source.clone());
+                            }
+                            resolved
+                        } else {
+                            resolve_module_import_in(files, file, source)
+                        }
+                    })
+                    .collect()
+            } else {
+                find_stylesheet_imports(&content)
+                    .iter()
+                    .filter_map(|source| resolve_stylesheet_import_in(files, file, source))
+                    .collect()
+            };
+            imports.insert(normalize_path(file), resolved);
+        }
+
+        Ok(ModuleGraph { imports, unresolved_stylesheet_imports })
+    }
+
+    /// Every script and stylesheet in the graph, in path order
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.imports.keys()
+    }
+
+    /// The resolved imports of one file
+    pub fn imports(&self, file: &Path) -> &[PathBuf] {
+        self.imports.get(file).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Whether a stylesheet import the graph couldn't resolve may point at `stylesheet`
+    /// Aliases and `baseUrl` paths aren't followed, so an unresolved import naming the same file counts.
+    pub fn may_be_imported_unresolved(&self, stylesheet: &Path) -> bool {
+        let Some(name) = stylesheet.file_name() else { return false };
+        self.unresolved_stylesheet_imports
+            .iter()
+            .any(|source| Path::new(source).file_name() == Some(name))
+    }
+
+    /// The files that import `file`
+    pub fn importers<'a>(&'a self, file: &'a Path) -> impl Iterator<Item = &'a PathBuf> + 'a {
+        self.imports
+            .iter()
+            .filter(move |(_, imports)| imports.iter().any(|import| import == file))
+            .map(|(importer, _)| importer)
+    }
+
+    /// The files whose path contains one of `entry_points`, and everything they import transitively
+    pub fn reachable_from(&self, entry_points: &[String]) -> HashSet<&PathBuf> {
+        let mut reachable = HashSet::new();
+        let mut pending: Vec<&PathBuf> = self
+            .files()
+            .filter(|file| {
+                let file = file.to_string_lossy();
+                entry_points.iter().any(|pattern| file.contains(pattern.as_str()))
+            })
+            .collect();
+
+        while let Some(file) = pending.pop() {
+            if reachable.insert(file) {
+                pending.extend(self.imports(file).iter().filter_map(|import| self.imports.get_key_value(import).map(|(key, _)| key)));
+            }
+        }
+        reachable
+    }
+}
+
+/// The module sources a JS/TS file imports, re-exports, requires or lazily imports
+/// Imports inside comments and string literals don't count.
+pub fn find_module_imports(content: &str) -> Vec<String> {
+    // Comments and strings are blanked out of `code` at the same offsets, so a statement is real when its keyword survives
+    let code = mask_literals(content);
+
+    let mut sources: Vec<(usize, String)> = Vec::new();
+    for regex in MODULE_IMPORT_REGEXES.iter() {
+        for capture in regex.captures_iter(content) {
+            let statement = capture.get(0).unwrap();
+            if code.as_bytes()[statement.start()] != content.as_bytes()[statement.start()] {
+                continue;
+            }
+            let source = capture.get(1).unwrap();
+            sources.push((source.start(), source.as_str().to_string()));
+        }
+    }
+
+    sources.sort();
+    sources.into_iter().map(|(_, source)| source).collect()
+}
+
+/// The stylesheets a stylesheet loads through `@import`, `@use` and `@forward`
+/// Remote URLs and Sass built-in modules (`sass:math`) are left out
+pub fn find_stylesheet_imports(content: &str) -> Vec<String> {
+    STYLESHEET_RULE_REGEX
+        .captures_iter(content)
+        .flat_map(|rule| {
+            STYLESHEET_SOURCE_REGEX
+                .captures_iter(&rule[1])
+                .filter_map(|source| source.get(1).or_else(|| source.get(2)).map(|s| s.as_str().to_string()))
+                .collect::<Vec<_>>()
+        })
+        .filter(|source| !source.contains("://") && !source.starts_with("//") && !source.starts_with("sass:"))
+        .collect()
+}
+
+/// Resolve a relative module import to a JS/TS file, trying extensions and `index` files
+/// Package imports (`react`) and path aliases (`@/components`) aren't resolved
+pub fn resolve_module_import(js_file: &Path, source: &str) -> Option<PathBuf> {
+    resolve_module_import_in(&FsFiles::new(""), js_file, source)
+}
+
+/// Same as `resolve_module_import`, looking the module up in `files`
+pub fn resolve_module_import_in(files: &dyn FileProvider, js_file: &Path, source: &str) -> Option<PathBuf> {
+    if !source.starts_with('.') {
+        return None;
+    }
+
+    let base = normalize_path(&js_file.parent().unwrap_or(Path::new("")).join(source));
+    let is_script = |path: &Path| files.is_file(path) && path.extension().is_some_and(|ext| SCRIPT_EXTENSIONS.iter().any(|e| ext == *e));
+
+    if is_script(&base) {
+        return Some(base);
+    }
+    SCRIPT_EXTENSIONS
+        .iter()
+        .map(|ext| PathBuf::from(format!("{}.{}", base.display(), ext)))
+        .chain(SCRIPT_EXTENSIONS.iter().map(|ext| base.join(format!("index.{}", ext))))
+        .find(|candidate| is_script(candidate))
+}
+
+/// Resolve a stylesheet `@import`/`@use` source the way Sass does: relative to the importing file,
+/// with or without an extension, as a `_partial` or as a directory's `_index`
+pub fn resolve_stylesheet_import(stylesheet: &Path, source: &str) -> Option<PathBuf> {
+    resolve_stylesheet_import_in(&FsFiles::new(""), stylesheet, source)
+}
+
+/// Same as `resolve_stylesheet_import`, looking the stylesheet up in `files`
+pub fn resolve_stylesheet_import_in(files: &dyn FileProvider, stylesheet: &Path, source: &str) -> Option<PathBuf> {
+    let base = normalize_path(&stylesheet.parent().unwrap_or(Path::new("")).join(source));
+    if files.is_file(&base) && is_stylesheet(&base) {
+        return Some(base);
+    }
+
+    let name = base.file_name()?.to_string_lossy().to_string();
+    let directory = base.parent().unwrap_or(Path::new(""));
+    STYLESHEET_EXTENSIONS
+        .iter()
+        .flat_map(|ext| {
+            [
+                directory.join(format!("{}.{}", name, ext)),
+                directory.join(format!("_{}.{}", name, ext)),
+                base.join(format!("_index.{}", ext)),
+                base.join(format!("index.{}", ext)),
+            ]
+        })
+        .find(|candidate| files.is_file(candidate))
+}
+
+pub(crate) fn is_stylesheet(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| STYLESHEET_EXTENSIONS.iter().any(|e| ext == *e))
+}
+
+/// Find components no entry point reaches through imports, with the stylesheets each one imports
+/// Components are `.jsx`/`.tsx` files and scripts importing a stylesheet; unreachable utilities, tests and
+/// stories aren't listed. Entry points are the files whose path contains one of `config.entry_points`;
+/// without any entry points configured nothing is considered dead.
+pub fn find_dead_components(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<DeadComponent>> {
+    if config.entry_points.is_empty() {
+        return Ok(Vec::new());
+    }
+    let graph = ModuleGraph::build(path, config)?;
+    Ok(dead_components(&graph, &dead_scripts(&graph, config)))
+}
+
+/// Find stylesheets that no entry point loads, directly or through scripts and other stylesheets
+/// Empty when no entry points are configured.
+pub fn find_unreachable_stylesheets(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<String>> {
+    if config.entry_points.is_empty() {
+        return Ok(Vec::new());
+    }
+    Ok(unreachable_stylesheets(&ModuleGraph::build(path, config)?, config))
+}
+
+/// Scripts no entry point reaches: components, utilities and tests alike
+fn dead_scripts<'a>(graph: &'a ModuleGraph, config: &AnalysisConfig) -> Vec<&'a PathBuf> {
+    let reachable = graph.reachable_from(&config.entry_points);
+    graph.files().filter(|file| !reachable.contains(file) && !is_stylesheet(file)).collect()
+}
+
+/// Whether a script is a component: a JSX file or a script importing a stylesheet, but not a test or a story
+fn is_component(graph: &ModuleGraph, file: &Path) -> bool {
+    let name = file.file_name().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let is_test_or_story = [".test.", ".spec.", ".stories."].iter().any(|suffix| name.contains(suffix))
+        || file.components().any(|component| component.as_os_str() == "__tests__");
+    let is_jsx = file.extension().is_some_and(|ext| ext == "jsx" || ext == "tsx");
+    !is_test_or_story && (is_jsx || graph.imports(file).iter().any(|import| is_stylesheet(import)))
+}
+
+fn dead_components(graph: &ModuleGraph, dead_scripts: &[&PathBuf]) -> Vec<DeadComponent> {
+    dead_scripts
+        .iter()
+        .filter(|file| is_component(graph, file))
+        .map(|file| DeadComponent {
+            file_path: file.to_string_lossy().to_string(),
+            stylesheets: graph
+                .imports(file)
+                .iter()
+                .filter(|import| is_stylesheet(import))
+                .map(|import| import.to_string_lossy().to_string())
+                .collect(),
+        })
+        .collect()
+}
+
+fn unreachable_stylesheets(graph: &ModuleGraph, config: &AnalysisConfig) -> Vec<String> {
+    let reachable = graph.reachable_from(&config.entry_points);
+    graph
+        .files()
+        .filter(|file| !reachable.contains(file) && is_stylesheet(file))
+        .map(|file| file.to_string_lossy().to_string())
+        .collect()
+}
+
+/// Follow the module graph from the entry points: record dead components and unreachable stylesheets,
+/// and move used classes whose every reference sits in an unreachable script into `dead_code_classes`.
+/// Safelisted classes stay used.
+pub(crate) fn apply_reachability(result: &mut AnalysisResult, files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<()> {
+    let graph = ModuleGraph::build_in(files, config)?;
+    let dead_scripts = dead_scripts(&graph, config);
+
+    // Usage from any unreachable script is dead code, not only from the components reported
+    let dead_files: HashSet<&PathBuf> = dead_scripts.iter().copied().collect();
+    let only_dead_code = |class: &CssClass| {
+        class.evidence.iter().all(|e| e.kind != UsageKind::Safelisted && dead_files.contains(&normalize_path(Path::new(&e.file_path))))
+    };
+
+    let (dead_code_classes, used_classes) = std::mem::take(&mut result.used_classes)
+        .into_iter()
+        .partition(only_dead_code);
+    result.used_classes = used_classes;
+    result.dead_code_classes = dead_code_classes;
+    result.dead_components = dead_components(&graph, &dead_scripts);
+    result.unreachable_stylesheets = unreachable_stylesheets(&graph, config);
+    Ok(())
+}
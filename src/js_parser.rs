@@ -2,25 +2,45 @@
 // Enhanced to detect dynamic class name patterns
 
 use regex::Regex;
-use walkdir::WalkDir;
-use std::fs;
-use std::path::Path;
-use std::collections::HashSet;
-use crate::AnalysisConfig;
+use std::path::{Component, Path, PathBuf};
+use std::collections::{HashMap, HashSet};
+use crate::error;
+use crate::files::{source_files, FileProvider, FsFiles};
+use crate::{AnalysisConfig, ClassUsage, StyleImport, UsageEvidence, UsageKind};
+
+/// A class name matched by one of the extraction patterns, with the byte offset of the match
+struct PatternMatch {
+    class_name: String,
+    kind: UsageKind,
+    offset: usize,
+    pattern: String,
+}
+
+fn pattern_match(class_name: &str, kind: UsageKind, source: regex::Match) -> PatternMatch {
+    PatternMatch {
+        class_name: class_name.to_string(),
+        kind,
+        offset: source.start(),
+        pattern: source.as_str().trim().to_string(),
+    }
+}
+
+fn class_names(matches: Vec<PatternMatch>) -> impl Iterator<Item = String> {
+    matches.into_iter().map(|m| m.class_name)
+}
 
 /// Find all CSS class references in JavaScript/TypeScript/React files
-pub fn find_js_css_references(path: &Path, config: &AnalysisConfig) -> anyhow::Result<Vec<String>> {
+pub fn find_js_css_references(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<String>> {
+    find_js_css_references_in(&FsFiles::new(path), config)
+}
+
+/// Same as `find_js_css_references`, reading from `files`
+pub fn find_js_css_references_in(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<Vec<String>> {
     let mut references = HashSet::new();
     
-    for entry in WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| should_process_js_file(e.path(), config))
-    {
-        if let Ok(content) = fs::read_to_string(entry.path()) {
-            let file_references = extract_css_references(&content, config);
-            references.extend(file_references);
-        }
+    for file in source_files(files, config, should_process_js_file) {
+        let file_references = extract_css_references(&file?.content, config);
+        references.extend(file_references);
     }
     
     // Convert to sorted Vec and remove duplicates
@@ -31,24 +51,55 @@ pub fn find_js_css_references(path: &Path, config: &AnalysisConfig) -> anyhow::R
 
 /// NEW: Find all CSS class references with known CSS classes for context
 /// This version uses actual CSS class definitions to generate smarter dynamic variants
-pub fn find_js_css_references_with_context(path: &Path, config: &AnalysisConfig, known_css_classes: &[String]) -> anyhow::Result<Vec<String>> {
-    let mut references = HashSet::new();
+pub fn find_js_css_references_with_context(path: &Path, config: &AnalysisConfig, known_css_classes: &[String]) -> error::Result<Vec<String>> {
+    find_js_css_references_with_context_in(&FsFiles::new(path), config, known_css_classes)
+}
+
+/// Same as `find_js_css_references_with_context`, reading from `files`
+pub fn find_js_css_references_with_context_in(files: &dyn FileProvider, config: &AnalysisConfig, known_css_classes: &[String]) -> error::Result<Vec<String>> {
+    let evidence = find_js_css_usage_evidence_in(files, config, known_css_classes)?;
     
-    for entry in WalkDir::new(path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| should_process_js_file(e.path(), config))
-    {
-        if let Ok(content) = fs::read_to_string(entry.path()) {
-            let file_references = extract_css_references_with_css_context(&content, config, known_css_classes);
-            references.extend(file_references);
+    // Convert to sorted Vec
+    let mut sorted_refs: Vec<String> = evidence.into_keys().collect();
+    sorted_refs.sort();
+    Ok(sorted_refs)
+}
+
+/// Find every reference to a known CSS class, keeping the evidence for each match:
+/// which pattern matched and where, so guesses can be told apart from real references
+pub fn find_js_css_usage_evidence(path: &Path, config: &AnalysisConfig, known_css_classes: &[String]) -> error::Result<HashMap<String, Vec<UsageEvidence>>> {
+    find_js_css_usage_evidence_in(&FsFiles::new(path), config, known_css_classes)
+}
+
+/// Same as `find_js_css_usage_evidence`, reading from `files`
+pub fn find_js_css_usage_evidence_in(files: &dyn FileProvider, config: &AnalysisConfig, known_css_classes: &[String]) -> error::Result<HashMap<String, Vec<UsageEvidence>>> {
+    let mut evidence: HashMap<String, Vec<UsageEvidence>> = HashMap::new();
+    
+    for file in source_files(files, config, should_process_js_file) {
+        let file = file?;
+        for (class_name, file_evidence) in extract_css_usage_evidence(&file.content, &file.file_path, config, known_css_classes) {
+            evidence.entry(class_name).or_default().extend(file_evidence);
         }
     }
     
-    // Convert to sorted Vec and remove duplicates
-    let mut sorted_refs: Vec<String> = references.into_iter().collect();
-    sorted_refs.sort();
-    Ok(sorted_refs)
+    Ok(evidence)
+}
+
+/// Find located class references in every JS/TS file under `path`
+pub fn find_class_usages_in_directory(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<ClassUsage>> {
+    find_class_usages_in_directory_in(&FsFiles::new(path), config)
+}
+
+/// Same as `find_class_usages_in_directory`, reading from `files`
+pub fn find_class_usages_in_directory_in(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<Vec<ClassUsage>> {
+    let mut usages = Vec::new();
+    
+    for file in source_files(files, config, should_process_js_file) {
+        let file = file?;
+        usages.extend(find_class_usages(&file.content, &file.file_path));
+    }
+    
+    Ok(usages)
 }
 
 /// Extract CSS class references from JavaScript content
@@ -56,22 +107,22 @@ pub fn extract_css_references(content: &str, config: &AnalysisConfig) -> Vec<Str
     let mut references = HashSet::new(); // Use HashSet to automatically handle duplicates
     
     // Pattern 1: className="class1 class2"
-    references.extend(extract_simple_classnames(content));
+    references.extend(class_names(extract_simple_classnames(content)));
     
     // Pattern 2: className={'class1 class2'}
-    references.extend(extract_object_classnames(content));
+    references.extend(class_names(extract_object_classnames(content)));
     
     // Pattern 3: CSS modules (if enabled)
     if config.include_css_modules {
-        references.extend(extract_css_modules_references(content));
+        references.extend(class_names(extract_css_modules_references(content)));
         
         // Pattern 4: Template literal patterns
-        references.extend(extract_template_literal_classes(content));
+        references.extend(class_names(extract_template_literal_classes(content)));
     }
     
     // Pattern 5: styled-components (if enabled)
     if config.include_styled_components {
-        references.extend(extract_styled_components_references(content));
+        references.extend(class_names(extract_styled_components_references(content)));
     }
     
     // Convert to Vec and sort
@@ -83,84 +134,210 @@ pub fn extract_css_references(content: &str, config: &AnalysisConfig) -> Vec<Str
 /// Extract CSS class references from JavaScript content with known CSS classes
 /// This version only returns classes that exist in the known_css_classes list
 pub fn extract_css_references_with_css_context(content: &str, config: &AnalysisConfig, known_css_classes: &[String]) -> Vec<String> {
+    let evidence = extract_css_usage_evidence(content, "", config, known_css_classes);
+    
+    // Convert to Vec and sort
+    let mut result: Vec<String> = evidence.into_keys().collect();
+    result.sort();
+    result
+}
+
+/// Extract references to known CSS classes from JavaScript content, with evidence per match
+pub fn extract_css_usage_evidence(content: &str, file_path: &str, config: &AnalysisConfig, known_css_classes: &[String]) -> HashMap<String, Vec<UsageEvidence>> {
     // Convert to HashSet for faster lookups
-    let known_classes_set: HashSet<String> = known_css_classes.iter().cloned().collect();
-    let mut references = HashSet::new();
+    let known_classes_set: HashSet<&str> = known_css_classes.iter().map(|c| c.as_str()).collect();
+    let mut matches = Vec::new();
     
     // Pattern 1: className="class1 class2"
-    let simple_classes = extract_simple_classnames(content);
-    for class in simple_classes {
-        if known_classes_set.contains(&class) {
-            references.insert(class);
-        }
-    }
+    matches.extend(extract_simple_classnames(content));
     
     // Pattern 2: className={'class1 class2'}
-    let object_classes = extract_object_classnames(content);
-    for class in object_classes {
-        if known_classes_set.contains(&class) {
-            references.insert(class);
-        }
-    }
+    matches.extend(extract_object_classnames(content));
     
     // Pattern 3: CSS modules (if enabled)
     if config.include_css_modules {
-        let css_module_classes = extract_css_modules_references(content);
-        for class in css_module_classes {
-            if known_classes_set.contains(&class) {
-                references.insert(class);
-            }
-        }
+        matches.extend(extract_css_modules_references(content));
         
         // Pattern 4: Dynamic CSS modules with CSS context
-        let dynamic_classes = extract_dynamic_css_modules_with_context(content, known_css_classes);
-        for class in dynamic_classes {
-            if known_classes_set.contains(&class) {
-                references.insert(class);
-            }
-        }
+        matches.extend(extract_dynamic_css_modules_with_context(content, known_css_classes));
         
         // Pattern 5: Variable assignment with CSS context
-        let variable_classes = extract_variable_assignment_patterns_with_context(content, known_css_classes);
-        for class in variable_classes {
-            if known_classes_set.contains(&class) {
-                references.insert(class);
+        matches.extend(extract_variable_assignment_patterns_with_context(content, known_css_classes));
+        
+        // Pattern 6: Template literal patterns
+        matches.extend(extract_template_literal_classes(content));
+    }
+    
+    // Pattern 7: styled-components (if enabled)
+    if config.include_styled_components {
+        matches.extend(extract_styled_components_references(content));
+    }
+    
+    // Only keep classes that exist, with one piece of evidence per rule and line
+    let mut evidence: HashMap<String, Vec<UsageEvidence>> = HashMap::new();
+    for found in matches {
+        if !known_classes_set.contains(found.class_name.as_str()) {
+            continue;
+        }
+        
+        let line_number = line_and_column(content, found.offset).0;
+        let class_evidence = evidence.entry(found.class_name).or_default();
+        if !class_evidence.iter().any(|e| e.kind == found.kind && e.line_number == line_number) {
+            class_evidence.push(UsageEvidence {
+                kind: found.kind,
+                file_path: file_path.to_string(),
+                line_number,
+                pattern: found.pattern,
+            });
+        }
+    }
+    
+    evidence
+}
+
+/// Find stylesheet imports: `import styles from './Button.module.scss'` and `import * as styles from ...`
+pub fn find_style_imports(content: &str) -> Vec<StyleImport> {
+    let import_regex = Regex::new(
+        r#"import\s+(?:\*\s+as\s+)?([a-zA-Z_$][a-zA-Z0-9_$]*)\s+from\s+['"]([^'"]+\.(?:css|scss|sass))['"]"#
+    ).unwrap();
+    
+    import_regex
+        .captures_iter(content)
+        .map(|capture| {
+            let whole = capture.get(0).unwrap();
+            StyleImport {
+                binding: capture[1].to_string(),
+                source: capture[2].to_string(),
+                line_number: line_and_column(content, whole.start()).0,
+            }
+        })
+        .collect()
+}
+
+/// Find located class references in a JS/TS file
+/// Covers `styles.foo`, `styles['foo']`, destructuring from the styles object and `className="foo"` strings
+pub fn find_class_usages(content: &str, file_path: &str) -> Vec<ClassUsage> {
+    let mut usages = Vec::new();
+    
+    // Fall back to the conventional `styles` name when no stylesheet import is found
+    let mut bindings: Vec<String> = find_style_imports(content).into_iter().map(|i| i.binding).collect();
+    if bindings.is_empty() {
+        bindings.push("styles".to_string());
+    }
+    bindings.dedup();
+    
+    for binding in &bindings {
+        let escaped = regex::escape(binding);
+        let patterns = [
+            format!(r"\b{}\.([a-zA-Z][a-zA-Z0-9_-]*)", escaped),
+            format!(r#"\b{}\[\s*['"]([a-zA-Z][a-zA-Z0-9_-]*)['"]\s*\]"#, escaped),
+        ];
+        
+        for pattern in &patterns {
+            let regex = Regex::new(pattern).unwrap();
+            for capture in regex.captures_iter(content) {
+                let name = capture.get(1).unwrap();
+                usages.push(located_usage(content, file_path, name.as_str(), Some(binding), name.start()));
             }
         }
         
-        // Pattern 6: Template literal patterns
-        let template_classes = extract_template_literal_classes(content);
-        for class in template_classes {
-            if known_classes_set.contains(&class) {
-                references.insert(class);
+        // Destructuring: const { card, cardHeader: header } = styles
+        let destructure_regex = Regex::new(&format!(r"(?:const|let|var)\s*\{{([^}}]+)\}}\s*=\s*{}\b", escaped)).unwrap();
+        for capture in destructure_regex.captures_iter(content) {
+            let destructured = capture.get(1).unwrap();
+            let mut offset = destructured.start();
+            for part in destructured.as_str().split(',') {
+                let key = part.split(':').next().unwrap_or("").trim();
+                if is_valid_class_name(key) {
+                    let key_offset = offset + part.find(key).unwrap_or(0);
+                    usages.push(located_usage(content, file_path, key, Some(binding), key_offset));
+                }
+                offset += part.len() + 1;
             }
         }
     }
     
-    // Pattern 7: styled-components (if enabled)
-    if config.include_styled_components {
-        let styled_classes = extract_styled_components_references(content);
-        for class in styled_classes {
-            if known_classes_set.contains(&class) {
-                references.insert(class);
+    // Plain class strings: className="foo bar" and className={'foo bar'}
+    let class_string_regex = Regex::new(r#"className\s*=\s*\{?\s*["']([^"'`]+)["']"#).unwrap();
+    for capture in class_string_regex.captures_iter(content) {
+        let classes = capture.get(1).unwrap();
+        let mut offset = classes.start();
+        for part in classes.as_str().split(' ') {
+            if is_valid_class_name(part) {
+                usages.push(located_usage(content, file_path, part, None, offset));
             }
+            offset += part.len() + 1;
         }
     }
     
-    // Convert to Vec and sort
-    let mut result: Vec<String> = references.into_iter().collect();
-    result.sort();
-    result
+    usages.sort_by_key(|usage| (usage.line_number, usage.column));
+    usages
+}
+
+/// Resolve a relative stylesheet import against the importing file
+/// Returns None for package or alias imports that can't be located on disk
+pub fn resolve_style_import(js_file: &Path, source: &str) -> Option<PathBuf> {
+    resolve_style_import_in(&FsFiles::new(""), js_file, source)
+}
+
+/// Same as `resolve_style_import`, looking the stylesheet up in `files`
+pub fn resolve_style_import_in(files: &dyn FileProvider, js_file: &Path, source: &str) -> Option<PathBuf> {
+    if !source.starts_with('.') {
+        return None;
+    }
+    
+    let joined = js_file.parent().unwrap_or(Path::new("")).join(source);
+    let resolved = normalize_path(&joined);
+    files.is_file(&resolved).then_some(resolved)
+}
+
+/// Lexically normalize a path by removing `.` and resolving `..` components
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+fn located_usage(content: &str, file_path: &str, name: &str, binding: Option<&String>, offset: usize) -> ClassUsage {
+    let (line_number, column) = line_and_column(content, offset);
+    ClassUsage {
+        name: name.to_string(),
+        binding: binding.cloned(),
+        file_path: file_path.to_string(),
+        line_number,
+        column,
+    }
+}
+
+/// Convert a byte offset into a 1-based line and column
+fn line_and_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line_number = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line_number, column)
 }
 
 /// Extract simple className patterns: className="class1 class2"
-fn extract_simple_classnames(content: &str) -> Vec<String> {
+fn extract_simple_classnames(content: &str) -> Vec<PatternMatch> {
     let mut classes = Vec::new();
     let regex = Regex::new(r#"className\s*=\s*["'`]([^"'`]+)["'`]"#).unwrap();
     
     for capture in regex.captures_iter(content) {
         if let Some(classes_str) = capture.get(1) {
-            classes.extend(split_class_string(classes_str.as_str()));
+            for class_name in split_class_string(classes_str.as_str()) {
+                classes.push(pattern_match(&class_name, UsageKind::Direct, capture.get(0).unwrap()));
+            }
         }
     }
     
@@ -168,13 +345,15 @@ fn extract_simple_classnames(content: &str) -> Vec<String> {
 }
 
 /// Extract object className patterns: className={'class1 class2'}
-fn extract_object_classnames(content: &str) -> Vec<String> {
+fn extract_object_classnames(content: &str) -> Vec<PatternMatch> {
     let mut classes = Vec::new();
     let regex = Regex::new(r#"className\s*=\s*\{\s*['"`]([^'"`]+)['"`]\s*\}"#).unwrap();
     
     for capture in regex.captures_iter(content) {
         if let Some(classes_str) = capture.get(1) {
-            classes.extend(split_class_string(classes_str.as_str()));
+            for class_name in split_class_string(classes_str.as_str()) {
+                classes.push(pattern_match(&class_name, UsageKind::Direct, capture.get(0).unwrap()));
+            }
         }
     }
     
@@ -182,17 +361,17 @@ fn extract_object_classnames(content: &str) -> Vec<String> {
 }
 
 /// Extract CSS modules references (styles.className)
-fn extract_css_modules_references(content: &str) -> Vec<String> {
+fn extract_css_modules_references(content: &str) -> Vec<PatternMatch> {
     let mut classes = Vec::new();
     
     // Direct usage: styles.className
     let direct_regex = Regex::new(r"styles\.([a-zA-Z][a-zA-Z0-9_-]*)").unwrap();
     for capture in direct_regex.captures_iter(content) {
         if let Some(class_name) = capture.get(1) {
-            let class_str = class_name.as_str().to_string();
+            let class_str = class_name.as_str();
             // Filter out template literal variables
             if !class_str.starts_with("${") && !class_str.ends_with("}") {
-                classes.push(class_str);
+                classes.push(pattern_match(class_str, UsageKind::Direct, capture.get(0).unwrap()));
             }
         }
     }
@@ -201,7 +380,7 @@ fn extract_css_modules_references(content: &str) -> Vec<String> {
     let template_regex = Regex::new(r"\$\{styles\.([a-zA-Z][a-zA-Z0-9_-]*)\}").unwrap();
     for capture in template_regex.captures_iter(content) {
         if let Some(class_name) = capture.get(1) {
-            classes.push(class_name.as_str().to_string());
+            classes.push(pattern_match(class_name.as_str(), UsageKind::Template, capture.get(0).unwrap()));
         }
     }
     
@@ -209,8 +388,9 @@ fn extract_css_modules_references(content: &str) -> Vec<String> {
     let destructure_regex = Regex::new(r"const\s*\{\s*([^}]+)\s*\}\s*=\s*styles").unwrap();
     for capture in destructure_regex.captures_iter(content) {
         if let Some(destructured) = capture.get(1) {
-            let class_names = extract_destructured_class_names(destructured.as_str());
-            classes.extend(class_names);
+            for class_name in extract_destructured_class_names(destructured.as_str()) {
+                classes.push(pattern_match(&class_name, UsageKind::Destructured, capture.get(0).unwrap()));
+            }
         }
     }
     
@@ -223,20 +403,25 @@ fn extract_css_modules_references(content: &str) -> Vec<String> {
 
 /// NEW: Extract template literal class patterns
 /// Handles: `${styles.button} ${variantClass}`
-fn extract_template_literal_classes(content: &str) -> Vec<String> {
+fn extract_template_literal_classes(content: &str) -> Vec<PatternMatch> {
     let mut classes = Vec::new();
     
     // Look for template literals that contain styles references
     let template_regex = Regex::new(r"`[^`]*\$\{[^}]*styles\.[^}]+\}[^`]*`").unwrap();
+    let styles_regex = Regex::new(r"styles\.([a-zA-Z][a-zA-Z0-9_-]*)").unwrap();
     
     for template_match in template_regex.find_iter(content) {
         let template_content = template_match.as_str();
         
         // Extract direct styles.className references within the template
-        let styles_regex = Regex::new(r"styles\.([a-zA-Z][a-zA-Z0-9_-]*)").unwrap();
         for capture in styles_regex.captures_iter(template_content) {
             if let Some(class_name) = capture.get(1) {
-                classes.push(class_name.as_str().to_string());
+                classes.push(PatternMatch {
+                    class_name: class_name.as_str().to_string(),
+                    kind: UsageKind::Template,
+                    offset: template_match.start(),
+                    pattern: template_content.to_string(),
+                });
             }
         }
     }
@@ -245,7 +430,7 @@ fn extract_template_literal_classes(content: &str) -> Vec<String> {
 }
 
 /// Extract styled-components references (for future implementation)
-fn extract_styled_components_references(_content: &str) -> Vec<String> {
+fn extract_styled_components_references(_content: &str) -> Vec<PatternMatch> {
     // TODO: Implement styled-components parsing
     // Look for styled.div`...` patterns and extract class usage
     Vec::new()
@@ -265,8 +450,8 @@ fn extract_destructured_class_names(destructured: &str) -> Vec<String> {
 /// NEW: Extract patterns where styles[template] is assigned to a variable
 /// then that variable is used in className
 /// Example: const colorClassName = color !== 'none' ? styles[`accordion_${color}`] : '';
-fn extract_variable_assignment_patterns(content: &str) -> Vec<String> {
-    let mut classes = HashSet::new();
+fn extract_variable_assignment_patterns(content: &str) -> Vec<PatternMatch> {
+    let mut classes = Vec::new();
     
     // Pattern 1: const varName = condition ? styles[`template`] : '';
     // This captures: const colorClassName = color !== 'none' ? styles[`accordion_${color}`] : '';
@@ -287,7 +472,7 @@ fn extract_variable_assignment_patterns(content: &str) -> Vec<String> {
             for variant in &variants {
                 let class_name = format!("{}{}{}", prefix, variant, suffix);
                 if !class_name.is_empty() && is_valid_class_name(&class_name) {
-                    classes.insert(class_name);
+                    classes.push(pattern_match(&class_name, UsageKind::PrefixExpansion, capture.get(0).unwrap()));
                 }
             }
         }
@@ -310,13 +495,13 @@ fn extract_variable_assignment_patterns(content: &str) -> Vec<String> {
             for variant in &variants {
                 let class_name = format!("{}{}{}", prefix, variant, suffix);
                 if !class_name.is_empty() && is_valid_class_name(&class_name) {
-                    classes.insert(class_name);
+                    classes.push(pattern_match(&class_name, UsageKind::PrefixExpansion, capture.get(0).unwrap()));
                 }
             }
         }
     }
     
-    classes.into_iter().collect()
+    classes
 }
 
 /// Check if a variable is used in any className assignment
@@ -361,8 +546,8 @@ fn generate_variants_for_prefix(prefix: &str) -> Vec<&'static str> {
     }
 }
 
-fn extract_dynamic_css_modules_with_context(content: &str, known_css_classes: &[String]) -> Vec<String> {
-    let mut classes = HashSet::new();
+fn extract_dynamic_css_modules_with_context(content: &str, known_css_classes: &[String]) -> Vec<PatternMatch> {
+    let mut classes = Vec::new();
     
     // Pattern 1: styles[`prefix_${variable}`] or styles[`prefix_${variable}_suffix`]
     // Example: styles[`button_${variant}`] or styles[`card_${color}_outline`]
@@ -373,40 +558,47 @@ fn extract_dynamic_css_modules_with_context(content: &str, known_css_classes: &[
         
         // Only process if we have at least a prefix or suffix (not pure variable)
         if !prefix.is_empty() || !suffix.is_empty() {
-            let matching_classes = find_matching_css_classes(known_css_classes, prefix, suffix);
-            classes.extend(matching_classes);
+            let kind = expansion_kind(prefix);
+            for class_name in find_matching_css_classes(known_css_classes, prefix, suffix) {
+                classes.push(pattern_match(&class_name, kind, capture.get(0).unwrap()));
+            }
         }
     }
     
     // Pattern 2: styles[`${variable1}_${variable2}`] - pure variables with underscore
     // Example: styles[`${variant}_${size}`] -> "systemIcon_small", "themeIcon_large"
     let double_variable_regex = Regex::new(r"styles\[\s*`\$\{[^}]+\}_\$\{[^}]+\}`\s*\]").unwrap();
-    if double_variable_regex.is_match(content) {
-        let variant_size_classes = find_variant_size_classes(known_css_classes);
-        classes.extend(variant_size_classes);
+    for source in double_variable_regex.find_iter(content) {
+        for class_name in find_variant_size_classes(known_css_classes) {
+            classes.push(pattern_match(&class_name, UsageKind::MultiVariableHeuristic, source));
+        }
     }
     
     // Pattern 3: styles[`${variable1}${variable2}`] - pure variables concatenated
     // Example: styles[`${prefix}${suffix}`] 
     let concat_variable_regex = Regex::new(r"styles\[\s*`\$\{[^}]+\}\$\{[^}]+\}`\s*\]").unwrap();
-    if concat_variable_regex.is_match(content) {
+    for source in concat_variable_regex.find_iter(content) {
         // This is harder to predict, but we can try common concatenation patterns
-        let concat_classes = find_concatenation_classes(known_css_classes);
-        classes.extend(concat_classes);
+        for class_name in find_concatenation_classes(known_css_classes) {
+            classes.push(pattern_match(&class_name, UsageKind::MultiVariableHeuristic, source));
+        }
     }
     
     // Pattern 4: styles[`${variable}_literal`] or styles[`literal_${variable}`]
     // Example: styles[`${variant}_outline`] or styles[`button_${size}`]
     let mixed_literal_regex = Regex::new(r"styles\[\s*`(?:([^`$]+)\$\{[^}]+\}|(\$\{[^}]+\})([^`$]+))`\s*\]").unwrap();
     for capture in mixed_literal_regex.captures_iter(content) {
+        let source = capture.get(0).unwrap();
         if let Some(prefix) = capture.get(1) {
             // Pattern: literal_${variable}
-            let matching_classes = find_css_classes_with_prefix(known_css_classes, prefix.as_str());
-            classes.extend(matching_classes);
+            for class_name in find_css_classes_with_prefix(known_css_classes, prefix.as_str()) {
+                classes.push(pattern_match(&class_name, UsageKind::PrefixExpansion, source));
+            }
         } else if let Some(suffix) = capture.get(3) {
             // Pattern: ${variable}_literal
-            let matching_classes = find_css_classes_with_suffix(known_css_classes, suffix.as_str());
-            classes.extend(matching_classes);
+            for class_name in find_css_classes_with_suffix(known_css_classes, suffix.as_str()) {
+                classes.push(pattern_match(&class_name, UsageKind::SuffixHeuristic, source));
+            }
         }
     }
     
@@ -415,25 +607,27 @@ fn extract_dynamic_css_modules_with_context(content: &str, known_css_classes: &[
     for capture in concat_regex.captures_iter(content) {
         if let Some(prefix_match) = capture.get(1) {
             let prefix_str = prefix_match.as_str();
-            let matching_classes = find_css_classes_with_prefix(known_css_classes, prefix_str);
-            classes.extend(matching_classes);
+            for class_name in find_css_classes_with_prefix(known_css_classes, prefix_str) {
+                classes.push(pattern_match(&class_name, UsageKind::PrefixExpansion, capture.get(0).unwrap()));
+            }
         }
     }
     
     // Pattern 6: Complex template literals with multiple variables
     // Example: styles[`${base}_${variant}_${size}`]
     let multi_variable_regex = Regex::new(r"styles\[\s*`[^`]*\$\{[^}]+\}[^`]*\$\{[^}]+\}[^`]*\$\{[^}]+\}[^`]*`\s*\]").unwrap();
-    if multi_variable_regex.is_match(content) {
-        let multi_var_classes = find_multi_variable_classes(known_css_classes);
-        classes.extend(multi_var_classes);
+    for source in multi_variable_regex.find_iter(content) {
+        for class_name in find_multi_variable_classes(known_css_classes) {
+            classes.push(pattern_match(&class_name, UsageKind::MultiVariableHeuristic, source));
+        }
     }
     
-    classes.into_iter().collect()
+    classes
 }
 
 /// NEW: Extract variable assignment patterns using actual CSS class definitions
-fn extract_variable_assignment_patterns_with_context(content: &str, known_css_classes: &[String]) -> Vec<String> {
-    let mut classes = HashSet::new();
+fn extract_variable_assignment_patterns_with_context(content: &str, known_css_classes: &[String]) -> Vec<PatternMatch> {
+    let mut classes = Vec::new();
     
     // Pattern 1: const varName = condition ? styles[`template`] : '';
     let conditional_assignment_regex = Regex::new(
@@ -448,8 +642,9 @@ fn extract_variable_assignment_patterns_with_context(content: &str, known_css_cl
         // Check if this variable is used in a className
         if is_variable_used_in_classname(content, var_name) {
             // Find all CSS classes that match this pattern
-            let matching_classes = find_matching_css_classes(known_css_classes, prefix, suffix);
-            classes.extend(matching_classes);
+            for class_name in find_matching_css_classes(known_css_classes, prefix, suffix) {
+                classes.push(pattern_match(&class_name, expansion_kind(prefix), capture.get(0).unwrap()));
+            }
         }
     }
     
@@ -465,15 +660,23 @@ fn extract_variable_assignment_patterns_with_context(content: &str, known_css_cl
         
         // Check if this variable is used in a className
         if is_variable_used_in_classname(content, var_name) {
-            let matching_classes = find_matching_css_classes(known_css_classes, prefix, suffix);
-            classes.extend(matching_classes);
+            for class_name in find_matching_css_classes(known_css_classes, prefix, suffix) {
+                classes.push(pattern_match(&class_name, expansion_kind(prefix), capture.get(0).unwrap()));
+            }
         }
     }
     
-    classes.into_iter().collect()
+    classes
 }
 
-
+/// Templates with a literal prefix are expanded from it; without one only the suffix is left to match on
+fn expansion_kind(prefix: &str) -> UsageKind {
+    if prefix.is_empty() {
+        UsageKind::SuffixHeuristic
+    } else {
+        UsageKind::PrefixExpansion
+    }
+}
 
 /// Find CSS classes that start with a given prefix
 fn find_css_classes_with_prefix(known_css_classes: &[String], prefix: &str) -> Vec<String> {
@@ -505,8 +708,8 @@ fn split_class_string(classes_str: &str) -> Vec<String> {
 }
 
 /// Check if we should process this JavaScript/TypeScript file
-fn should_process_js_file(path: &Path, config: &AnalysisConfig) -> bool {
-    let is_js_file = path.extension().map_or(false, |ext| {
+pub(crate) fn should_process_js_file(path: &Path, config: &AnalysisConfig) -> bool {
+    let is_js_file = path.extension().is_some_and(|ext| {
         matches!(ext.to_str(), Some("js") | Some("jsx") | Some("ts") | Some("tsx"))
     });
     
@@ -623,7 +826,7 @@ fn is_likely_size(s: &str) -> bool {
 /// Check if a class name looks like a camelCase concatenation
 fn is_camel_case_concatenation(class_name: &str) -> bool {
     // Look for patterns like buttonPrimary, cardLarge, etc.
-    let has_lowercase_start = class_name.chars().next().map_or(false, |c| c.is_lowercase());
+    let has_lowercase_start = class_name.chars().next().is_some_and(|c| c.is_lowercase());
     let has_uppercase_middle = class_name.chars().skip(1).any(|c| c.is_uppercase());
     
     has_lowercase_start && has_uppercase_middle && class_name.chars().all(|c| c.is_alphanumeric())
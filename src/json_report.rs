@@ -0,0 +1,215 @@
+// src/json_report.rs - The JSON report format
+// `--format json` writes a `JsonReport`: typed, versioned with `schema_version` and described by the JSON Schema
+// in `schema/report.schema.json` (`rustbrother schema` prints it). Every list is sorted by file, line and name,
+// and the timestamp can be left out, so two runs over the same tree produce byte-identical reports.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use crate::{
+    AnalysisResult, ComplexityWarning, CssClass, CustomProperty, DeadComponent, DuplicateRuleGroup, FileDiagnostic, SafelistEntry,
+    StylesheetWarning, Suppression, UndefinedReference, UnusedStylesheetFile, UsageKind, WarningSeverity,
+};
+
+/// Version of the JSON report shape. The major number changes when fields are removed, renamed or change
+/// meaning; the minor number when fields are added.
+pub const REPORT_SCHEMA_VERSION: &str = "1.1";
+
+/// A full analysis as written by `--format json`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonReport {
+    /// `REPORT_SCHEMA_VERSION` of the tool that wrote the report
+    pub schema_version: String,
+    /// RFC 3339 time the report was generated; absent with `--no-timestamp`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    pub summary: ReportSummary,
+    pub unused_classes: Vec<CssClass>,
+    pub dead_code_classes: Vec<CssClass>,
+    pub dead_components: Vec<DeadComponent>,
+    pub unreachable_stylesheets: Vec<String>,
+    pub unused_stylesheet_files: Vec<UnusedStylesheetFile>,
+    pub used_classes: Vec<CssClass>,
+    /// The used classes backed only by dynamic guesses, also listed in `used_classes`
+    pub possibly_used_classes: Vec<CssClass>,
+    pub undefined_references: Vec<UndefinedReference>,
+    pub duplicate_rules: Vec<DuplicateRuleGroup>,
+    pub custom_properties: Vec<CustomProperty>,
+    pub complexity_warnings: Vec<ComplexityWarning>,
+    pub stylesheet_warnings: Vec<StylesheetWarning>,
+    pub unused_suppressions: Vec<Suppression>,
+    pub unused_safelist_entries: Vec<SafelistEntry>,
+    /// Files that couldn't be read or parsed (since 1.1)
+    #[serde(default)]
+    pub diagnostics: Vec<FileDiagnostic>,
+}
+
+/// Headline counts of a report
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReportSummary {
+    pub total_css_classes: usize,
+    pub used_classes: usize,
+    pub definitely_used_classes: usize,
+    pub possibly_used_classes: usize,
+    pub unused_classes: usize,
+    pub dead_code_classes: usize,
+    pub dead_components: usize,
+    pub unreachable_stylesheets: usize,
+    pub unused_stylesheet_files: usize,
+    pub unused_stylesheet_bytes: u64,
+    /// Unused classes as a whole percentage of all classes
+    pub unused_percentage: f64,
+    pub total_files_scanned: usize,
+    pub css_files_scanned: usize,
+    pub js_files_scanned: usize,
+    pub custom_properties_found: usize,
+    pub undefined_references: usize,
+    pub stylesheet_warnings: SeverityCounts,
+    pub duplicate_rule_groups: usize,
+    pub duplicate_bytes_saved: usize,
+    pub unused_suppressions: usize,
+    pub safelisted_classes: usize,
+    pub unused_safelist_entries: usize,
+    pub complexity_warnings: SeverityCounts,
+    /// Files that couldn't be read or parsed (since 1.1)
+    #[serde(default)]
+    pub file_diagnostics: usize,
+}
+
+/// Warnings of one kind, in total and per severity
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct SeverityCounts {
+    pub total: usize,
+    pub high: usize,
+    pub medium: usize,
+    pub low: usize,
+}
+
+impl SeverityCounts {
+    fn count<'a>(severities: impl Iterator<Item = &'a WarningSeverity>) -> Self {
+        let mut counts = SeverityCounts::default();
+        for severity in severities {
+            counts.total += 1;
+            match severity {
+                WarningSeverity::High => counts.high += 1,
+                WarningSeverity::Medium => counts.medium += 1,
+                WarningSeverity::Low => counts.low += 1,
+            }
+        }
+        counts
+    }
+}
+
+impl ReportSummary {
+    /// Count the findings of `result`
+    pub fn from_result(result: &AnalysisResult) -> Self {
+        let possibly_used_classes = result.used_classes.iter().filter(|class| !class.is_definitely_used()).count();
+        let total_classes = result.used_classes.len() + result.dead_code_classes.len() + result.unused_classes.len();
+        let unused_stylesheet_bytes = result.unused_stylesheet_files.iter().map(|file| file.size_bytes).sum();
+
+        ReportSummary {
+            total_css_classes: total_classes,
+            used_classes: result.used_classes.len(),
+            definitely_used_classes: result.used_classes.len() - possibly_used_classes,
+            possibly_used_classes,
+            unused_classes: result.unused_classes.len(),
+            dead_code_classes: result.dead_code_classes.len(),
+            dead_components: result.dead_components.len(),
+            unreachable_stylesheets: result.unreachable_stylesheets.len(),
+            unused_stylesheet_files: result.unused_stylesheet_files.len(),
+            unused_stylesheet_bytes,
+            unused_percentage: if total_classes > 0 {
+                (result.unused_classes.len() as f64 / total_classes as f64 * 100.0).round()
+            } else {
+                0.0
+            },
+            total_files_scanned: result.total_files_scanned,
+            css_files_scanned: result.total_css_files,
+            js_files_scanned: result.total_js_files,
+            custom_properties_found: result.used_custom_properties.len(),
+            undefined_references: result.undefined_references.len(),
+            stylesheet_warnings: SeverityCounts::count(result.stylesheet_warnings.iter().map(|w| &w.severity)),
+            duplicate_rule_groups: result.duplicate_rules.len(),
+            duplicate_bytes_saved: result.duplicate_rules.iter().map(|group| group.estimated_bytes_saved).sum(),
+            unused_suppressions: result.unused_suppressions.len(),
+            safelisted_classes: result.used_classes
+                .iter()
+                .filter(|class| class.evidence.iter().any(|e| e.kind == UsageKind::Safelisted))
+                .count(),
+            unused_safelist_entries: result.unused_safelist_entries.len(),
+            complexity_warnings: SeverityCounts::count(result.complexity_warnings.iter().map(|w| &w.severity)),
+            file_diagnostics: result.diagnostics.len(),
+        }
+    }
+}
+
+impl JsonReport {
+    /// Build the report for `result` in a deterministic order, stamped with `timestamp` when given
+    pub fn from_result(result: &AnalysisResult, timestamp: Option<String>) -> Self {
+        let sorted_classes = |classes: &[CssClass]| {
+            let mut classes = classes.to_vec();
+            for class in &mut classes {
+                class.evidence.sort_by(|a, b| (&a.file_path, a.line_number, &a.pattern).cmp(&(&b.file_path, b.line_number, &b.pattern)));
+            }
+            classes.sort_by(|a, b| (&a.file_path, a.line_number, &a.name).cmp(&(&b.file_path, b.line_number, &b.name)));
+            classes
+        };
+
+        let used_classes = sorted_classes(&result.used_classes);
+        let possibly_used_classes: Vec<CssClass> = used_classes.iter().filter(|class| !class.is_definitely_used()).cloned().collect();
+
+        let mut dead_components = result.dead_components.clone();
+        dead_components.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        let mut unreachable_stylesheets = result.unreachable_stylesheets.clone();
+        unreachable_stylesheets.sort();
+        let mut unused_stylesheet_files = result.unused_stylesheet_files.clone();
+        unused_stylesheet_files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        let mut undefined_references = result.undefined_references.clone();
+        undefined_references.sort_by(|a, b| (&a.file_path, a.line_number, a.column, &a.name).cmp(&(&b.file_path, b.line_number, b.column, &b.name)));
+        let mut duplicate_rules = result.duplicate_rules.clone();
+        for group in &mut duplicate_rules {
+            group.occurrences.sort_by(|a, b| (&a.file_path, a.start_line).cmp(&(&b.file_path, b.start_line)));
+        }
+        duplicate_rules.sort_by(|a, b| {
+            let first = |group: &DuplicateRuleGroup| group.occurrences.first().map(|o| (o.file_path.clone(), o.start_line));
+            (first(a), &a.suggested_name).cmp(&(first(b), &b.suggested_name))
+        });
+        let mut custom_properties = result.used_custom_properties.clone();
+        custom_properties.sort_by(|a, b| (&a.file_path, a.line_number, &a.name).cmp(&(&b.file_path, b.line_number, &b.name)));
+        let mut complexity_warnings = result.complexity_warnings.clone();
+        complexity_warnings.sort_by(|a, b| (&a.file_path, a.start_line, a.end_line, &a.rule_id).cmp(&(&b.file_path, b.start_line, b.end_line, &b.rule_id)));
+        let mut stylesheet_warnings = result.stylesheet_warnings.clone();
+        stylesheet_warnings.sort_by(|a, b| (&a.file_path, a.line_number, &a.pattern).cmp(&(&b.file_path, b.line_number, &b.pattern)));
+        let mut unused_suppressions = result.unused_suppressions.clone();
+        unused_suppressions.sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
+        let mut diagnostics = result.diagnostics.clone();
+        diagnostics.sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
+
+        JsonReport {
+            schema_version: REPORT_SCHEMA_VERSION.to_string(),
+            timestamp,
+            summary: ReportSummary::from_result(result),
+            unused_classes: sorted_classes(&result.unused_classes),
+            dead_code_classes: sorted_classes(&result.dead_code_classes),
+            dead_components,
+            unreachable_stylesheets,
+            unused_stylesheet_files,
+            used_classes,
+            possibly_used_classes,
+            undefined_references,
+            duplicate_rules,
+            custom_properties,
+            complexity_warnings,
+            stylesheet_warnings,
+            unused_suppressions,
+            unused_safelist_entries: result.unused_safelist_entries.clone(),
+            diagnostics,
+        }
+    }
+}
+
+/// The JSON Schema of `JsonReport`, pretty-printed; `schema/report.schema.json` is this output
+pub fn report_json_schema() -> anyhow::Result<String> {
+    let generator = schemars::generate::SchemaSettings::draft2020_12().for_serialize().into_generator();
+    let schema = generator.into_root_schema_for::<JsonReport>();
+    Ok(serde_json::to_string_pretty(&schema)? + "\n")
+}
@@ -1,6 +1,9 @@
 // src/lib.rs - complexity analysis module and types
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 // Add the new complexity analyzer module
 pub mod css_parser;
@@ -8,24 +11,92 @@ pub mod js_parser;
 pub mod analyzer;
 pub mod reporter;
 pub mod complexity_analyzer; 
+pub mod complexity_rules;
+pub mod lsp;
+pub mod config;
+pub mod fixer;
+pub mod duplicate_analyzer;
+pub mod suppressions;
+pub mod safelist;
+pub mod import_graph;
+pub mod stylesheet_files;
+pub mod changes;
+pub mod compare;
+pub mod json_report;
+pub mod templates;
+pub mod edits;
+pub mod tui;
+pub mod progress;
+pub mod error;
+pub mod files;
 
 // Re-export functions including complexity analysis
-pub use analyzer::analyze_directory;
-pub use css_parser::{find_css_classes, find_custom_properties, find_custom_property_usage};
-pub use js_parser::{find_js_css_references, find_js_css_references_with_context, extract_css_references, extract_css_references_with_css_context};
-pub use reporter::generate_report;
-pub use complexity_analyzer::find_complexity_warnings; 
+pub use analyzer::{analyze_directory, analyze_directory_with_progress, analyze_sources, analyze_sources_with_progress, explain_class, directory_stats, find_always_overridden_classes, find_excessive_important_usage, find_specificity_issues, find_undefined_references, find_undefined_references_in};
+pub use css_parser::{find_css_classes, find_css_classes_in, find_css_rules, find_css_rules_in, find_custom_properties, find_custom_properties_in, find_custom_property_usage, find_custom_property_usage_in, parse_rules, parse_stylesheet, rule_block_range, selector_specificity};
+pub use js_parser::{find_js_css_references, find_js_css_references_in, find_js_css_references_with_context, find_js_css_references_with_context_in, find_js_css_usage_evidence, find_js_css_usage_evidence_in, extract_css_references, extract_css_references_with_css_context, extract_css_usage_evidence, find_style_imports, find_class_usages, find_class_usages_in_directory, find_class_usages_in_directory_in, resolve_style_import, resolve_style_import_in};
+pub use reporter::{generate_report, generate_report_with_config};
+pub use complexity_analyzer::{find_complexity_warnings, find_complexity_warnings_in, find_complexity_warnings_with_rules, find_complexity_warnings_with_rules_in, find_stylesheet_complexity_warnings, ScriptFile};
+pub use complexity_rules::{ComplexityRule, RuleRegistry};
+pub use duplicate_analyzer::{find_duplicate_rules, group_duplicate_rules};
+pub use suppressions::{find_suppressions, find_suppressions_in, parse_suppressions};
+pub use stylesheet_files::{find_unused_stylesheet_files, find_unused_stylesheet_files_in};
+pub use compare::{compare_reports, load_report, ReportSnapshot};
+pub use json_report::{report_json_schema, JsonReport, ReportSummary, SeverityCounts, REPORT_SCHEMA_VERSION};
+pub use changes::{affected_files, analyze_changes, analyze_changes_with_progress, changed_files, deleted_files, retain_findings, stylesheets_of_deleted_files};
+pub use import_graph::{find_dead_components, find_module_imports, find_stylesheet_imports, find_unreachable_stylesheets, resolve_module_import, resolve_module_import_in, resolve_stylesheet_import, resolve_stylesheet_import_in, ModuleGraph};
+pub use config::{load_config, find_config_file, CONFIG_FILE_NAME};
+pub use error::Error;
+pub use files::{find_file_diagnostics, find_file_diagnostics_in, read_source_file, walk_source_files, FileProvider, FsFiles, MemoryFiles, OverlayFiles, SourceFile};
+pub use progress::{Phase, ProgressEvent, ProgressReporter, SilentReporter, StderrReporter, Verbosity};
 
 /// Represents a single CSS class found in a stylesheet
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CssClass {
     pub name: String,
     pub file_path: String,
     pub line_number: usize,
+    /// Why the class counts as used - empty for unused classes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub evidence: Vec<UsageEvidence>,
+}
+
+impl CssClass {
+    /// True when at least one reference names the class literally rather than through a dynamic guess
+    pub fn is_definitely_used(&self) -> bool {
+        self.evidence.iter().any(|e| e.kind.is_definite())
+    }
+}
+
+/// Which extraction rule matched a class reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub enum UsageKind {
+    Direct,                 // styles.foo, className="foo"
+    Destructured,           // const { foo } = styles
+    Template,               // `${styles.foo} ...`
+    PrefixExpansion,        // styles[`foo_${x}`] expanded to every known foo_* class
+    SuffixHeuristic,        // styles[`${x}_foo`] matched on the suffix alone
+    MultiVariableHeuristic, // styles[`${a}_${b}`] matched by naming heuristics
+    Safelisted,             // matched a `safelist` entry in the config
+}
+
+impl UsageKind {
+    /// Direct, destructured and template references name the class literally; safelisted classes are kept on purpose
+    pub fn is_definite(&self) -> bool {
+        matches!(self, UsageKind::Direct | UsageKind::Destructured | UsageKind::Template | UsageKind::Safelisted)
+    }
+}
+
+/// Where and how a class reference was found
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UsageEvidence {
+    pub kind: UsageKind,
+    pub file_path: String,
+    pub line_number: usize,
+    pub pattern: String,
 }
 
 /// Represents a CSS custom property (CSS variable)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CustomProperty {
     pub name: String,
     pub value: String,
@@ -33,56 +104,428 @@ pub struct CustomProperty {
     pub line_number: usize,
 }
 
-/// NEW: Represents a complexity warning found in the codebase
+/// A CSS module import in a JS/TS file: `import styles from './Panel.module.scss'`
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ComplexityWarning {
+pub struct StyleImport {
+    pub binding: String,
+    pub source: String,
+    pub line_number: usize,
+}
+
+/// A single `property: value` declaration inside a rule block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CssDeclaration {
+    pub property: String,
+    pub value: String,
+    pub important: bool,
+    pub line_number: usize,
+}
+
+/// Selector specificity as (ids, classes, elements); compares the way the cascade does
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Specificity {
+    pub ids: u32,
+    pub classes: u32,
+    pub elements: u32,
+}
+
+impl std::fmt::Display for Specificity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{},{})", self.ids, self.classes, self.elements)
+    }
+}
+
+/// A rule block with its selector fully expanded through SCSS nesting (`&_outline` -> `.panel_outline`)
+/// `raw_selector` is the selector as written, `depth` counts the enclosing rules (0 at the top level)
+/// and `conditions` are the enclosing at-rule preludes such as `@media (min-width: 768px)`, outermost first.
+/// `specificity` is the highest specificity in the selector list.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CssRule {
+    pub selector: String,
+    pub raw_selector: String,
+    pub specificity: Specificity,
+    pub file_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub depth: usize,
+    pub conditions: Vec<String>,
+    pub declarations: Vec<CssDeclaration>,
+}
+
+/// Where one copy of a duplicated rule lives
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RuleLocation {
+    pub selector: String,
+    pub file_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// Rules whose declaration blocks are identical (or nearly) after normalization
+/// `declarations` are the normalized declarations every copy shares
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DuplicateRuleGroup {
+    pub suggested_name: String,
+    pub identical: bool,
+    pub declarations: Vec<String>,
+    pub occurrences: Vec<RuleLocation>,
+    pub estimated_bytes_saved: usize,
+}
+
+/// A located reference to a CSS class in a JS/TS file
+/// `binding` is the CSS module object (`styles`) or None for plain `className="..."` strings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassUsage {
+    pub name: String,
+    pub binding: Option<String>,
     pub file_path: String,
     pub line_number: usize,
+    pub column: usize,
+}
+
+/// A class referenced from JS/TS that no stylesheet defines - it resolves to `undefined` at runtime
+/// `stylesheet` is the imported module the binding points at, None for plain `className="..."` strings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UndefinedReference {
+    pub name: String,
+    pub binding: Option<String>,
+    pub file_path: String,
+    pub line_number: usize,
+    pub column: usize,
+    pub stylesheet: Option<String>,
+    pub suggestion: Option<String>,
+}
+
+/// A component (a `.jsx`/`.tsx` file or a script importing a stylesheet, not a test or story) no entry point
+/// reaches through imports, and the stylesheets it imports
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DeadComponent {
+    pub file_path: String,
+    pub stylesheets: Vec<String>,
+}
+
+/// A file the analysis couldn't fully use; its classes and references may be missing from the results
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FileDiagnostic {
+    pub file_path: String,
+    pub kind: DiagnosticKind,
+    /// Where parsing went wrong; none for files that couldn't be read at all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_number: Option<usize>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum DiagnosticKind {
+    /// A directory couldn't be listed; everything below it was skipped
+    WalkError,
+    /// The file couldn't be read, e.g. for lack of permission
+    Unreadable,
+    /// The file isn't valid UTF-8 and was skipped
+    NonUtf8,
+    /// The stylesheet has unbalanced braces, so rules after the problem may be misread
+    ParseError,
+}
+
+/// A stylesheet that can most likely be deleted as a whole
+/// `used_classes` and `unused_classes` count the classes it defines (classes used only by dead code count
+/// as unused) and `unused_ratio` is the unused share (0.0-1.0, 0.0 for files without classes)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UnusedStylesheetFile {
+    pub file_path: String,
+    pub reason: UnusedFileReason,
+    pub size_bytes: u64,
+    pub used_classes: usize,
+    pub unused_classes: usize,
+    pub unused_ratio: f64,
+}
+
+/// Why a whole stylesheet is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum UnusedFileReason {
+    /// A CSS module no JS/TS file imports
+    NotImported,
+    /// A CSS module none of whose classes are used
+    AllClassesUnused,
+    /// A stylesheet without a single class that no script or stylesheet imports
+    NoClassDefinitions,
+}
+
+/// Everything known about one class name: where it's defined, where it's referenced and the verdict
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassExplanation {
+    pub class_name: String,
+    pub definitions: Vec<CssClass>,
+    pub references: Vec<ClassUsage>,
+    pub is_used: bool,
+    /// Referenced, but only from dead components
+    pub used_only_by_dead_code: bool,
+    pub reason: String,
+}
+
+/// Per-directory metrics for the `stats` command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryStats {
+    pub directory: String,
+    pub css_files: usize,
+    pub total_classes: usize,
+    pub used_classes: usize,
+    pub unused_classes: usize,
+    pub dead_code_classes: usize,
+    pub unused_custom_properties: usize,
+    pub complexity_warnings: usize,
+}
+
+/// NEW: Represents a complexity warning found in the codebase
+/// `start_line` and `end_line` (1-based, inclusive) span the whole expression or rule the warning is about
+/// and `rule_id` is the stable ID of the rule that raised it, e.g. `deep-template-nesting`
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ComplexityWarning {
+    pub file_path: String,
+    pub start_line: usize,
+    pub end_line: usize,
     pub warning_type: ComplexityWarningType,
     pub pattern: String,
     pub suggestion: String,
     pub severity: WarningSeverity,
+    #[serde(default)]
+    pub rule_id: String,
 }
 
 /// NEW: Types of complexity warnings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum ComplexityWarningType {
     DynamicClassConstruction,
     DeepTemplateNesting,
     ConditionalClassAssignment,
     MultiVariablePattern,
     UntrackedDynamicPattern,
+    DeepSelectorNesting,
+    LongDescendantChain,
+    SelectorConcatenation,
+    OversizedRuleBlock,
+    /// Raised by a user-registered `ComplexityRule`; the name is shown in reports
+    Custom(String),
+}
+
+/// A maintainability problem found in a stylesheet rather than in JS
+/// `pattern` describes what was counted or matched, `selector` is the rule it applies to (None for whole-file findings)
+/// and `competing_rule` is the rule that wins over it, for findings about the cascade
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StylesheetWarning {
+    pub file_path: String,
+    pub line_number: usize,
+    pub selector: Option<String>,
+    pub warning_type: StylesheetWarningType,
+    pub pattern: String,
+    pub suggestion: String,
+    pub severity: WarningSeverity,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub competing_rule: Option<RuleLocation>,
+}
+
+/// Types of stylesheet warnings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum StylesheetWarningType {
+    ImportantOverusedInRule,
+    ImportantOverusedOnSelector,
+    ImportantDensity,
+    ImportantInComponentModule,
+    ShadowedDeclaration,
+    AlwaysOverriddenRule,
+    SpecificityOverBudget,
+    IdSelector,
 }
 
 /// NEW: Severity levels for warnings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum WarningSeverity {
     Low,    // Minor complexity, easy to refactor
     Medium, // Moderate complexity, consider refactoring
     High,   // High complexity, should refactor for maintainability
 }
 
+/// How one count moved between two reports; `change` is `new - old`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CountDelta {
+    pub name: String,
+    pub old: usize,
+    pub new: usize,
+    pub change: i64,
+}
+
+/// Unused classes and complexity warnings of one directory in two reports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectoryDelta {
+    pub directory: String,
+    pub unused_classes: CountDelta,
+    pub complexity_warnings: CountDelta,
+}
+
+/// The difference between an older and a newer JSON report, for `rustbrother compare`
+/// Classes are matched by file and name, so a class that only moved lines isn't reported as added
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportComparison {
+    pub unused_classes: CountDelta,
+    pub added_unused_classes: Vec<CssClass>,
+    pub removed_unused_classes: Vec<CssClass>,
+    pub warnings_by_severity: Vec<CountDelta>,
+    pub warnings_by_type: Vec<CountDelta>,
+    pub directories: Vec<DirectoryDelta>,
+}
+
+/// An inline `rustbrother-ignore` comment at `line_number`; it silences findings starting on
+/// `target_start_line`..=`target_end_line`. `rules` is empty when it silences every finding there.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Suppression {
+    pub file_path: String,
+    pub line_number: usize,
+    pub rules: Vec<String>,
+    pub target_start_line: usize,
+    pub target_end_line: usize,
+}
+
+/// A class the config declares used no matter what the JS says - runtime, CMS or third-party classes
+/// `class` is an exact name (`is-active`), a prefix ending in `*` (`swiper-*`) or a regex between slashes
+/// (`/^cms-[a-z]+$/`). With `paths` set, only classes in stylesheets whose path contains one of them match.
+/// In the config file a plain string is shorthand for an entry without `paths`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(from = "SafelistSpec")]
+pub struct SafelistEntry {
+    pub class: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+#[serde(untagged)]
+enum SafelistSpec {
+    Class(String),
+    Scoped {
+        class: String,
+        #[serde(default)]
+        paths: Vec<String>,
+    },
+}
+
+impl From<SafelistSpec> for SafelistEntry {
+    fn from(spec: SafelistSpec) -> Self {
+        match spec {
+            SafelistSpec::Class(class) => SafelistEntry { class, paths: Vec::new() },
+            SafelistSpec::Scoped { class, paths } => SafelistEntry { class, paths },
+        }
+    }
+}
+
+/// Per-rule settings under `complexity_rules` in the config file, keyed by rule ID
+/// `severity` and `suggestion` replace what the rule reports; `thresholds` are the rule's own tuning knobs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuleConfig {
+    pub enabled: bool,
+    pub severity: Option<WarningSeverity>,
+    pub suggestion: Option<String>,
+    pub thresholds: BTreeMap<String, usize>,
+}
+
+impl RuleConfig {
+    /// A threshold from config, or the rule's default when it isn't set
+    pub fn threshold(&self, name: &str, default: usize) -> usize {
+        self.thresholds.get(name).copied().unwrap_or(default)
+    }
+}
+
+impl Default for RuleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            severity: None,
+            suggestion: None,
+            thresholds: BTreeMap::new(),
+        }
+    }
+}
+
 /// Analysis results to include complexity warnings
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AnalysisResult {
     pub used_classes: Vec<CssClass>,
     pub unused_classes: Vec<CssClass>,
+    /// Classes referenced only from dead components
+    #[serde(default)]
+    pub dead_code_classes: Vec<CssClass>,
     pub used_custom_properties: Vec<CustomProperty>,
     pub unused_custom_properties: Vec<CustomProperty>,
     pub complexity_warnings: Vec<ComplexityWarning>, 
+    #[serde(default)]
+    pub undefined_references: Vec<UndefinedReference>,
+    #[serde(default)]
+    pub duplicate_rules: Vec<DuplicateRuleGroup>,
+    #[serde(default)]
+    pub stylesheet_warnings: Vec<StylesheetWarning>,
+    /// Suppression comments that didn't silence anything
+    #[serde(default)]
+    pub unused_suppressions: Vec<Suppression>,
+    /// Safelist entries that matched no class in any stylesheet
+    #[serde(default)]
+    pub unused_safelist_entries: Vec<SafelistEntry>,
+    /// Components no entry point reaches; empty unless `entry_points` is configured
+    #[serde(default)]
+    pub dead_components: Vec<DeadComponent>,
+    /// Stylesheets no entry point loads through JS imports or `@import`/`@use`; empty unless `entry_points` is configured
+    #[serde(default)]
+    pub unreachable_stylesheets: Vec<String>,
+    /// Whole stylesheets that look safe to delete
+    #[serde(default)]
+    pub unused_stylesheet_files: Vec<UnusedStylesheetFile>,
+    /// Files that were skipped or only partly understood; the whole tree's, even in `--since` mode
+    #[serde(default)]
+    pub diagnostics: Vec<FileDiagnostic>,
     pub total_files_scanned: usize,
     pub total_css_files: usize,
     pub total_js_files: usize,
 }
 
 /// Cconfiguration to include complexity analysis options
-#[derive(Debug, Clone)]
+/// Also the shape of the `rustbrother.json` config file - missing fields fall back to defaults
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AnalysisConfig {
     pub include_css_modules: bool,
     pub include_styled_components: bool,
     pub ignore_patterns: Vec<String>,
     pub enable_complexity_warnings: bool, 
     pub complexity_threshold: WarningSeverity, 
+    /// Rules need at least this many declarations to be considered for duplication
+    pub duplicate_min_declarations: usize,
+    /// Declaration overlap (0.0-1.0) at which two rule bodies count as near-duplicates
+    pub duplicate_similarity: f64,
+    /// `!important` declarations allowed in one rule (or one selector across a file) before it's flagged
+    pub important_rule_limit: usize,
+    /// Share of a file's declarations (0.0-1.0) that may be `!important` before the file is flagged
+    pub important_density: f64,
+    /// Selectors more specific than this are flagged
+    pub specificity_budget: Specificity,
+    /// Complexity rule settings by rule ID; rules without an entry run with their defaults
+    pub complexity_rules: BTreeMap<String, RuleConfig>,
+    /// Classes that always count as used, see `SafelistEntry`
+    pub safelist: Vec<SafelistEntry>,
+    /// Files whose path contains one of these are entry points (pages, routes, index exports, global stylesheets).
+    /// Scripts they don't reach through imports are dead components and stylesheets they don't load are
+    /// unreachable. Empty turns reachability analysis off.
+    pub entry_points: Vec<String>,
+    /// Link template for source locations in Markdown reports, with `{file}` (the path relative to the git
+    /// repository root), `{line}` and `{end_line}` placeholders, e.g. `https://github.com/acme/shop/blob/main/{file}#L{line}`
+    pub source_url_template: Option<String>,
+    /// Stamp JSON and HTML reports with the time they were generated; turn off for reproducible reports
+    pub report_timestamp: bool,
+    /// Directory of HTML templates replacing the built-in ones of the same name (`report.html`, `file-group.html`, ...)
+    pub template_dir: Option<PathBuf>,
+    /// Fail the analysis on unreadable, non-UTF-8 or unparsable files instead of skipping them
+    pub strict: bool,
+    /// The file this config was loaded from, set by `load_config`; findings that come from the config name it
+    #[serde(skip)]
+    pub config_file: Option<PathBuf>,
 }
 
 impl Default for AnalysisConfig {
@@ -98,6 +541,19 @@ impl Default for AnalysisConfig {
             ],
             enable_complexity_warnings: true, // Enable by default
             complexity_threshold: WarningSeverity::Medium, // Show medium and high by default
+            duplicate_min_declarations: 3,
+            duplicate_similarity: 0.8,
+            important_rule_limit: 3,
+            important_density: 0.1,
+            specificity_budget: Specificity { ids: 0, classes: 3, elements: 2 },
+            complexity_rules: BTreeMap::new(),
+            safelist: Vec::new(),
+            entry_points: Vec::new(),
+            source_url_template: None,
+            report_timestamp: true,
+            template_dir: None,
+            strict: false,
+            config_file: None,
         }
     }
 }
\ No newline at end of file
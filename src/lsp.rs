@@ -0,0 +1,708 @@
+// src/lsp.rs - Language Server Protocol mode
+// Publishes unused classes and stylesheet files, dead components, unreachable stylesheets, undefined references, duplicated rules, stylesheet and complexity warnings and unused suppressions
+// as editor diagnostics and answers go-to-definition, find-references and "remove unused rule" code actions
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument, LogMessage,
+    Notification as _, PublishDiagnostics, ShowMessage,
+};
+use lsp_types::request::{CodeActionRequest, GotoDefinition, References, Request as _};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams, CodeActionProviderCapability,
+    Diagnostic, DiagnosticSeverity, DiagnosticTag, GotoDefinitionParams, GotoDefinitionResponse,
+    InitializeParams, Location, LogMessageParams, MessageType, NumberOrString, OneOf, Position,
+    PublishDiagnosticsParams, Range, ReferenceParams, ShowMessageParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TextDocumentSyncSaveOptions, TextEdit, Url, WorkspaceEdit,
+};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use crate::analyzer::analyze_sources;
+use crate::css_parser::{parse_stylesheet, rule_block_range};
+use crate::error;
+use crate::files::{source_files, FsFiles, MemoryFiles, OverlayFiles};
+use crate::js_parser::{find_class_usages, find_style_imports, resolve_style_import, should_process_js_file};
+use crate::{AnalysisConfig, AnalysisResult, ClassUsage, ComplexityWarning, CssClass, DeadComponent, DuplicateRuleGroup, RuleLocation, StylesheetWarning, Suppression, UndefinedReference, UnusedFileReason, UnusedStylesheetFile, WarningSeverity};
+
+const UNUSED_CLASS_CODE: &str = "unused-class";
+const UNDEFINED_CLASS_CODE: &str = "undefined-class";
+const DUPLICATE_RULE_CODE: &str = "duplicate-rule";
+const UNUSED_SUPPRESSION_CODE: &str = "unused-suppression";
+const DEAD_CODE_CLASS_CODE: &str = "dead-code-class";
+const DEAD_COMPONENT_CODE: &str = "dead-component";
+const UNREACHABLE_STYLESHEET_CODE: &str = "unreachable-stylesheet";
+const UNUSED_STYLESHEET_FILE_CODE: &str = "unused-stylesheet-file";
+const DIAGNOSTIC_SOURCE: &str = "rustbrother";
+/// How long typing has to pause before an edit re-runs the analysis
+const CHANGE_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Run the language server over stdin/stdout until the client shuts it down
+pub fn run_server(root: Option<PathBuf>, config: AnalysisConfig) -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    serve(&connection, root, config)?;
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Run the language server on an existing connection
+/// `root` is used when the client doesn't send a workspace root of its own
+pub fn serve(connection: &Connection, root: Option<PathBuf>, config: AnalysisConfig) -> anyhow::Result<()> {
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+            open_close: Some(true),
+            change: Some(TextDocumentSyncKind::FULL),
+            save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+            ..Default::default()
+        })),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..Default::default()
+    };
+
+    let params: InitializeParams = serde_json::from_value(connection.initialize(serde_json::to_value(capabilities)?)?)?;
+    // Absolute, so the files walked match the paths of open documents
+    let root = absolute(&workspace_root(&params).or(root).unwrap_or_else(|| PathBuf::from(".")));
+
+    let mut server = Server {
+        root,
+        config,
+        documents: HashMap::new(),
+        result: None,
+        published: HashSet::new(),
+        refresh_due: None,
+    };
+    server.refresh(connection)?;
+
+    loop {
+        // An edit schedules a refresh; more edits before it's due push it back
+        let message = match server.refresh_due {
+            Some(due) => match connection.receiver.recv_deadline(due) {
+                Ok(message) => message,
+                Err(error) if error.is_timeout() => {
+                    server.refresh(connection)?;
+                    continue;
+                }
+                Err(_) => break,
+            },
+            None => match connection.receiver.recv() {
+                Ok(message) => message,
+                Err(_) => break,
+            },
+        };
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                let response = server.handle_request(request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => server.handle_notification(connection, notification)?,
+            Message::Response(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Server state: the latest analysis plus the text of documents open in the editor
+struct Server {
+    root: PathBuf,
+    config: AnalysisConfig,
+    documents: HashMap<PathBuf, String>,
+    result: Option<AnalysisResult>,
+    published: HashSet<PathBuf>,
+    /// When the analysis should next re-run after edits to open documents
+    refresh_due: Option<Instant>,
+}
+
+impl Server {
+    fn handle_request(&self, request: Request) -> Response {
+        let id = request.id.clone();
+        let result = match request.method.as_str() {
+            GotoDefinition::METHOD => parse_params::<GotoDefinitionParams>(request)
+                .map(|params| serde_json::to_value(self.definition(params)).unwrap_or_default()),
+            References::METHOD => match parse_params::<ReferenceParams>(request) {
+                Ok(params) => match self.references(params) {
+                    Ok(locations) => Ok(serde_json::to_value(locations).unwrap_or_default()),
+                    Err(error) => return Response::new_err(id, lsp_server::ErrorCode::RequestFailed as i32, error.to_string()),
+                },
+                Err(error) => Err(error),
+            },
+            CodeActionRequest::METHOD => parse_params::<CodeActionParams>(request)
+                .map(|params| serde_json::to_value(self.code_actions(params)).unwrap_or_default()),
+            method => return method_not_found(id, method),
+        };
+
+        match result {
+            Ok(value) => Response::new_ok(id, value),
+            Err(error) => Response::new_err(id, lsp_server::ErrorCode::InvalidParams as i32, error.to_string()),
+        }
+    }
+
+    /// Only failures to talk to the client end the session; a malformed notification is logged and dropped
+    fn handle_notification(&mut self, connection: &Connection, notification: Notification) -> anyhow::Result<()> {
+        let method = notification.method.clone();
+        match method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Some(params) = notification_params::<lsp_types::DidOpenTextDocumentParams>(connection, notification)? else {
+                    return Ok(());
+                };
+                if let Ok(path) = params.text_document.uri.to_file_path() {
+                    self.documents.insert(path, params.text_document.text);
+                }
+            }
+            DidChangeTextDocument::METHOD => {
+                let Some(params) = notification_params::<lsp_types::DidChangeTextDocumentParams>(connection, notification)? else {
+                    return Ok(());
+                };
+                if let (Ok(path), Some(change)) = (params.text_document.uri.to_file_path(), params.content_changes.into_iter().last()) {
+                    self.documents.insert(path, change.text);
+                    self.refresh_due = Some(Instant::now() + CHANGE_DEBOUNCE);
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let Some(params) = notification_params::<lsp_types::DidCloseTextDocumentParams>(connection, notification)? else {
+                    return Ok(());
+                };
+                // Unsaved edits are gone with the buffer, so the analysis goes back to the file on disk
+                if let Ok(path) = params.text_document.uri.to_file_path() {
+                    if self.documents.remove(&path).is_some() {
+                        self.refresh_due = Some(Instant::now() + CHANGE_DEBOUNCE);
+                    }
+                }
+            }
+            DidSaveTextDocument::METHOD => self.refresh(connection)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Re-run the analysis over the workspace, with open documents as the editor has them, and publish
+    /// diagnostics for every affected file
+    /// When the analysis fails the editor is told why, and the last good result and its diagnostics stay
+    fn refresh(&mut self, connection: &Connection) -> anyhow::Result<()> {
+        self.refresh_due = None;
+        let disk = FsFiles::new(&self.root);
+        let open: MemoryFiles = self.documents.iter().collect();
+        let result = match analyze_sources(&OverlayFiles::new(&disk, &open), &self.config) {
+            Ok(result) => result,
+            Err(error) => return show_message(connection, MessageType::ERROR, format!("RustBrother analysis failed: {}", error)),
+        };
+
+        let mut diagnostics: HashMap<PathBuf, Vec<Diagnostic>> = HashMap::new();
+        for class in &result.unused_classes {
+            diagnostics
+                .entry(PathBuf::from(&class.file_path))
+                .or_default()
+                .push(unused_class_diagnostic(class, &self.read(Path::new(&class.file_path))));
+        }
+        for class in &result.dead_code_classes {
+            diagnostics
+                .entry(PathBuf::from(&class.file_path))
+                .or_default()
+                .push(dead_code_class_diagnostic(class, &self.read(Path::new(&class.file_path))));
+        }
+        for component in &result.dead_components {
+            diagnostics
+                .entry(PathBuf::from(&component.file_path))
+                .or_default()
+                .push(dead_component_diagnostic(component));
+        }
+        for stylesheet in &result.unreachable_stylesheets {
+            diagnostics
+                .entry(PathBuf::from(stylesheet))
+                .or_default()
+                .push(unreachable_stylesheet_diagnostic());
+        }
+        for file in &result.unused_stylesheet_files {
+            diagnostics
+                .entry(PathBuf::from(&file.file_path))
+                .or_default()
+                .push(unused_stylesheet_file_diagnostic(file));
+        }
+        for reference in &result.undefined_references {
+            diagnostics
+                .entry(PathBuf::from(&reference.file_path))
+                .or_default()
+                .push(undefined_reference_diagnostic(reference, &self.read(Path::new(&reference.file_path))));
+        }
+        for group in &result.duplicate_rules {
+            for location in &group.occurrences {
+                diagnostics
+                    .entry(PathBuf::from(&location.file_path))
+                    .or_default()
+                    .push(duplicate_rule_diagnostic(group, location));
+            }
+        }
+        for warning in &result.stylesheet_warnings {
+            diagnostics
+                .entry(PathBuf::from(&warning.file_path))
+                .or_default()
+                .push(stylesheet_diagnostic(warning));
+        }
+        for warning in &result.complexity_warnings {
+            diagnostics
+                .entry(PathBuf::from(&warning.file_path))
+                .or_default()
+                .push(complexity_diagnostic(warning));
+        }
+        for suppression in &result.unused_suppressions {
+            diagnostics
+                .entry(PathBuf::from(&suppression.file_path))
+                .or_default()
+                .push(unused_suppression_diagnostic(suppression));
+        }
+
+        // Clear diagnostics for files that no longer have findings
+        for path in self.published.difference(&diagnostics.keys().cloned().collect()) {
+            publish(connection, path, Vec::new())?;
+        }
+        for (path, file_diagnostics) in &diagnostics {
+            publish(connection, path, file_diagnostics.clone())?;
+        }
+
+        self.published = diagnostics.into_keys().collect();
+        self.result = Some(result);
+        Ok(())
+    }
+
+    /// `styles.foo` in a component -> `.foo` in the imported stylesheet
+    fn definition(&self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let position = params.text_document_position_params.position;
+        let js_path = params.text_document_position_params.text_document.uri.to_file_path().ok()?;
+        let content = self.read(&js_path);
+        let file_path = js_path.to_string_lossy();
+
+        let usage = find_class_usages(&content, &file_path)
+            .into_iter()
+            .find(|usage| usage.binding.is_some() && usage_contains(usage, &content, position))?;
+        let binding = usage.binding.as_deref()?;
+        let import = find_style_imports(&content).into_iter().find(|import| import.binding == binding)?;
+        let stylesheet = resolve_style_import(&js_path, &import.source)?;
+
+        let stylesheet_content = self.read(&stylesheet);
+        let class = parse_stylesheet(&stylesheet_content, &stylesheet.to_string_lossy())
+            .ok()?
+            .into_iter()
+            .find(|class| class.name == usage.name)?;
+
+        let location = Location::new(
+            Url::from_file_path(absolute(&stylesheet)).ok()?,
+            class_range(&class, &stylesheet_content),
+        );
+        Some(GotoDefinitionResponse::Scalar(location))
+    }
+
+    /// `.foo` in a stylesheet -> every JSX usage of it, among the scripts the analysis scans
+    fn references(&self, params: ReferenceParams) -> error::Result<Option<Vec<Location>>> {
+        let position = params.text_document_position.position;
+        let Ok(stylesheet) = params.text_document_position.text_document.uri.to_file_path() else {
+            return Ok(None);
+        };
+        let content = self.read(&stylesheet);
+        let Some(class) = class_at_position(&content, &stylesheet, position) else {
+            return Ok(None);
+        };
+        let is_module = stylesheet.to_string_lossy().contains(".module.");
+        let stylesheet = absolute(&stylesheet);
+
+        let mut locations = Vec::new();
+        for file in source_files(&FsFiles::new(&self.root), &self.config, should_process_js_file) {
+            let file = file?;
+            let js_path = file.path.as_path();
+            let js_content = self.open_document(js_path).cloned().unwrap_or(file.content);
+            let imports = find_style_imports(&js_content);
+            let bindings: HashSet<String> = imports
+                .iter()
+                .filter(|import| resolve_style_import(js_path, &import.source).is_some_and(|p| absolute(&p) == stylesheet))
+                .map(|import| import.binding.clone())
+                .collect();
+
+            for usage in find_class_usages(&js_content, &js_path.to_string_lossy()) {
+                let matches_sheet = match &usage.binding {
+                    Some(binding) => bindings.contains(binding),
+                    None => !is_module,
+                };
+                if matches_sheet && usage.name == class {
+                    if let Ok(uri) = Url::from_file_path(absolute(js_path)) {
+                        locations.push(Location::new(uri, usage_range(&usage, &js_content)));
+                    }
+                }
+            }
+        }
+
+        Ok(Some(locations))
+    }
+
+    /// Offer to delete the rule block behind an unused-class diagnostic
+    fn code_actions(&self, params: CodeActionParams) -> Option<Vec<CodeActionOrCommand>> {
+        let result = self.result.as_ref()?;
+        let path = params.text_document.uri.to_file_path().ok()?;
+        let content = self.read(&path);
+        let used_in_file: Vec<&CssClass> = result.used_classes
+            .iter()
+            .filter(|class| absolute(Path::new(&class.file_path)) == absolute(&path))
+            .collect();
+
+        let mut actions = Vec::new();
+        for diagnostic in &params.context.diagnostics {
+            if diagnostic.code != Some(NumberOrString::String(UNUSED_CLASS_CODE.to_string())) {
+                continue;
+            }
+
+            let line_number = diagnostic.range.start.line as usize + 1;
+            let Some((start, end)) = rule_block_range(&content, line_number) else {
+                continue;
+            };
+
+            // Never delete a block that still contains used (nested) classes
+            let block_has_used_class = used_in_file
+                .iter()
+                .any(|class| class.line_number >= start && class.line_number <= end);
+            if block_has_used_class {
+                continue;
+            }
+
+            let range = Range::new(Position::new(start as u32 - 1, 0), Position::new(end as u32, 0));
+            let edit = WorkspaceEdit {
+                changes: Some(HashMap::from([(params.text_document.uri.clone(), vec![TextEdit::new(range, String::new())])])),
+                ..Default::default()
+            };
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title: format!("Remove unused rule ({})", diagnostic.message.trim_start_matches("Unused CSS class ")),
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic.clone()]),
+                edit: Some(edit),
+                ..Default::default()
+            }));
+        }
+
+        Some(actions)
+    }
+
+    /// Read a file, preferring the editor's unsaved text when the document is open
+    fn read(&self, path: &Path) -> String {
+        self.open_document(path)
+            .cloned()
+            .unwrap_or_else(|| fs::read_to_string(path).unwrap_or_default())
+    }
+
+    /// The editor's text of `path`, when it's open
+    fn open_document(&self, path: &Path) -> Option<&String> {
+        self.documents.get(path).or_else(|| self.documents.get(&absolute(path)))
+    }
+}
+
+fn unused_class_diagnostic(class: &CssClass, content: &str) -> Diagnostic {
+    Diagnostic {
+        range: class_range(class, content),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String(UNUSED_CLASS_CODE.to_string())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message: format!("Unused CSS class `.{}`", class.name),
+        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+        ..Default::default()
+    }
+}
+
+fn dead_code_class_diagnostic(class: &CssClass, content: &str) -> Diagnostic {
+    Diagnostic {
+        range: class_range(class, content),
+        severity: Some(DiagnosticSeverity::INFORMATION),
+        code: Some(NumberOrString::String(DEAD_CODE_CLASS_CODE.to_string())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message: format!("CSS class `.{}` is only used by components no entry point imports", class.name),
+        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+        ..Default::default()
+    }
+}
+
+fn dead_component_diagnostic(component: &DeadComponent) -> Diagnostic {
+    let mut message = "Dead component - no entry point reaches this file through imports".to_string();
+    if !component.stylesheets.is_empty() {
+        message.push_str(&format!("; its styles in {} only serve dead code", component.stylesheets.join(", ")));
+    }
+
+    Diagnostic {
+        range: Range::new(Position::new(0, 0), Position::new(0, u32::MAX)),
+        severity: Some(DiagnosticSeverity::INFORMATION),
+        code: Some(NumberOrString::String(DEAD_COMPONENT_CODE.to_string())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message,
+        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+        ..Default::default()
+    }
+}
+
+fn unreachable_stylesheet_diagnostic() -> Diagnostic {
+    Diagnostic {
+        range: Range::new(Position::new(0, 0), Position::new(0, u32::MAX)),
+        severity: Some(DiagnosticSeverity::INFORMATION),
+        code: Some(NumberOrString::String(UNREACHABLE_STYLESHEET_CODE.to_string())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message: "Unreachable stylesheet - no entry point loads it through imports, @import or @use".to_string(),
+        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+        ..Default::default()
+    }
+}
+
+fn unused_stylesheet_file_diagnostic(file: &UnusedStylesheetFile) -> Diagnostic {
+    let message = match file.reason {
+        UnusedFileReason::NotImported => "No component imports this CSS module - the whole file can go",
+        UnusedFileReason::AllClassesUnused => "None of this CSS module's classes are used - the whole file can go",
+        UnusedFileReason::NoClassDefinitions => "This stylesheet defines no classes and no other stylesheet imports it",
+    };
+
+    Diagnostic {
+        range: Range::new(Position::new(0, 0), Position::new(0, u32::MAX)),
+        severity: Some(DiagnosticSeverity::INFORMATION),
+        code: Some(NumberOrString::String(UNUSED_STYLESHEET_FILE_CODE.to_string())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message: message.to_string(),
+        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+        ..Default::default()
+    }
+}
+
+fn undefined_reference_diagnostic(reference: &UndefinedReference, content: &str) -> Diagnostic {
+    let text = content.lines().nth(reference.line_number.saturating_sub(1)).unwrap_or("");
+    let line = reference.line_number.saturating_sub(1) as u32;
+    let start = utf16_offset(text, reference.column.saturating_sub(1));
+    let mut message = format!("CSS class `.{}` is not defined", reference.name);
+    if let Some(suggestion) = &reference.suggestion {
+        message.push_str(&format!(" - did you mean `.{}`?", suggestion));
+    }
+
+    Diagnostic {
+        range: Range::new(Position::new(line, start), Position::new(line, start + utf16_len(&reference.name))),
+        severity: Some(DiagnosticSeverity::WARNING),
+        code: Some(NumberOrString::String(UNDEFINED_CLASS_CODE.to_string())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+fn duplicate_rule_diagnostic(group: &DuplicateRuleGroup, location: &RuleLocation) -> Diagnostic {
+    let line = location.start_line.saturating_sub(1) as u32;
+
+    Diagnostic {
+        range: Range::new(Position::new(line, 0), Position::new(line, u32::MAX)),
+        severity: Some(DiagnosticSeverity::HINT),
+        code: Some(NumberOrString::String(DUPLICATE_RULE_CODE.to_string())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message: format!(
+            "Same declarations as {} other rule(s) - consider a shared `.{}` utility",
+            group.occurrences.len() - 1,
+            group.suggested_name
+        ),
+        ..Default::default()
+    }
+}
+
+fn complexity_diagnostic(warning: &ComplexityWarning) -> Diagnostic {
+    let start = warning.start_line.saturating_sub(1) as u32;
+    let end = warning.end_line.saturating_sub(1).max(warning.start_line.saturating_sub(1)) as u32;
+
+    Diagnostic {
+        range: Range::new(Position::new(start, 0), Position::new(end, u32::MAX)),
+        severity: Some(diagnostic_severity(&warning.severity)),
+        code: Some(NumberOrString::String(warning.rule_id.clone())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message: format!("{}\n💡 {}", warning.pattern, warning.suggestion),
+        ..Default::default()
+    }
+}
+
+fn stylesheet_diagnostic(warning: &StylesheetWarning) -> Diagnostic {
+    let line = warning.line_number.saturating_sub(1) as u32;
+
+    Diagnostic {
+        range: Range::new(Position::new(line, 0), Position::new(line, u32::MAX)),
+        severity: Some(diagnostic_severity(&warning.severity)),
+        code: Some(NumberOrString::String(format!("{:?}", warning.warning_type))),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message: format!("{}\n💡 {}", warning.pattern, warning.suggestion),
+        ..Default::default()
+    }
+}
+
+fn unused_suppression_diagnostic(suppression: &Suppression) -> Diagnostic {
+    let line = suppression.line_number.saturating_sub(1) as u32;
+
+    Diagnostic {
+        range: Range::new(Position::new(line, 0), Position::new(line, u32::MAX)),
+        severity: Some(DiagnosticSeverity::HINT),
+        code: Some(NumberOrString::String(UNUSED_SUPPRESSION_CODE.to_string())),
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message: "This rustbrother-ignore comment doesn't silence anything".to_string(),
+        tags: Some(vec![DiagnosticTag::UNNECESSARY]),
+        ..Default::default()
+    }
+}
+
+fn diagnostic_severity(severity: &WarningSeverity) -> DiagnosticSeverity {
+    match severity {
+        WarningSeverity::High => DiagnosticSeverity::WARNING,
+        WarningSeverity::Medium => DiagnosticSeverity::INFORMATION,
+        WarningSeverity::Low => DiagnosticSeverity::HINT,
+    }
+}
+
+/// Range of a class name on its definition line, falling back to the whole line for
+/// `&` concatenations where the full name doesn't appear literally
+fn class_range(class: &CssClass, content: &str) -> Range {
+    let line = class.line_number.saturating_sub(1);
+    let text = content.lines().nth(line).unwrap_or("");
+    let selector = format!(".{}", class.name);
+
+    match text.find(&selector) {
+        Some(start) => {
+            let start = utf16_len(&text[..start]);
+            Range::new(
+                Position::new(line as u32, start),
+                Position::new(line as u32, start + utf16_len(&selector)),
+            )
+        }
+        None => {
+            let indent = utf16_len(&text[..text.len() - text.trim_start().len()]);
+            let end = utf16_len(text.split('{').next().unwrap_or("").trim_end());
+            Range::new(Position::new(line as u32, indent), Position::new(line as u32, end.max(indent)))
+        }
+    }
+}
+
+/// Range of a usage in `content`, the file it was found in
+fn usage_range(usage: &ClassUsage, content: &str) -> Range {
+    let text = content.lines().nth(usage.line_number - 1).unwrap_or("");
+    let line = usage.line_number as u32 - 1;
+    let start = utf16_offset(text, usage.column - 1);
+    Range::new(Position::new(line, start), Position::new(line, start + utf16_len(&usage.name)))
+}
+
+fn usage_contains(usage: &ClassUsage, content: &str, position: Position) -> bool {
+    let range = usage_range(usage, content);
+    range.start.line == position.line && range.start.character <= position.character && position.character <= range.end.character
+}
+
+/// Pick the class defined on the cursor's line, preferring the one under the cursor
+fn class_at_position(content: &str, stylesheet: &Path, position: Position) -> Option<String> {
+    let line_number = position.line as usize + 1;
+    let classes: Vec<CssClass> = parse_stylesheet(content, &stylesheet.to_string_lossy())
+        .ok()?
+        .into_iter()
+        .filter(|class| class.line_number == line_number)
+        .collect();
+
+    let text = content.lines().nth(position.line as usize).unwrap_or("");
+    let word = word_at(text, char_index(text, position.character));
+
+    classes
+        .iter()
+        .find(|class| class.name == word.trim_start_matches('.'))
+        .or_else(|| classes.iter().find(|class| word.starts_with('&') && class.name.ends_with(&word[1..])))
+        .or_else(|| classes.last())
+        .map(|class| class.name.clone())
+}
+
+/// The selector-ish word (`.foo`, `&_bar`) around a character index
+fn word_at(text: &str, character: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let is_word = |c: &char| c.is_alphanumeric() || matches!(c, '_' | '-' | '&' | '.');
+    let index = character.min(chars.len());
+
+    let start = chars[..index].iter().rposition(|c| !is_word(c)).map_or(0, |i| i + 1);
+    let end = chars[index..].iter().position(|c| !is_word(c)).map_or(chars.len(), |i| index + i);
+
+    // Keep only the last `.class` segment of compound selectors like `.a.b`
+    let word: String = chars[start..end].iter().collect();
+    match word.rfind('.') {
+        Some(dot) if dot > 0 => word[dot..].to_string(),
+        _ => word,
+    }
+}
+
+/// LSP positions count UTF-16 code units, the only encoding every client supports; findings count characters.
+/// The UTF-16 offset of the character at `chars` in `text`.
+fn utf16_offset(text: &str, chars: usize) -> u32 {
+    text.chars().take(chars).map(char::len_utf16).sum::<usize>() as u32
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+/// The character index in `text` at a UTF-16 offset
+fn char_index(text: &str, utf16_offset: u32) -> usize {
+    let mut units = 0;
+    text.chars()
+        .take_while(|c| {
+            units += c.len_utf16() as u32;
+            units <= utf16_offset
+        })
+        .count()
+}
+
+fn workspace_root(params: &InitializeParams) -> Option<PathBuf> {
+    params
+        .workspace_folders
+        .as_ref()
+        .and_then(|folders| folders.first())
+        .and_then(|folder| folder.uri.to_file_path().ok())
+        .or_else(|| {
+            #[allow(deprecated)]
+            params.root_uri.as_ref().and_then(|uri| uri.to_file_path().ok())
+        })
+}
+
+fn publish(connection: &Connection, path: &Path, diagnostics: Vec<Diagnostic>) -> anyhow::Result<()> {
+    let Ok(uri) = Url::from_file_path(absolute(path)) else {
+        return Ok(());
+    };
+    let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+    connection.sender.send(Message::Notification(Notification::new(PublishDiagnostics::METHOD.to_string(), params)))?;
+    Ok(())
+}
+
+fn show_message(connection: &Connection, typ: MessageType, message: String) -> anyhow::Result<()> {
+    let params = ShowMessageParams { typ, message };
+    connection.sender.send(Message::Notification(Notification::new(ShowMessage::METHOD.to_string(), params)))?;
+    Ok(())
+}
+
+fn log_message(connection: &Connection, typ: MessageType, message: String) -> anyhow::Result<()> {
+    let params = LogMessageParams { typ, message };
+    connection.sender.send(Message::Notification(Notification::new(LogMessage::METHOD.to_string(), params)))?;
+    Ok(())
+}
+
+fn parse_params<P: serde::de::DeserializeOwned>(request: Request) -> anyhow::Result<P> {
+    Ok(serde_json::from_value(request.params)?)
+}
+
+/// The params of a notification, or `None` after logging why they couldn't be parsed
+fn notification_params<P: serde::de::DeserializeOwned>(connection: &Connection, notification: Notification) -> anyhow::Result<Option<P>> {
+    match serde_json::from_value(notification.params) {
+        Ok(params) => Ok(Some(params)),
+        Err(error) => {
+            log_message(connection, MessageType::WARNING, format!("Ignoring malformed {} notification: {}", notification.method, error))?;
+            Ok(None)
+        }
+    }
+}
+
+fn method_not_found(id: RequestId, method: &str) -> Response {
+    Response::new_err(id, lsp_server::ErrorCode::MethodNotFound as i32, format!("Unsupported method: {}", method))
+}
+
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir().map(|dir| dir.join(path)).unwrap_or_else(|_| path.to_path_buf())
+    }
+}
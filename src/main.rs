@@ -2,12 +2,15 @@
 // It handles command line arguments and calls the library functions
 
 // Import the clap crate for command line argument parsing
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use colored::*; // For colored terminal output
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // Import our library functions
-use rustbrother::{analyze_directory, generate_report, AnalysisConfig};
+use rustbrother::{analyze_directory_with_progress, generate_report_with_config, AnalysisConfig, AnalysisResult};
+use rustbrother::{StderrReporter, Verbosity};
+use rustbrother::{config, fixer, reporter};
+use rustbrother::edits::PendingEdits;
 
 // Define the command line interface using clap
 // The #[derive(Parser)] macro automatically generates argument parsing code
@@ -15,12 +18,155 @@ use rustbrother::{analyze_directory, generate_report, AnalysisConfig};
 #[command(name = "rustbrother")]
 #[command(about = "Hunt down unused CSS in React components")]
 #[command(version = "0.1.0")]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    // Running without a subcommand is the same as `analyze`
+    #[command(flatten)]
+    analyze: AnalyzeArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Find unused CSS and complexity warnings (the default)
+    Analyze(AnalyzeArgs),
+
+    /// Remove the rule blocks of unused classes from stylesheets
+    Fix {
+        #[command(flatten)]
+        target: TargetArgs,
+
+        #[command(flatten)]
+        log: LogArgs,
+
+        /// Only list the rules that would be removed
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Show where a class is defined, every reference and why it's used or unused
+    Explain {
+        /// The class to explain (with or without the leading dot)
+        class: String,
+
+        #[command(flatten)]
+        target: TargetArgs,
+    },
+
+    /// Show per-directory metrics
+    Stats {
+        #[command(flatten)]
+        target: TargetArgs,
+
+        #[command(flatten)]
+        log: LogArgs,
+
+        /// Output format: text or json
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Compare two JSON reports: added and removed unused classes, warning counts and per-directory deltas
+    Compare {
+        /// The older report (written with --format json)
+        old: PathBuf,
+
+        /// The newer report
+        new: PathBuf,
+
+        /// Output format: text, json or markdown
+        #[arg(short, long, default_value = "text")]
+        format: String,
+    },
+
+    /// Browse findings interactively; safelist classes or delete their rules, reviewed as a diff on exit
+    Tui {
+        #[command(flatten)]
+        target: TargetArgs,
+
+        #[command(flatten)]
+        log: LogArgs,
+    },
+
+    /// Print the JSON Schema of the `--format json` report
+    Schema,
+
+    /// Write a starter rustbrother.json config
+    Init {
+        /// Directory to write the config into
+        #[arg(value_name = "DIR", default_value = ".")]
+        path: PathBuf,
+
+        /// Overwrite an existing config
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Run a Language Server Protocol server over stdio for editor integration
+    Lsp {
+        #[command(flatten)]
+        target: TargetArgs,
+    },
+}
+
+/// The directory to work on and where its config comes from
+#[derive(Args)]
+struct TargetArgs {
     /// The directory to analyze (e.g., ./src/components)
-    #[arg(short, long, value_name = "DIR")]
+    #[arg(value_name = "DIR", default_value = ".")]
     path: PathBuf,
 
-    /// Output format: text, json, or html
+    /// Same as the positional directory (kept for existing scripts)
+    #[arg(short = 'p', long = "path", value_name = "DIR", hide = true)]
+    path_flag: Option<PathBuf>,
+
+    /// Config file to use instead of the nearest rustbrother.json
+    #[arg(short, long, value_name = "FILE")]
+    config: Option<PathBuf>,
+}
+
+/// How much progress to print; it always goes to stderr so reports on stdout stay clean
+/// Only the commands that run a whole analysis take these.
+#[derive(Args)]
+struct LogArgs {
+    /// Print no progress
+    #[arg(short, long, conflicts_with_all = ["verbose", "debug"])]
+    quiet: bool,
+
+    /// Show verbose output
+    #[arg(short, long)]
+    verbose: bool,
+
+    /// Show verbose output and how long each phase took
+    #[arg(long)]
+    debug: bool,
+}
+
+impl LogArgs {
+    fn reporter(&self) -> StderrReporter {
+        StderrReporter::new(if self.debug {
+            Verbosity::Debug
+        } else if self.verbose {
+            Verbosity::Verbose
+        } else if self.quiet {
+            Verbosity::Quiet
+        } else {
+            Verbosity::Normal
+        })
+    }
+}
+
+#[derive(Args)]
+struct AnalyzeArgs {
+    #[command(flatten)]
+    target: TargetArgs,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    /// Output format: text, json, html, markdown, junit or checkstyle
     #[arg(short, long, default_value = "text")]
     format: String,
 
@@ -28,13 +174,64 @@ struct Cli {
     #[arg(short, long, value_name = "FILE")]
     output: Option<PathBuf>,
 
-    /// Include CSS modules in analysis
-    #[arg(long, default_value = "true")]
-    css_modules: bool,
+    /// Include CSS modules in analysis (overrides the config file)
+    #[arg(long, value_name = "BOOL")]
+    css_modules: Option<bool>,
 
-    /// Show verbose output
-    #[arg(short, long)]
-    verbose: bool,
+    /// Entry point for reachability analysis (repeatable; added to `entry_points` from the config file)
+    #[arg(long = "entry", value_name = "PATH")]
+    entry_points: Vec<String>,
+
+    /// Only report findings in files changed since this git ref, and the components/stylesheets they import or are imported by
+    #[arg(long, value_name = "REF")]
+    since: Option<String>,
+
+    /// Directory of HTML templates overriding the built-in ones of the same name
+    #[arg(long, value_name = "DIR")]
+    template_dir: Option<PathBuf>,
+
+    /// Leave the generation time out of JSON and HTML reports so identical trees give identical reports
+    #[arg(long)]
+    no_timestamp: bool,
+
+    /// Fail instead of skipping files that can't be read or parsed
+    #[arg(long)]
+    strict: bool,
+}
+
+impl TargetArgs {
+    fn path(&self) -> &Path {
+        self.path_flag.as_deref().unwrap_or(&self.path)
+    }
+
+    /// The config file edits go to: the explicit one, the nearest rustbrother.json, or a new one in the directory
+    fn config_path(&self) -> PathBuf {
+        self.config
+            .clone()
+            .or_else(|| config::find_config_file(self.path()))
+            .unwrap_or_else(|| self.path().join(config::CONFIG_FILE_NAME))
+    }
+
+    /// Load the explicit config, the nearest rustbrother.json, or the defaults
+    fn load_config(&self) -> anyhow::Result<AnalysisConfig> {
+        match self.config.clone().or_else(|| config::find_config_file(self.path())) {
+            Some(config_path) => config::load_config(&config_path),
+            None => Ok(AnalysisConfig::default()),
+        }
+    }
+
+    /// Run the analysis with progress on stderr
+    fn analyze(&self, config: &AnalysisConfig, log: &LogArgs) -> anyhow::Result<AnalysisResult> {
+        Ok(analyze_directory_with_progress(self.path(), config, &log.reporter())?)
+    }
+
+    fn ensure_exists(&self) {
+        // Check if the path exists
+        if !self.path().exists() {
+            eprintln!("{}", "Error: Directory does not exist".red().bold());
+            std::process::exit(1);
+        }
+    }
 }
 
 // The main function - this is where the program starts
@@ -42,47 +239,83 @@ fn main() -> anyhow::Result<()> {
     // Parse command line arguments
     let cli = Cli::parse();
 
-    // Print what we're doing if verbose mode is on
-    if cli.verbose {
-        println!("{}", "Starting CSS analysis...".blue().bold());
-        println!("Analyzing directory: {}", cli.path.display());
-        println!("{}", "Complexity analysis enabled".yellow());
+    match cli.command {
+        None => run_analyze(cli.analyze),
+        Some(Command::Analyze(args)) => run_analyze(args),
+        Some(Command::Fix { target, log, dry_run }) => run_fix(target, log, dry_run),
+        Some(Command::Explain { class, target }) => {
+            target.ensure_exists();
+            let config = target.load_config()?;
+            let explanation = rustbrother::explain_class(target.path(), &config, &class)?;
+            print!("{}", reporter::format_explanation(&explanation));
+            Ok(())
+        }
+        Some(Command::Stats { target, log, format }) => {
+            target.ensure_exists();
+            let config = target.load_config()?;
+            let result = target.analyze(&config, &log)?;
+            let stats = rustbrother::directory_stats(&result);
+            print!("{}", reporter::format_directory_stats(&stats, &format)?);
+            Ok(())
+        }
+        Some(Command::Compare { old, new, format }) => {
+            let comparison = rustbrother::compare_reports(&rustbrother::load_report(&old)?, &rustbrother::load_report(&new)?);
+            print!("{}", reporter::format_comparison(&comparison, &format)?);
+            Ok(())
+        }
+        Some(Command::Tui { target, log }) => run_tui(target, log),
+        Some(Command::Schema) => {
+            print!("{}", rustbrother::report_json_schema()?);
+            Ok(())
+        }
+        Some(Command::Init { path, force }) => run_init(&path, force),
+        // The language server owns stdin/stdout, so it bypasses the report flow entirely
+        Some(Command::Lsp { target }) => {
+            let config = target.load_config()?;
+            rustbrother::lsp::run_server(Some(target.path().to_path_buf()), config)
+        }
     }
+}
 
-    // Check if the path exists
-    if !cli.path.exists() {
-        eprintln!("{}", "Error: Directory does not exist".red().bold());
-        std::process::exit(1);
-    }
+fn run_analyze(args: AnalyzeArgs) -> anyhow::Result<()> {
+    let progress = args.log.reporter();
+    // Print what we're doing if verbose mode is on
+    progress.log(Verbosity::Verbose, &"Starting CSS analysis...".blue().bold().to_string());
+    progress.log(Verbosity::Verbose, &format!("Analyzing directory: {}", args.target.path().display()));
 
-    // Create analysis configuration based on CLI arguments
-    let config = AnalysisConfig {
-        include_css_modules: cli.css_modules,
-        include_styled_components: false, // We'll add this later
-        ignore_patterns: vec![
-            "node_modules".to_string(),
-            ".git".to_string(),
-            "dist".to_string(),
-            "build".to_string(),
-        ],
-        enable_complexity_warnings: true, // Always enabled
-        complexity_threshold: rustbrother::WarningSeverity::Medium, // Always medium
-    };
+    args.target.ensure_exists();
+
+    // Create analysis configuration from the config file and CLI overrides
+    let mut config = args.target.load_config()?;
+    if let Some(css_modules) = args.css_modules {
+        config.include_css_modules = css_modules;
+    }
+    config.entry_points.extend(args.entry_points);
+    if args.no_timestamp {
+        config.report_timestamp = false;
+    }
+    if args.strict {
+        config.strict = true;
+    }
+    if args.template_dir.is_some() {
+        config.template_dir = args.template_dir;
+    }
 
     // Run the analysis using our library
-    let result = analyze_directory(&cli.path, &config)?;
+    let result = match &args.since {
+        Some(git_ref) => rustbrother::analyze_changes_with_progress(args.target.path(), &config, git_ref, &progress)?,
+        None => analyze_directory_with_progress(args.target.path(), &config, &progress)?,
+    };
 
     // Generate the report in the requested format
-    let report = generate_report(&result, &cli.format)?;
+    let report = generate_report_with_config(&result, &args.format, &config)?;
 
     // Output the report
-    match cli.output {
+    match args.output {
         Some(output_path) => {
             // Write to file
             std::fs::write(&output_path, report)?;
-            if cli.verbose {
-                println!("{}", format!("Report saved to: {}", output_path.display()).green());
-            }
+            progress.log(Verbosity::Verbose, &format!("Report saved to: {}", output_path.display()).green().to_string());
         }
         None => {
             // Print to stdout
@@ -91,12 +324,59 @@ fn main() -> anyhow::Result<()> {
     }
 
     // Print summary if verbose
-    if cli.verbose {
-        println!("{}", "Analysis complete!".green().bold());
-        println!("Total files scanned: {}", result.total_files_scanned);
-        println!("Unused CSS classes found: {}", result.unused_classes.len());
-        println!("Complexity warnings found: {}", result.complexity_warnings.len());
+    progress.log(Verbosity::Verbose, &format!("Total files scanned: {}", result.total_files_scanned));
+    progress.log(Verbosity::Verbose, &format!("Unused CSS classes found: {}", result.unused_classes.len()));
+    progress.log(Verbosity::Verbose, &format!("Complexity warnings found: {}", result.complexity_warnings.len()));
+
+    Ok(())
+}
+
+fn run_fix(target: TargetArgs, log: LogArgs, dry_run: bool) -> anyhow::Result<()> {
+    target.ensure_exists();
+    let config = target.load_config()?;
+    let result = target.analyze(&config, &log)?;
+    let removals = fixer::plan_unused_rule_removals(&result)?;
+
+    if removals.is_empty() {
+        println!("{}", "🎉 Nothing to purge - no removable unused rules found".green());
+        return Ok(());
+    }
+
+    for removal in &removals {
+        println!("  🗑️  {}:{}-{} .{}", removal.file_path, removal.start_line, removal.end_line, removal.class_name);
+    }
+
+    if dry_run {
+        println!("{}", format!("{} rule(s) would be purged (dry run)", removals.len()).yellow());
+    } else {
+        let files = fixer::apply_rule_removals(&removals)?;
+        println!("{}", format!("⚔️  Purged {} rule(s) from {} file(s)", removals.len(), files).green().bold());
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+fn run_tui(target: TargetArgs, log: LogArgs) -> anyhow::Result<()> {
+    target.ensure_exists();
+    let config = target.load_config()?;
+    let result = target.analyze(&config, &log)?;
+    let edits = PendingEdits::new(target.config_path());
+
+    match rustbrother::tui::run(result, edits)? {
+        Some(files) => println!("{}", format!("⚔️  Applied the queued edits to {} file(s)", files).green().bold()),
+        None => println!("No files changed"),
+    }
+    Ok(())
+}
+
+fn run_init(path: &Path, force: bool) -> anyhow::Result<()> {
+    let config_path = path.join(config::CONFIG_FILE_NAME);
+    if config_path.exists() && !force {
+        eprintln!("{}", format!("Error: {} already exists (use --force to overwrite)", config_path.display()).red().bold());
+        std::process::exit(1);
+    }
+
+    std::fs::write(&config_path, config::starter_config()?)?;
+    println!("{}", format!("Wrote {}", config_path.display()).green());
+    Ok(())
+}
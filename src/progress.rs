@@ -0,0 +1,188 @@
+// src/progress.rs - Progress and log output of an analysis
+// The analysis reports what it is doing as `ProgressEvent`s to a `ProgressReporter` instead of printing, so reports
+// written to stdout stay clean. The CLI uses `StderrReporter`; embedders pass any `Fn(&ProgressEvent)` to show their own.
+
+use colored::*;
+use std::time::{Duration, Instant};
+
+/// How much progress output to show; each level includes the ones before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+    /// Nothing
+    Quiet,
+    /// One line per phase
+    #[default]
+    Normal,
+    /// Plus what each phase found
+    Verbose,
+    /// Plus how long each phase took
+    Debug,
+}
+
+/// A step of the analysis, in the order they run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    ChangedFiles,
+    CssClasses,
+    ClassUsage,
+    UndefinedReferences,
+    CustomProperties,
+    CustomPropertyUsage,
+    Complexity,
+    StylesheetRules,
+    UsagePatterns,
+    Reachability,
+    StylesheetFiles,
+    Suppressions,
+    FileDiagnostics,
+}
+
+impl Phase {
+    /// Stable kebab-case name, for timings and machine-readable progress
+    pub fn id(self) -> &'static str {
+        match self {
+            Phase::ChangedFiles => "changed-files",
+            Phase::CssClasses => "css-classes",
+            Phase::ClassUsage => "class-usage",
+            Phase::UndefinedReferences => "undefined-references",
+            Phase::CustomProperties => "custom-properties",
+            Phase::CustomPropertyUsage => "custom-property-usage",
+            Phase::Complexity => "complexity",
+            Phase::StylesheetRules => "stylesheet-rules",
+            Phase::UsagePatterns => "usage-patterns",
+            Phase::Reachability => "reachability",
+            Phase::StylesheetFiles => "stylesheet-files",
+            Phase::Suppressions => "suppressions",
+            Phase::FileDiagnostics => "file-diagnostics",
+        }
+    }
+
+    /// What the phase is doing, as shown at the normal level
+    pub fn message(self) -> &'static str {
+        match self {
+            Phase::ChangedFiles => "🔀 Collecting changed files...",
+            Phase::CssClasses => "🔍 Scanning CSS files for class definitions...",
+            Phase::ClassUsage => "🔍 Scanning JS/React files for class usage...",
+            Phase::UndefinedReferences => "🔍 Checking class references against their stylesheets...",
+            Phase::CustomProperties => "🔍 Scanning for CSS custom properties...",
+            Phase::CustomPropertyUsage => "🔍 Analyzing custom property usage...",
+            Phase::Complexity => "🔍 Analyzing code complexity patterns...",
+            Phase::StylesheetRules => "🔍 Inspecting stylesheet rules...",
+            Phase::UsagePatterns => "📊 Analyzing usage patterns...",
+            Phase::Reachability => "💀 Tracing imports from the entry points...",
+            Phase::StylesheetFiles => "📦 Looking for stylesheet files to delete...",
+            Phase::Suppressions => "🤫 Applying inline suppressions...",
+            Phase::FileDiagnostics => "🩺 Checking for files that couldn't be read...",
+        }
+    }
+}
+
+/// Something that happened during an analysis
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProgressEvent {
+    PhaseStarted(Phase),
+    PhaseFinished { phase: Phase, elapsed: Duration },
+    /// A detail worth showing at `level` and above (`Verbose` or `Debug`)
+    Log { level: Verbosity, message: String },
+    /// The whole analysis is done
+    Finished { elapsed: Duration },
+}
+
+/// Receives the progress of an analysis; implemented for every `Fn(&ProgressEvent)`
+pub trait ProgressReporter {
+    fn report(&self, event: &ProgressEvent);
+}
+
+impl<F: Fn(&ProgressEvent)> ProgressReporter for F {
+    fn report(&self, event: &ProgressEvent) {
+        self(event)
+    }
+}
+
+/// Drops every event; what `analyze_directory` uses
+pub struct SilentReporter;
+
+impl ProgressReporter for SilentReporter {
+    fn report(&self, _event: &ProgressEvent) {}
+}
+
+/// Writes progress to stderr, filtered by `verbosity`
+pub struct StderrReporter {
+    pub verbosity: Verbosity,
+}
+
+impl StderrReporter {
+    pub fn new(verbosity: Verbosity) -> Self {
+        StderrReporter { verbosity }
+    }
+
+    /// The line for `event` at this reporter's verbosity, if any
+    pub fn format(&self, event: &ProgressEvent) -> Option<String> {
+        match event {
+            ProgressEvent::PhaseStarted(phase) if self.verbosity >= Verbosity::Normal => Some(phase.message().to_string()),
+            ProgressEvent::PhaseFinished { phase, elapsed } if self.verbosity >= Verbosity::Debug => {
+                Some(format!("   ⏱️  {} took {}", phase.id(), format_duration(*elapsed)).dimmed().to_string())
+            }
+            ProgressEvent::Log { level, message } if self.verbosity >= *level && *level > Verbosity::Quiet => {
+                Some(format!("   {}", message))
+            }
+            ProgressEvent::Finished { elapsed } if self.verbosity >= Verbosity::Debug => {
+                Some(format!("✅ Analysis complete in {}", format_duration(*elapsed)))
+            }
+            ProgressEvent::Finished { .. } if self.verbosity >= Verbosity::Normal => Some(String::from("✅ Analysis complete!")),
+            _ => None,
+        }
+    }
+
+    /// Print a message of the CLI itself at `level`
+    pub fn log(&self, level: Verbosity, message: &str) {
+        self.report(&ProgressEvent::Log { level, message: message.to_string() });
+    }
+}
+
+impl ProgressReporter for StderrReporter {
+    fn report(&self, event: &ProgressEvent) {
+        if let Some(line) = self.format(event) {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+fn format_duration(elapsed: Duration) -> String {
+    format!("{:.1} ms", elapsed.as_secs_f64() * 1000.0)
+}
+
+/// Times the phases of one analysis: starting a phase finishes the previous one
+pub(crate) struct PhaseTimer<'a> {
+    progress: &'a dyn ProgressReporter,
+    started: Instant,
+    current: Option<(Phase, Instant)>,
+}
+
+impl<'a> PhaseTimer<'a> {
+    pub(crate) fn new(progress: &'a dyn ProgressReporter) -> Self {
+        PhaseTimer { progress, started: Instant::now(), current: None }
+    }
+
+    pub(crate) fn start(&mut self, phase: Phase) {
+        self.end_phase();
+        self.progress.report(&ProgressEvent::PhaseStarted(phase));
+        self.current = Some((phase, Instant::now()));
+    }
+
+    pub(crate) fn log(&self, level: Verbosity, message: String) {
+        self.progress.report(&ProgressEvent::Log { level, message });
+    }
+
+    /// End the current phase without starting another
+    pub(crate) fn end_phase(&mut self) {
+        if let Some((phase, started)) = self.current.take() {
+            self.progress.report(&ProgressEvent::PhaseFinished { phase, elapsed: started.elapsed() });
+        }
+    }
+
+    pub(crate) fn finish(mut self) {
+        self.end_phase();
+        self.progress.report(&ProgressEvent::Finished { elapsed: self.started.elapsed() });
+    }
+}
@@ -1,16 +1,219 @@
 // src/reporter.rs - Template-based output formatting
 // This module handles generating reports using HTML templates
 
-use crate::{AnalysisResult, CssClass, ComplexityWarning, ComplexityWarningType, WarningSeverity};
+use crate::{AnalysisConfig, AnalysisResult, ClassExplanation, CountDelta, CssClass, ComplexityWarning, ComplexityWarningType, DirectoryStats, DuplicateRuleGroup, ReportComparison, StylesheetWarning, SafelistEntry, StylesheetWarningType, Suppression, UndefinedReference, UnusedFileReason, UnusedStylesheetFile, UsageKind, WarningSeverity};
+use crate::json_report::JsonReport;
+use crate::templates::render_html_report;
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
 
 /// Generate a report in the specified format
 pub fn generate_report(result: &AnalysisResult, format: &str) -> anyhow::Result<String> {
+    generate_report_with_config(result, format, &AnalysisConfig::default())
+}
+
+/// Generate a report, taking report settings such as `source_url_template` from `config`
+pub fn generate_report_with_config(result: &AnalysisResult, format: &str, config: &AnalysisConfig) -> anyhow::Result<String> {
+    match format.to_lowercase().as_str() {
+        "json" => generate_json_report(result, config.report_timestamp),
+        "html" => generate_html_report(result, config),
+        "markdown" | "md" => Ok(generate_markdown_report(result, config.source_url_template.as_deref())),
+        "junit" => Ok(generate_junit_report(result)),
+        "checkstyle" => Ok(generate_checkstyle_report(result)),
+        _ => generate_text_report(result), // Default to text
+    }
+}
+
+/// Format the result of `explain` for a single class
+pub fn format_explanation(explanation: &ClassExplanation) -> String {
+    let mut report = String::new();
+    
+    let verdict = if explanation.definitions.is_empty() {
+        "👻 Phantom (not defined)"
+    } else if explanation.is_used {
+        "✅ Active"
+    } else if explanation.used_only_by_dead_code {
+        "💀 Used only by dead code"
+    } else {
+        "🗑️  Corrupted remnant (unused)"
+    };
+    
+    report.push_str(&format!("🔎 .{} - {}\n", explanation.class_name, verdict));
+    report.push_str(&format!("   {}\n", explanation.reason));
+    
+    report.push_str("\n📜 Defined in:\n");
+    if explanation.definitions.is_empty() {
+        report.push_str("  (no stylesheet)\n");
+    }
+    for class in &explanation.definitions {
+        report.push_str(&format!("  • {}:{}\n", class.file_path, class.line_number));
+    }
+    
+    if let Some(class) = explanation.definitions.first() {
+        if !class.evidence.is_empty() {
+            report.push_str("\n🧾 Evidence:\n");
+        }
+        for evidence in &class.evidence {
+            report.push_str(&format!("  • {} at {}:{}\n", format_usage_kind(&evidence.kind), evidence.file_path, evidence.line_number));
+            report.push_str(&format!("     {}\n", evidence.pattern));
+        }
+    }
+    
+    report.push_str("\n🔗 Referenced from:\n");
+    if explanation.references.is_empty() {
+        report.push_str("  (no direct references)\n");
+    }
+    for usage in &explanation.references {
+        let via = format_reference(usage.binding.as_deref(), &usage.name);
+        report.push_str(&format!("  • {}:{}:{} ({})\n", usage.file_path, usage.line_number, usage.column, via));
+    }
+    
+    report
+}
+
+/// Format per-directory metrics as a text table or JSON
+pub fn format_directory_stats(stats: &[DirectoryStats], format: &str) -> anyhow::Result<String> {
+    if format.eq_ignore_ascii_case("json") {
+        return Ok(serde_json::to_string_pretty(stats)?);
+    }
+    
+    let width = stats.iter().map(|s| s.directory.len()).max().unwrap_or(0).max("Directory".len());
+    let mut report = String::new();
+    report.push_str("📊 Territory Statistics\n");
+    report.push_str("=======================\n\n");
+    report.push_str(&format!(
+        "{:<width$}  {:>6}  {:>7}  {:>6}  {:>6}  {:>7}  {:>4}  {:>5}  {:>8}\n",
+        "Directory", "Sheets", "Classes", "Used", "Unused", "Unused%", "Dead", "Vars", "Warnings",
+        width = width
+    ));
+    
+    for dir in stats {
+        let unused_percentage = if dir.total_classes > 0 {
+            (dir.unused_classes as f64 / dir.total_classes as f64 * 100.0).round()
+        } else {
+            0.0
+        };
+        report.push_str(&format!(
+            "{:<width$}  {:>6}  {:>7}  {:>6}  {:>6}  {:>6.0}%  {:>4}  {:>5}  {:>8}\n",
+            dir.directory, dir.css_files, dir.total_classes, dir.used_classes, dir.unused_classes,
+            unused_percentage, dir.dead_code_classes, dir.unused_custom_properties, dir.complexity_warnings,
+            width = width
+        ));
+    }
+    
+    Ok(report)
+}
+
+/// Format the result of `compare` as text, JSON or a Markdown summary for pull requests
+pub fn format_comparison(comparison: &ReportComparison, format: &str) -> anyhow::Result<String> {
     match format.to_lowercase().as_str() {
-        "json" => generate_json_report(result),
-        "html" => generate_html_report(result),
-        "text" | _ => generate_text_report(result), // Default to text
+        "json" => Ok(serde_json::to_string_pretty(comparison)?),
+        "markdown" | "md" => Ok(format_comparison_markdown(comparison)),
+        _ => Ok(format_comparison_text(comparison)),
+    }
+}
+
+fn format_comparison_text(comparison: &ReportComparison) -> String {
+    let mut report = String::new();
+    report.push_str("📈 RustBrother Trend Report\n");
+    report.push_str("===========================\n\n");
+    report.push_str(&format!("🗑️  Unused classes: {}\n", format_count_delta(&comparison.unused_classes)));
+    
+    for (title, classes) in [("🆕 Newly unused", &comparison.added_unused_classes), ("🧹 Cleaned up", &comparison.removed_unused_classes)] {
+        if !classes.is_empty() {
+            report.push_str(&format!("\n{} ({}):\n", title, classes.len()));
+            for class in classes {
+                report.push_str(&format!("  • .{} ({}:{})\n", class.name, class.file_path, class.line_number));
+            }
+        }
+    }
+    
+    report.push_str("\n⚠️  Complexity warnings by severity:\n");
+    for delta in &comparison.warnings_by_severity {
+        report.push_str(&format!("  {} {:<7} {}\n", severity_name_icon(&delta.name), delta.name, format_count_delta(delta)));
+    }
+    
+    if !comparison.warnings_by_type.is_empty() {
+        report.push_str("\n🔮 Complexity warnings by type:\n");
+        let width = comparison.warnings_by_type.iter().map(|d| d.name.len()).max().unwrap_or(0);
+        for delta in &comparison.warnings_by_type {
+            report.push_str(&format!("  {:<width$}  {}\n", delta.name, format_count_delta(delta), width = width));
+        }
+    }
+    
+    if !comparison.directories.is_empty() {
+        report.push_str("\n📁 Per directory (unused classes | complexity warnings):\n");
+        let width = comparison.directories.iter().map(|d| d.directory.len()).max().unwrap_or(0);
+        for directory in &comparison.directories {
+            report.push_str(&format!("  {:<width$}  {:<16} | {}\n", directory.directory,
+                format_count_delta(&directory.unused_classes), format_count_delta(&directory.complexity_warnings), width = width));
+        }
+    }
+    
+    report
+}
+
+fn format_comparison_markdown(comparison: &ReportComparison) -> String {
+    let mut report = String::new();
+    report.push_str("## 📈 RustBrother CSS trend\n\n");
+    report.push_str("| | Before | After | Change |\n");
+    report.push_str("|---|---:|---:|---:|\n");
+    report.push_str(&format!("| Unused classes | {} | {} | {} |\n", comparison.unused_classes.old, comparison.unused_classes.new, format_change(comparison.unused_classes.change)));
+    for delta in &comparison.warnings_by_severity {
+        report.push_str(&format!("| {} {} complexity warnings | {} | {} | {} |\n", severity_name_icon(&delta.name), delta.name, delta.old, delta.new, format_change(delta.change)));
+    }
+    
+    for (title, classes) in [("🆕 Newly unused classes", &comparison.added_unused_classes), ("🧹 Cleaned up", &comparison.removed_unused_classes)] {
+        if !classes.is_empty() {
+            report.push_str(&format!("\n### {} ({})\n\n", title, classes.len()));
+            for class in classes {
+                report.push_str(&format!("- `.{}` in `{}:{}`\n", class.name, class.file_path, class.line_number));
+            }
+        }
+    }
+    
+    if !comparison.warnings_by_type.is_empty() {
+        report.push_str("\n### Complexity warnings by type\n\n");
+        report.push_str("| Type | Before | After | Change |\n");
+        report.push_str("|---|---:|---:|---:|\n");
+        for delta in &comparison.warnings_by_type {
+            report.push_str(&format!("| {} | {} | {} | {} |\n", delta.name, delta.old, delta.new, format_change(delta.change)));
+        }
+    }
+    
+    if !comparison.directories.is_empty() {
+        report.push_str("\n### Per directory\n\n");
+        report.push_str("| Directory | Unused classes | Complexity warnings |\n");
+        report.push_str("|---|---:|---:|\n");
+        for directory in &comparison.directories {
+            report.push_str(&format!("| `{}` | {} | {} |\n", directory.directory,
+                format_count_delta(&directory.unused_classes), format_count_delta(&directory.complexity_warnings)));
+        }
+    }
+    
+    report
+}
+
+/// `12 → 9 (-3)`
+fn format_count_delta(delta: &CountDelta) -> String {
+    format!("{} → {} ({})", delta.old, delta.new, format_change(delta.change))
+}
+
+/// `+3`, `-3` or `±0`
+fn format_change(change: i64) -> String {
+    match change {
+        0 => "±0".to_string(),
+        change if change > 0 => format!("+{}", change),
+        change => change.to_string(),
+    }
+}
+
+fn severity_name_icon(severity: &str) -> &'static str {
+    match severity {
+        "High" => "🔴",
+        "Medium" => "🟡",
+        _ => "🟢",
     }
 }
 
@@ -23,18 +226,53 @@ fn generate_text_report(result: &AnalysisResult) -> anyhow::Result<String> {
     report.push_str("====================================\n\n");
     
     // Summary section
-    let total_classes = result.used_classes.len() + result.unused_classes.len();
+    let total_classes = count_classes(result);
     let unused_percentage = if total_classes > 0 {
         (result.unused_classes.len() as f64 / total_classes as f64 * 100.0).round()
     } else {
         0.0
     };
     
-    report.push_str(&format!("📊 Territory Analysis:\n"));
+    report.push_str("📊 Territory Analysis:\n");
     report.push_str(&format!("  Total CSS classes found: {}\n", total_classes));
+    let (definite, possible) = split_used_classes(&result.used_classes);
     report.push_str(&format!("  Active classes: {}\n", result.used_classes.len()));
+    report.push_str(&format!("    ✅ Definitely used: {}\n", definite.len()));
+    report.push_str(&format!("    🎲 Possibly used (dynamic): {}\n", possible.len()));
     report.push_str(&format!("  Corrupted remnants: {} ({:.0}%)\n", result.unused_classes.len(), unused_percentage));
+    if !result.dead_components.is_empty() {
+        report.push_str(&format!("  💀 Used only by dead code: {} (in {} dead component(s))\n", result.dead_code_classes.len(), result.dead_components.len()));
+    }
+    if !result.unreachable_stylesheets.is_empty() {
+        report.push_str(&format!("  🕸️  Unreachable stylesheets: {}\n", result.unreachable_stylesheets.len()));
+    }
+    if !result.unused_stylesheet_files.is_empty() {
+        report.push_str(&format!("  📦 Stylesheet files to delete: {} (~{} bytes)\n",
+            result.unused_stylesheet_files.len(), total_unused_file_bytes(result)));
+    }
     report.push_str(&format!("  Files patrolled: {}\n", result.total_files_scanned));
+    if !result.undefined_references.is_empty() {
+        report.push_str(&format!("  👻 Undefined references: {}\n", result.undefined_references.len()));
+    }
+    if !result.stylesheet_warnings.is_empty() {
+        let (high, medium, low) = count_stylesheet_warnings_by_severity(&result.stylesheet_warnings);
+        report.push_str(&format!("  🚨 Stylesheet warnings: {} (🔴 {} forbidden, 🟡 {} cursed, 🟢 {} tainted)\n",
+            result.stylesheet_warnings.len(), high, medium, low));
+    }
+    if !result.duplicate_rules.is_empty() {
+        report.push_str(&format!("  ♻️  Duplicated rule bodies: {} group(s), ~{} bytes to save\n",
+            result.duplicate_rules.len(), total_bytes_saved(&result.duplicate_rules)));
+    }
+    if !result.unused_suppressions.is_empty() {
+        report.push_str(&format!("  🤫 Unused suppressions: {}\n", result.unused_suppressions.len()));
+    }
+    if !result.diagnostics.is_empty() {
+        report.push_str(&format!("  🩹 Files skipped or misread: {}\n", result.diagnostics.len()));
+    }
+    let safelisted = count_safelisted_classes(result);
+    if safelisted > 0 || !result.unused_safelist_entries.is_empty() {
+        report.push_str(&format!("  🛡️  Safelisted classes: {} ({} unused safelist entries)\n", safelisted, result.unused_safelist_entries.len()));
+    }
     
     // Complexity warnings summary
     if !result.complexity_warnings.is_empty() {
@@ -42,7 +280,7 @@ fn generate_text_report(result: &AnalysisResult) -> anyhow::Result<String> {
         report.push_str(&format!("  ⚠️  Dark sorcery detected: {} (🔴 {} forbidden, 🟡 {} cursed, 🟢 {} tainted)\n", 
             result.complexity_warnings.len(), high, medium, low));
     }
-    report.push_str("\n");
+    report.push('\n');
     
     // Complexity warnings section (show first, as it's about code quality)
     if !result.complexity_warnings.is_empty() {
@@ -67,10 +305,33 @@ fn generate_text_report(result: &AnalysisResult) -> anyhow::Result<String> {
                 
                 let warning_type = format_warning_type(&warning.warning_type);
                 
-                report.push_str(&format!("  {} {} (line {})\n", severity_icon, warning_type, warning.line_number));
+                report.push_str(&format!("  {} {} [{}] ({})\n", severity_icon, warning_type, warning.rule_id, format_line_span(warning.start_line, warning.end_line)));
                 report.push_str(&format!("     Spell pattern: {}\n", warning.pattern));
                 report.push_str(&format!("     💡 {}\n", warning.suggestion));
-                report.push_str("\n");
+                report.push('\n');
+            }
+        }
+    }
+    
+    // Stylesheet warnings section
+    if !result.stylesheet_warnings.is_empty() {
+        report.push_str("🚨 Stylesheet Warnings:\n");
+        report.push_str("----------------------\n");
+        
+        let mut warnings_by_file: HashMap<&str, Vec<&StylesheetWarning>> = HashMap::new();
+        for warning in &result.stylesheet_warnings {
+            warnings_by_file.entry(&warning.file_path).or_default().push(warning);
+        }
+        let mut sorted_files: Vec<_> = warnings_by_file.keys().collect();
+        sorted_files.sort();
+        
+        for file_path in sorted_files {
+            report.push_str(&format!("\n📄 {}:\n", file_path));
+            for warning in &warnings_by_file[file_path] {
+                report.push_str(&format!("  {} {} (line {})\n", severity_icon(&warning.severity), format_stylesheet_warning_type(&warning.warning_type), warning.line_number));
+                report.push_str(&format!("     {}\n", warning.pattern));
+                report.push_str(&format!("     💡 {}\n", warning.suggestion));
+                report.push('\n');
             }
         }
     }
@@ -103,6 +364,134 @@ fn generate_text_report(result: &AnalysisResult) -> anyhow::Result<String> {
         report.push_str("🎉 Excellent! No CSS corruption detected in your territory!\n");
     }
     
+    // References to classes that don't exist
+    if !result.undefined_references.is_empty() {
+        report.push_str("\n👻 Undefined References (undefined at runtime):\n");
+        report.push_str("----------------------------------------------\n");
+        
+        let mut references_by_file: HashMap<&str, Vec<&UndefinedReference>> = HashMap::new();
+        for reference in &result.undefined_references {
+            references_by_file.entry(&reference.file_path).or_default().push(reference);
+        }
+        let mut sorted_files: Vec<_> = references_by_file.keys().collect();
+        sorted_files.sort();
+        
+        for file_path in sorted_files {
+            report.push_str(&format!("\n📄 {}:\n", file_path));
+            for reference in &references_by_file[file_path] {
+                report.push_str(&format!("  • {}\n", format_undefined_reference(reference)));
+            }
+        }
+    }
+    
+    // Copy-pasted rule bodies
+    if !result.duplicate_rules.is_empty() {
+        report.push_str("\n♻️  Duplicated Rule Bodies:\n");
+        report.push_str("--------------------------\n");
+        
+        for group in &result.duplicate_rules {
+            let kind = if group.identical { "identical" } else { "near-identical" };
+            report.push_str(&format!("\n  .{} - {} {} rules, ~{} bytes to save\n",
+                group.suggested_name, group.occurrences.len(), kind, group.estimated_bytes_saved));
+            report.push_str(&format!("     {}\n", group.declarations.join("; ")));
+            for location in &group.occurrences {
+                report.push_str(&format!("     • {}:{}-{} {}\n", location.file_path, location.start_line, location.end_line, location.selector));
+            }
+        }
+    }
+    
+    // Components nothing imports, and the classes only they keep alive
+    if !result.dead_components.is_empty() {
+        report.push_str("\n💀 Dead Components (unreachable from the entry points):\n");
+        report.push_str("-----------------------------------------------------\n");
+        for component in &result.dead_components {
+            report.push_str(&format!("  • {}\n", component.file_path));
+            for stylesheet in &component.stylesheets {
+                report.push_str(&format!("     🎨 {}\n", stylesheet));
+            }
+        }
+        
+        if !result.dead_code_classes.is_empty() {
+            report.push_str("\n  Used only by dead code:\n");
+            let dead_by_file = group_classes_by_file(&result.dead_code_classes.iter().collect::<Vec<_>>());
+            let mut sorted_files: Vec<_> = dead_by_file.keys().collect();
+            sorted_files.sort();
+            
+            for file_path in sorted_files {
+                report.push_str(&format!("\n  📄 {}:\n", file_path));
+                for class in &dead_by_file[file_path] {
+                    report.push_str(&format!("     • .{} (line {})\n", class.name, class.line_number));
+                }
+            }
+        }
+    }
+    
+    // Whole stylesheets that can be deleted
+    if !result.unused_stylesheet_files.is_empty() {
+        report.push_str("\n📦 Stylesheet Files to Delete:\n");
+        report.push_str("------------------------------\n");
+        for file in &result.unused_stylesheet_files {
+            report.push_str(&format!("  • {}\n", format_unused_stylesheet_file(file)));
+        }
+    }
+    
+    // Stylesheets no entry point loads
+    if !result.unreachable_stylesheets.is_empty() {
+        report.push_str("\n🕸️  Unreachable Stylesheets (no entry point loads them):\n");
+        report.push_str("-----------------------------------------------------\n");
+        for stylesheet in &result.unreachable_stylesheets {
+            report.push_str(&format!("  • {}\n", stylesheet));
+        }
+    }
+    
+    // Classes only kept alive by guesses from dynamic patterns
+    if !possible.is_empty() {
+        report.push_str("\n🎲 Possibly Used (dynamic matches only):\n");
+        report.push_str("---------------------------------------\n");
+        
+        let possible_by_file = group_classes_by_file(&possible);
+        let mut sorted_files: Vec<_> = possible_by_file.keys().collect();
+        sorted_files.sort();
+        
+        for file_path in sorted_files {
+            report.push_str(&format!("\n📄 {}:\n", file_path));
+            for class in &possible_by_file[file_path] {
+                report.push_str(&format!("  • .{} (line {})", class.name, class.line_number));
+                if let Some(evidence) = class.evidence.first() {
+                    report.push_str(&format!(" ← {} at {}:{}", format_usage_kind(&evidence.kind), evidence.file_path, evidence.line_number));
+                }
+                report.push('\n');
+            }
+        }
+    }
+    
+    // Suppression comments that no longer silence anything
+    if !result.unused_suppressions.is_empty() {
+        report.push_str("\n🤫 Unused Suppressions (safe to remove):\n");
+        report.push_str("--------------------------------------\n");
+        for suppression in &result.unused_suppressions {
+            report.push_str(&format!("  • {}\n", format_suppression(suppression)));
+        }
+    }
+    
+    // Safelist entries that no stylesheet class matches any more
+    if !result.unused_safelist_entries.is_empty() {
+        report.push_str("\n🛡️  Unused Safelist Entries (safe to remove):\n");
+        report.push_str("-------------------------------------------\n");
+        for entry in &result.unused_safelist_entries {
+            report.push_str(&format!("  • {}\n", format_safelist_entry(entry)));
+        }
+    }
+    
+    // Files the analysis couldn't use; their classes and references may be missing above
+    if !result.diagnostics.is_empty() {
+        report.push_str("\n🩹 Files Skipped or Misread (results may be incomplete):\n");
+        report.push_str("------------------------------------------------------\n");
+        for diagnostic in &result.diagnostics {
+            report.push_str(&format!("  • {}\n", diagnostic.message));
+        }
+    }
+    
     // Custom properties section
     if !result.used_custom_properties.is_empty() {
         report.push_str("\n\n🎨 CSS Artifacts:\n");
@@ -124,184 +513,296 @@ fn generate_text_report(result: &AnalysisResult) -> anyhow::Result<String> {
     Ok(report)
 }
 
-/// Generate a JSON report for programmatic consumption with complexity warnings
-fn generate_json_report(result: &AnalysisResult) -> anyhow::Result<String> {
-    let (high, medium, low) = count_warnings_by_severity(&result.complexity_warnings);
-    
-    let json_report = serde_json::json!({
-        "summary": {
-            "total_css_classes": result.used_classes.len() + result.unused_classes.len(),
-            "used_classes": result.used_classes.len(),
-            "unused_classes": result.unused_classes.len(),
-            "unused_percentage": if result.used_classes.len() + result.unused_classes.len() > 0 {
-                (result.unused_classes.len() as f64 / (result.used_classes.len() + result.unused_classes.len()) as f64 * 100.0).round()
-            } else {
-                0.0
-            },
-            "total_files_scanned": result.total_files_scanned,
-            "css_files_scanned": result.total_css_files,
-            "js_files_scanned": result.total_js_files,
-            "custom_properties_found": result.used_custom_properties.len(),
-            "complexity_warnings": {
-                "total": result.complexity_warnings.len(),
-                "high": high,
-                "medium": medium,
-                "low": low
-            }
-        },
-        "unused_classes": result.unused_classes,
-        "used_classes": result.used_classes,
-        "custom_properties": result.used_custom_properties,
-        "complexity_warnings": result.complexity_warnings,
-        "timestamp": chrono::Utc::now().to_rfc3339()
-    });
-    
-    Ok(serde_json::to_string_pretty(&json_report)?)
-}
-
-/// Generate an HTML report using templates
-fn generate_html_report(result: &AnalysisResult) -> anyhow::Result<String> {
-    let total_classes = result.used_classes.len() + result.unused_classes.len();
+/// Generate a Markdown report for PR comments and wikis: a summary table and a collapsible section per file
+/// Locations link to the source when `source_url_template` is set, with `{file}` relative to the git repository root
+fn generate_markdown_report(result: &AnalysisResult, source_url_template: Option<&str>) -> String {
+    let mut report = String::new();
+    let total_classes = count_classes(result);
     let unused_percentage = if total_classes > 0 {
         (result.unused_classes.len() as f64 / total_classes as f64 * 100.0).round()
     } else {
         0.0
     };
-    
+    let (definite, possible) = split_used_classes(&result.used_classes);
     let (high, medium, low) = count_warnings_by_severity(&result.complexity_warnings);
     
-    // Load main template
-    let main_template = include_str!("../templates/report.html");
-    
-    // Generate content sections using templates
-    let unused_content = generate_unused_classes_content(&result.unused_classes);
-    let complexity_content = generate_complexity_warnings_content(&result.complexity_warnings);
-    
-    // Replace all template variables in the main template
-    let html = main_template
-        .replace("{{TIMESTAMP}}", &chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string())
-        .replace("{{TOTAL_CLASSES}}", &total_classes.to_string())
-        .replace("{{USED_CLASSES}}", &result.used_classes.len().to_string())
-        .replace("{{UNUSED_CLASSES}}", &result.unused_classes.len().to_string())
-        .replace("{{UNUSED_PERCENTAGE}}", &format!("{:.0}", unused_percentage))
-        .replace("{{UNUSED_CLASSES_CONTENT}}", &unused_content)
-        .replace("{{COMPLEXITY_WARNINGS_CONTENT}}", &complexity_content)
-        .replace("{{COMPLEXITY_WARNINGS_TOTAL}}", &result.complexity_warnings.len().to_string())
-        .replace("{{COMPLEXITY_HIGH}}", &high.to_string())
-        .replace("{{COMPLEXITY_MEDIUM}}", &medium.to_string())
-        .replace("{{COMPLEXITY_LOW}}", &low.to_string())
-        .replace("{{TOTAL_FILES}}", &result.total_files_scanned.to_string())
-        .replace("{{CSS_FILES}}", &result.total_css_files.to_string())
-        .replace("{{CUSTOM_PROPERTIES}}", &result.used_custom_properties.len().to_string());
-
-    Ok(html)
-}
-
-/// Generate the content for unused classes section using templates
-fn generate_unused_classes_content(unused_classes: &[CssClass]) -> String {
-    if unused_classes.is_empty() {
-        return include_str!("../templates/no-unused.html").to_string();
-    }
-
-    // Group classes by file
-    let classes_by_file = group_classes_by_file(unused_classes);
-    let mut sorted_files: Vec<_> = classes_by_file.keys().collect();
-    sorted_files.sort();
-
-    // Generate content for each file using templates
-    sorted_files
-        .iter()
-        .map(|&file_path| generate_file_group_html(file_path, classes_by_file.get(file_path).unwrap()))
-        .collect::<Vec<String>>()
-        .join("\n")
+    report.push_str("# ⚔️ RustBrother CSS Analysis Report\n\n");
+    report.push_str("| Metric | Count |\n");
+    report.push_str("|---|---:|\n");
+    report.push_str(&format!("| Total CSS classes | {} |\n", total_classes));
+    report.push_str(&format!("| ✅ Used classes | {} ({} definite, {} possible) |\n", result.used_classes.len(), definite.len(), possible.len()));
+    report.push_str(&format!("| 🗑️ Unused classes | {} ({:.0}%) |\n", result.unused_classes.len(), unused_percentage));
+    if !result.dead_code_classes.is_empty() {
+        report.push_str(&format!("| 💀 Used only by dead code | {} |\n", result.dead_code_classes.len()));
+    }
+    report.push_str(&format!("| 🔴 High complexity warnings | {} |\n", high));
+    report.push_str(&format!("| 🟡 Medium complexity warnings | {} |\n", medium));
+    report.push_str(&format!("| 🟢 Low complexity warnings | {} |\n", low));
+    report.push_str(&format!("| 🚨 Stylesheet warnings | {} |\n", result.stylesheet_warnings.len()));
+    report.push_str(&format!("| 👻 Undefined references | {} |\n", result.undefined_references.len()));
+    if !result.unused_stylesheet_files.is_empty() {
+        report.push_str(&format!("| 📦 Stylesheet files to delete | {} (~{} bytes) |\n", result.unused_stylesheet_files.len(), total_unused_file_bytes(result)));
+    }
+    report.push_str(&format!("| Files patrolled | {} |\n", result.total_files_scanned));
+    
+    if !result.unused_classes.is_empty() {
+        report.push_str(&format!("\n## 🗑️ Unused classes ({})\n", result.unused_classes.len()));
+        let classes: Vec<&CssClass> = result.unused_classes.iter().collect();
+        let classes_by_file = group_classes_by_file(&classes);
+        let mut sorted_files: Vec<_> = classes_by_file.keys().collect();
+        sorted_files.sort();
+        
+        for file_path in sorted_files {
+            let classes = &classes_by_file[file_path];
+            let link_path = repository_path(file_path);
+            report.push_str(&format!("\n<details>\n<summary><code>{}</code> - {} unused</summary>\n\n", html_escape(file_path), classes.len()));
+            for class in classes {
+                report.push_str(&format!("- `.{}` {}\n", class.name, markdown_location(source_url_template, &link_path, class.line_number, class.line_number)));
+            }
+            report.push_str("\n</details>\n");
+        }
+    }
+    
+    if !result.complexity_warnings.is_empty() {
+        report.push_str(&format!("\n## ⚠️ Complexity warnings ({})\n", result.complexity_warnings.len()));
+        let warnings_by_file = group_warnings_by_file(&result.complexity_warnings);
+        let mut sorted_files: Vec<_> = warnings_by_file.keys().collect();
+        sorted_files.sort();
+        
+        for file_path in sorted_files {
+            let warnings = &warnings_by_file[file_path];
+            let link_path = repository_path(file_path);
+            report.push_str(&format!("\n<details>\n<summary><code>{}</code> - {} warning(s)</summary>\n\n", html_escape(file_path), warnings.len()));
+            for warning in warnings {
+                report.push_str(&format!("- {} **{}** `{}` {}  \n", severity_icon(&warning.severity), format_warning_type(&warning.warning_type),
+                    warning.rule_id, markdown_location(source_url_template, &link_path, warning.start_line, warning.end_line)));
+                report.push_str(&format!("  {}  \n", markdown_code(&warning.pattern)));
+                report.push_str(&format!("  💡 {}\n", warning.suggestion));
+            }
+            report.push_str("\n</details>\n");
+        }
+    }
+    
+    report
 }
 
-/// Generate the content for complexity warnings section using templates
-fn generate_complexity_warnings_content(warnings: &[ComplexityWarning]) -> String {
-    if warnings.is_empty() {
-        return r#"<div class="no-unused">🎉 No dark sorcery detected! Your code is pure and righteous.</div>"#.to_string();
+/// `(line 12)`, or `([lines 12-15](https://...))` when a source URL template is configured
+fn markdown_location(source_url_template: Option<&str>, link_path: &str, start_line: usize, end_line: usize) -> String {
+    let span = format_line_span(start_line, end_line);
+    match source_url_template {
+        Some(template) => {
+            let url = template
+                .replace("{file}", link_path)
+                .replace("{line}", &start_line.to_string())
+                .replace("{end_line}", &end_line.to_string());
+            format!("([{}]({}))", span, url)
+        }
+        None => format!("({})", span),
     }
+}
 
-    // Group warnings by file
-    let warnings_by_file = group_warnings_by_file(warnings);
-    let mut sorted_files: Vec<_> = warnings_by_file.keys().collect();
-    sorted_files.sort();
+/// `file_path` relative to the root of the git repository containing it, as source links expect, whether
+/// the analysis ran on an absolute path, from a subdirectory or on `../app/src`. Outside a repository
+/// it's the path as analyzed, without a leading `./`.
+fn repository_path(file_path: &str) -> String {
+    let fallback = || file_path.trim_start_matches("./").to_string();
+    let Ok(absolute) = Path::new(file_path).canonicalize() else {
+        return fallback();
+    };
+    absolute
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join(".git").exists())
+        .and_then(|root| absolute.strip_prefix(root).ok())
+        .map(|relative| relative.components().map(|part| part.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/"))
+        .unwrap_or_else(fallback)
+}
 
-    // Generate content for each file using templates
-    sorted_files
-        .iter()
-        .map(|&file_path| generate_complexity_file_group_html(file_path, warnings_by_file.get(file_path).unwrap()))
-        .collect::<Vec<String>>()
-        .join("\n")
+/// Inline code that survives backticks in the content
+fn markdown_code(text: &str) -> String {
+    let text = text.replace('\n', " ");
+    if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
 }
 
-/// Generate HTML for a complexity warning file group using template
-fn generate_complexity_file_group_html(file_path: &str, warnings: &[&ComplexityWarning]) -> String {
-    let file_template = include_str!("../templates/complexity-file-group.html");
-    
-    // Generate all warning items for this file using template
-    let warning_items = warnings
-        .iter()
-        .map(|warning| generate_complexity_warning_html(warning))
-        .collect::<Vec<String>>()
-        .join("\n        ");
+/// One unused class, unused custom property or complexity warning, as the CI XML formats list them
+struct XmlFinding {
+    line: usize,
+    rule_id: String,
+    severity: WarningSeverity,
+    name: String,
+    message: String,
+}
 
-    // Replace variables in file group template
-    file_template
-        .replace("{{FILE_PATH}}", file_path)
-        .replace("{{WARNING_COUNT}}", &warnings.len().to_string())
-        .replace("{{WARNING_ITEMS}}", &warning_items)
+/// Findings per file, in path order; every stylesheet defining classes is listed even when it has none
+fn collect_xml_findings(result: &AnalysisResult) -> BTreeMap<&str, Vec<XmlFinding>> {
+    let mut files: BTreeMap<&str, Vec<XmlFinding>> = BTreeMap::new();
+    for class in result.used_classes.iter().chain(&result.dead_code_classes) {
+        files.entry(class.file_path.as_str()).or_default();
+    }
+    for class in &result.unused_classes {
+        files.entry(class.file_path.as_str()).or_default().push(XmlFinding {
+            line: class.line_number,
+            rule_id: "unused-class".to_string(),
+            severity: WarningSeverity::Medium,
+            name: format!(".{}", class.name),
+            message: format!("Unused class .{}", class.name),
+        });
+    }
+    for property in &result.unused_custom_properties {
+        files.entry(property.file_path.as_str()).or_default().push(XmlFinding {
+            line: property.line_number,
+            rule_id: "unused-custom-property".to_string(),
+            severity: WarningSeverity::Medium,
+            name: property.name.clone(),
+            message: format!("Unused custom property {}", property.name),
+        });
+    }
+    for warning in &result.complexity_warnings {
+        files.entry(warning.file_path.as_str()).or_default().push(XmlFinding {
+            line: warning.start_line,
+            rule_id: warning.rule_id.clone(),
+            severity: warning.severity.clone(),
+            name: format!("{} ({})", warning.rule_id, format_line_span(warning.start_line, warning.end_line)),
+            message: format!("{}: {} - {}", format_warning_type(&warning.warning_type), warning.pattern.split_whitespace().collect::<Vec<_>>().join(" "), warning.suggestion),
+        });
+    }
+    for findings in files.values_mut() {
+        findings.sort_by_key(|finding| finding.line);
+    }
+    files
 }
 
-/// Generate HTML for a single complexity warning using template
-fn generate_complexity_warning_html(warning: &ComplexityWarning) -> String {
-    let warning_template = include_str!("../templates/complexity-warning.html");
-    
-    let severity_color = match warning.severity {
+/// Generate a JUnit XML report: a test suite per file and a test case per finding
+/// High severity findings are errors, the rest failures; a file without findings gets one passing case.
+fn generate_junit_report(result: &AnalysisResult) -> String {
+    let files = collect_xml_findings(result);
+    let is_error = |finding: &XmlFinding| matches!(finding.severity, WarningSeverity::High);
+    let count = |findings: &[XmlFinding]| {
+        let errors = findings.iter().filter(|finding| is_error(finding)).count();
+        (findings.len().max(1), findings.len() - errors, errors)
+    };
+    let (tests, failures, errors) = files.values().map(|findings| count(findings)).fold((0, 0, 0), |total, file| {
+        (total.0 + file.0, total.1 + file.1, total.2 + file.2)
+    });
+
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    report.push_str(&format!("<testsuites name=\"rustbrother\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n", tests, failures, errors));
+    for (file_path, findings) in &files {
+        let (tests, failures, errors) = count(findings);
+        let file_path = html_escape(file_path);
+        report.push_str(&format!("  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n", file_path, tests, failures, errors));
+        if findings.is_empty() {
+            report.push_str(&format!("    <testcase name=\"no findings\" classname=\"{}\"/>\n", file_path));
+        }
+        for finding in findings {
+            let element = if is_error(finding) { "error" } else { "failure" };
+            report.push_str(&format!("    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" line=\"{}\">\n", html_escape(&finding.name), file_path, file_path, finding.line));
+            report.push_str(&format!("      <{} type=\"{}\" message=\"{}\">{}:{}: {}</{}>\n",
+                element, html_escape(&finding.rule_id), html_escape(&finding.message), file_path, finding.line, html_escape(&finding.message), element));
+            report.push_str("    </testcase>\n");
+        }
+        report.push_str("  </testsuite>\n");
+    }
+    report.push_str("</testsuites>\n");
+    report
+}
+
+/// Generate a Checkstyle XML report: a file element per file and an error element per finding
+/// Severity maps High to `error`, Medium to `warning` and Low to `info`.
+fn generate_checkstyle_report(result: &AnalysisResult) -> String {
+    let mut report = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"4.3\">\n");
+    for (file_path, findings) in collect_xml_findings(result) {
+        report.push_str(&format!("  <file name=\"{}\">\n", html_escape(file_path)));
+        for finding in &findings {
+            let severity = match finding.severity {
+                WarningSeverity::High => "error",
+                WarningSeverity::Medium => "warning",
+                WarningSeverity::Low => "info",
+            };
+            report.push_str(&format!("    <error line=\"{}\" severity=\"{}\" message=\"{}\" source=\"rustbrother.{}\"/>\n",
+                finding.line, severity, html_escape(&finding.message), html_escape(&finding.rule_id)));
+        }
+        report.push_str("  </file>\n");
+    }
+    report.push_str("</checkstyle>\n");
+    report
+}
+
+/// Generate a JSON report for programmatic consumption, following the versioned `JsonReport` schema
+fn generate_json_report(result: &AnalysisResult, include_timestamp: bool) -> anyhow::Result<String> {
+    let timestamp = include_timestamp.then(|| chrono::Utc::now().to_rfc3339());
+    Ok(serde_json::to_string_pretty(&JsonReport::from_result(result, timestamp))?)
+}
+
+/// Generate an HTML report from the built-in templates, or from `config.template_dir` where it overrides them
+fn generate_html_report(result: &AnalysisResult, config: &AnalysisConfig) -> anyhow::Result<String> {
+    let timestamp = config.report_timestamp.then(|| chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string());
+    render_html_report(result, config.template_dir.as_deref(), timestamp)
+}
+
+pub(crate) fn severity_color(severity: &WarningSeverity) -> &'static str {
+    match severity {
         WarningSeverity::High => "#fc8181",     // Red for high
         WarningSeverity::Medium => "#fbd38d",   // Orange for medium  
         WarningSeverity::Low => "#68d391",      // Green for low
-    };
-    
-    warning_template
-        .replace("{{WARNING_TYPE}}", &format_warning_type(&warning.warning_type))
-        .replace("{{LINE_NUMBER}}", &warning.line_number.to_string())
-        .replace("{{PATTERN}}", &html_escape(&warning.pattern))
-        .replace("{{SUGGESTION}}", &html_escape(&warning.suggestion))
-        .replace("{{SEVERITY_COLOR}}", severity_color)
+    }
 }
 
-/// Generate HTML for a single file group using template
-fn generate_file_group_html(file_path: &str, classes: &[&CssClass]) -> String {
-    let file_template = include_str!("../templates/file-group.html");
-    
-    // Generate all class items for this file using template
-    let class_items = classes
-        .iter()
-        .map(|class| generate_class_item_html(class))
-        .collect::<Vec<String>>()
-        .join("\n        ");
+fn severity_icon(severity: &WarningSeverity) -> &'static str {
+    match severity {
+        WarningSeverity::High => "🔴",
+        WarningSeverity::Medium => "🟡",
+        WarningSeverity::Low => "🟢",
+    }
+}
 
-    // Replace variables in file group template
-    file_template
-        .replace("{{FILE_PATH}}", file_path)
-        .replace("{{CLASS_COUNT}}", &classes.len().to_string())
-        .replace("{{CLASS_ITEMS}}", &class_items)
+/// How a class is referenced: `styles.foo` or `className="foo"`
+pub(crate) fn format_reference(binding: Option<&str>, name: &str) -> String {
+    match binding {
+        Some(binding) => format!("{}.{}", binding, name),
+        None => format!("className=\"{}\"", name),
+    }
 }
 
-/// Generate HTML for a single class item using template
-fn generate_class_item_html(class: &CssClass) -> String {
-    let item_template = include_str!("../templates/class-item.html");
-    
-    item_template
-        .replace("{{CLASS_NAME}}", &class.name)
-        .replace("{{LINE_NUMBER}}", &class.line_number.to_string())
+/// One line describing an undefined reference, with its "did you mean" hint
+fn format_undefined_reference(reference: &UndefinedReference) -> String {
+    let mut line = format!(
+        "{} (line {}:{})",
+        format_reference(reference.binding.as_deref(), &reference.name),
+        reference.line_number,
+        reference.column
+    );
+    match &reference.stylesheet {
+        Some(stylesheet) => {
+            let file_name = std::path::Path::new(stylesheet).file_name().map_or(stylesheet.clone(), |name| name.to_string_lossy().to_string());
+            line.push_str(&format!(" - not defined in {}", file_name));
+        }
+        None => line.push_str(" - not defined in any global stylesheet"),
+    }
+    if let Some(suggestion) = &reference.suggestion {
+        line.push_str(&format!(", did you mean .{}?", suggestion));
+    }
+    line
+}
+
+/// Bytes saved if every duplicate group became one shared utility
+fn total_bytes_saved(groups: &[DuplicateRuleGroup]) -> usize {
+    groups.iter().map(|group| group.estimated_bytes_saved).sum()
+}
+
+/// Split used classes into those referenced by name and those only matched by dynamic guesses
+fn split_used_classes(used_classes: &[CssClass]) -> (Vec<&CssClass>, Vec<&CssClass>) {
+    used_classes.iter().partition(|class| class.is_definitely_used())
 }
 
 /// Helper function to group classes by file path
-fn group_classes_by_file(classes: &[CssClass]) -> HashMap<&str, Vec<&CssClass>> {
+fn group_classes_by_file<'a>(classes: &[&'a CssClass]) -> HashMap<&'a str, Vec<&'a CssClass>> {
     let mut grouped = HashMap::new();
-    for class in classes {
+    for &class in classes {
         grouped
             .entry(class.file_path.as_str())
             .or_insert_with(Vec::new)
@@ -327,6 +828,15 @@ fn count_warnings_by_severity(warnings: &[ComplexityWarning]) -> (usize, usize,
     (high, medium, low)
 }
 
+fn count_stylesheet_warnings_by_severity(warnings: &[StylesheetWarning]) -> (usize, usize, usize) {
+    let severities: Vec<&WarningSeverity> = warnings.iter().map(|warning| &warning.severity).collect();
+    (
+        severities.iter().filter(|s| matches!(s, WarningSeverity::High)).count(),
+        severities.iter().filter(|s| matches!(s, WarningSeverity::Medium)).count(),
+        severities.iter().filter(|s| matches!(s, WarningSeverity::Low)).count(),
+    )
+}
+
 fn group_warnings_by_file(warnings: &[ComplexityWarning]) -> HashMap<&str, Vec<&ComplexityWarning>> {
     let mut grouped = HashMap::new();
     for warning in warnings {
@@ -338,18 +848,110 @@ fn group_warnings_by_file(warnings: &[ComplexityWarning]) -> HashMap<&str, Vec<&
     grouped
 }
 
-fn format_warning_type(warning_type: &ComplexityWarningType) -> &'static str {
+/// `src/Card.tsx:12 (deep-template-nesting) - nothing to silence on line 13`
+fn format_suppression(suppression: &Suppression) -> String {
+    let rules = if suppression.rules.is_empty() { "all findings".to_string() } else { suppression.rules.join(", ") };
+    format!("{}:{} ({}) - nothing to silence on {}", suppression.file_path, suppression.line_number, rules,
+        format_line_span(suppression.target_start_line, suppression.target_end_line))
+}
+
+/// `swiper-* (only in vendor/)` - nothing in the stylesheets matches it
+fn format_safelist_entry(entry: &SafelistEntry) -> String {
+    if entry.paths.is_empty() {
+        format!("{} - matches no class", entry.class)
+    } else {
+        format!("{} (only in {}) - matches no class", entry.class, entry.paths.join(", "))
+    }
+}
+
+/// `src/Old.module.scss - no component imports it (412 bytes, 0/3 classes used)`
+fn format_unused_stylesheet_file(file: &UnusedStylesheetFile) -> String {
+    let reason = match file.reason {
+        UnusedFileReason::NotImported => "no component imports it",
+        UnusedFileReason::AllClassesUnused => "none of its classes are used",
+        UnusedFileReason::NoClassDefinitions => "defines no classes",
+    };
+    format!("{} - {} ({} bytes, {}/{} classes used)", file.file_path, reason, file.size_bytes,
+        file.used_classes, file.used_classes + file.unused_classes)
+}
+
+fn total_unused_file_bytes(result: &AnalysisResult) -> u64 {
+    result.unused_stylesheet_files.iter().map(|file| file.size_bytes).sum()
+}
+
+/// Every class defined in a stylesheet: used, used only by dead code and unused
+fn count_classes(result: &AnalysisResult) -> usize {
+    result.used_classes.len() + result.dead_code_classes.len() + result.unused_classes.len()
+}
+
+/// Used classes the config safelist keeps alive
+fn count_safelisted_classes(result: &AnalysisResult) -> usize {
+    result.used_classes
+        .iter()
+        .filter(|class| class.evidence.iter().any(|e| e.kind == UsageKind::Safelisted))
+        .count()
+}
+
+/// `12` for a single line, `12-15` for a span
+pub(crate) fn line_range(start_line: usize, end_line: usize) -> String {
+    if end_line > start_line {
+        format!("{}-{}", start_line, end_line)
+    } else {
+        start_line.to_string()
+    }
+}
+
+/// `line 12` or `lines 12-15`
+fn format_line_span(start_line: usize, end_line: usize) -> String {
+    if end_line > start_line {
+        format!("lines {}", line_range(start_line, end_line))
+    } else {
+        format!("line {}", start_line)
+    }
+}
+
+pub(crate) fn format_warning_type(warning_type: &ComplexityWarningType) -> &str {
     match warning_type {
         ComplexityWarningType::DynamicClassConstruction => "Forbidden dynamic class sorcery",
         ComplexityWarningType::DeepTemplateNesting => "Cursed template nesting",
         ComplexityWarningType::ConditionalClassAssignment => "Dark conditional magic",
         ComplexityWarningType::MultiVariablePattern => "Multi-variable dark arts",
         ComplexityWarningType::UntrackedDynamicPattern => "Untrackable shadow magic",
+        ComplexityWarningType::DeepSelectorNesting => "Bottomless nesting ritual",
+        ComplexityWarningType::LongDescendantChain => "Endless descendant chain",
+        ComplexityWarningType::SelectorConcatenation => "Unsearchable & incantation",
+        ComplexityWarningType::OversizedRuleBlock => "Bloated rule grimoire",
+        ComplexityWarningType::Custom(name) => name,
+    }
+}
+
+pub(crate) fn format_stylesheet_warning_type(warning_type: &StylesheetWarningType) -> &'static str {
+    match warning_type {
+        StylesheetWarningType::ImportantOverusedInRule => "!important barrage",
+        StylesheetWarningType::ImportantOverusedOnSelector => "!important piling up on a selector",
+        StylesheetWarningType::ImportantDensity => "!important plague",
+        StylesheetWarningType::ImportantInComponentModule => "!important in a scoped module",
+        StylesheetWarningType::ShadowedDeclaration => "Shadowed declaration",
+        StylesheetWarningType::AlwaysOverriddenRule => "Always overridden rule",
+        StylesheetWarningType::SpecificityOverBudget => "Specificity arms race",
+        StylesheetWarningType::IdSelector => "ID selector in component styles",
+    }
+}
+
+pub(crate) fn format_usage_kind(kind: &UsageKind) -> &'static str {
+    match kind {
+        UsageKind::Direct => "direct reference",
+        UsageKind::Destructured => "destructured from styles",
+        UsageKind::Template => "template literal",
+        UsageKind::PrefixExpansion => "prefix expansion (dynamic)",
+        UsageKind::SuffixHeuristic => "suffix heuristic (dynamic)",
+        UsageKind::MultiVariableHeuristic => "multi-variable heuristic (dynamic)",
+        UsageKind::Safelisted => "safelisted",
     }
 }
 
-/// Escape HTML entities
-fn html_escape(s: &str) -> String {
+/// Escape HTML entities (also used for XML text and attributes)
+pub(crate) fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
      .replace('<', "&lt;")
      .replace('>', "&gt;")
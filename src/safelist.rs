@@ -0,0 +1,85 @@
+// src/safelist.rs - Config safelist for classes the JS never names
+// Runtime state classes, CMS markup and third-party widgets use classes no component references;
+// safelist entries keep them out of the unused list and mark them as used with `Safelisted` evidence.
+
+use regex::Regex;
+use std::path::Path;
+use crate::error::{self, Error};
+use crate::{CssClass, SafelistEntry, UsageEvidence, UsageKind};
+
+/// How a safelist entry matches class names
+enum Matcher {
+    Exact(String),
+    Prefix(String),
+    Pattern(Regex),
+}
+
+/// Safelist entries compiled once for matching against every class
+pub(crate) struct Safelist<'a> {
+    entries: Vec<(&'a SafelistEntry, Matcher)>,
+    /// Where the entries come from, for the evidence; None when the config wasn't loaded from a file
+    config_file: Option<&'a Path>,
+}
+
+impl<'a> Safelist<'a> {
+    /// Compile the entries; an invalid regex is an error naming the entry
+    pub(crate) fn compile(entries: &'a [SafelistEntry], config_file: Option<&'a Path>) -> error::Result<Self> {
+        let mut compiled = Vec::new();
+
+        for entry in entries {
+            let class = entry.class.trim_start_matches('.');
+            let matcher = if let Some(pattern) = class.strip_prefix('/').and_then(|rest| rest.strip_suffix('/')) {
+                let regex = Regex::new(pattern)
+                    .map_err(|source| Error::Safelist { pattern: entry.class.clone(), source })?;
+                Matcher::Pattern(regex)
+            } else if let Some(prefix) = class.strip_suffix('*') {
+                Matcher::Prefix(prefix.to_string())
+            } else {
+                Matcher::Exact(class.to_string())
+            };
+            compiled.push((entry, matcher));
+        }
+
+        Ok(Safelist { entries: compiled, config_file })
+    }
+
+    /// Indexes of the entries that match `class`
+    pub(crate) fn matching_entries(&self, class: &CssClass) -> Vec<usize> {
+        self.entries
+            .iter()
+            .enumerate()
+            .filter(|(_, (entry, matcher))| {
+                let in_scope = entry.paths.is_empty() || entry.paths.iter().any(|path| class.file_path.contains(path.as_str()));
+                in_scope && match matcher {
+                    Matcher::Exact(name) => class.name == *name,
+                    Matcher::Prefix(prefix) => class.name.starts_with(prefix.as_str()),
+                    Matcher::Pattern(regex) => regex.is_match(&class.name),
+                }
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Evidence recording that `entry` keeps `class` alive
+    pub(crate) fn evidence(&self, index: usize, class: &CssClass) -> UsageEvidence {
+        UsageEvidence {
+            kind: UsageKind::Safelisted,
+            file_path: class.file_path.clone(),
+            line_number: class.line_number,
+            pattern: match self.config_file {
+                Some(config_file) => format!("safelist entry \"{}\" in {}", self.entries[index].0.class, config_file.display()),
+                None => format!("safelist entry \"{}\" in the config", self.entries[index].0.class),
+            },
+        }
+    }
+
+    /// The entries whose index is not in `matched`
+    pub(crate) fn unmatched(&self, matched: &[bool]) -> Vec<SafelistEntry> {
+        self.entries
+            .iter()
+            .zip(matched)
+            .filter(|(_, matched)| !**matched)
+            .map(|((entry, _), _)| (*entry).clone())
+            .collect()
+    }
+}
@@ -0,0 +1,87 @@
+// src/stylesheet_files.rs - File-level stylesheet findings
+// CSS modules nothing imports, CSS modules whose classes are all unused and stylesheets without any class
+// can usually be deleted whole; each comes with its size and how many of its classes are used.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::error;
+use crate::files::{FileProvider, FsFiles};
+use crate::import_graph::{is_stylesheet, ModuleGraph};
+use crate::js_parser::normalize_path;
+use crate::suppressions::{find_suppressions_in, UNUSED_CLASS};
+use crate::{AnalysisConfig, AnalysisResult, Suppression, UnusedFileReason, UnusedStylesheetFile};
+
+/// Find whole stylesheets under `path` that look safe to delete, given the class verdicts in `result`
+/// A stylesheet without classes is only reported when nothing imports it: partials pulled in with
+/// `@use`/`@import` and global styles a component imports (`import './reset.css'`) are in use. CSS modules
+/// a path alias, `baseUrl` or package import of the same file name may load aren't reported.
+pub fn find_unused_stylesheet_files(path: &Path, config: &AnalysisConfig, result: &AnalysisResult) -> error::Result<Vec<UnusedStylesheetFile>> {
+    find_unused_stylesheet_files_in(&FsFiles::new(path), config, result)
+}
+
+/// Same as `find_unused_stylesheet_files`, reading from `files`
+pub fn find_unused_stylesheet_files_in(files: &dyn FileProvider, config: &AnalysisConfig, result: &AnalysisResult) -> error::Result<Vec<UnusedStylesheetFile>> {
+    unused_stylesheet_files(files, config, result, &find_suppressions_in(files, config)?)
+}
+
+/// An unused class a `rustbrother-ignore` comment keeps counts as used, so its stylesheet isn't reported
+pub(crate) fn unused_stylesheet_files(
+    files: &dyn FileProvider,
+    config: &AnalysisConfig,
+    result: &AnalysisResult,
+    suppressions: &[Suppression],
+) -> error::Result<Vec<UnusedStylesheetFile>> {
+    let graph = ModuleGraph::build_in(files, config)?;
+
+    // stylesheet -> (used, unused) class counts
+    let mut class_counts: HashMap<PathBuf, (usize, usize)> = HashMap::new();
+    for class in &result.used_classes {
+        class_counts.entry(normalize_path(Path::new(&class.file_path))).or_default().0 += 1;
+    }
+    for class in result.dead_code_classes.iter().chain(&result.unused_classes) {
+        let counts = class_counts.entry(normalize_path(Path::new(&class.file_path))).or_default();
+        if suppressions.iter().any(|suppression| suppression.covers(&class.file_path, class.line_number, UNUSED_CLASS)) {
+            counts.0 += 1;
+        } else {
+            counts.1 += 1;
+        }
+    }
+
+    let mut unused = Vec::new();
+    for stylesheet in graph.files().filter(|file| is_stylesheet(file)) {
+        let (used_classes, unused_classes) = class_counts.get(stylesheet).copied().unwrap_or_default();
+        let is_module = stylesheet.to_string_lossy().contains(".module.");
+        let mut importers = graph.importers(stylesheet);
+
+        // A module an alias or package import may load can't be verified either way, so it isn't listed
+        if is_module && graph.may_be_imported_unresolved(stylesheet) {
+            continue;
+        }
+
+        let reason = if is_module && !importers.any(|importer| !is_stylesheet(importer)) {
+            UnusedFileReason::NotImported
+        } else if used_classes + unused_classes == 0 {
+            // Partials pulled in with `@use` and global styles a component imports are doing their job
+            if graph.importers(stylesheet).next().is_some() {
+                continue;
+            }
+            UnusedFileReason::NoClassDefinitions
+        } else if is_module && used_classes == 0 {
+            UnusedFileReason::AllClassesUnused
+        } else {
+            continue;
+        };
+
+        let total = used_classes + unused_classes;
+        unused.push(UnusedStylesheetFile {
+            file_path: stylesheet.to_string_lossy().to_string(),
+            reason,
+            size_bytes: files.size(stylesheet).unwrap_or(0),
+            used_classes,
+            unused_classes,
+            unused_ratio: if total > 0 { unused_classes as f64 / total as f64 } else { 0.0 },
+        });
+    }
+
+    Ok(unused)
+}
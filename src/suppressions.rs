@@ -0,0 +1,155 @@
+// src/suppressions.rs - Inline suppression comments
+// `// rustbrother-ignore-next-line [rules]` in JS/TS and `/* rustbrother-ignore [rules] */` before a CSS rule
+// or declaration silence findings on the lines they point at. Suppressions that silence nothing are reported.
+
+use regex::Regex;
+use std::path::Path;
+use crate::css_parser::should_process_css_file;
+use crate::error;
+use crate::files::{source_files, FileProvider, FsFiles};
+use crate::js_parser::should_process_js_file;
+use crate::{AnalysisConfig, AnalysisResult, ComplexityWarning, StylesheetWarningType, Suppression};
+
+/// Rule name for unused class findings
+pub const UNUSED_CLASS: &str = "unused-class";
+/// Rule name for unused custom property findings
+pub const UNUSED_CUSTOM_PROPERTY: &str = "unused-custom-property";
+/// Rule name for class references no stylesheet defines
+pub const UNDEFINED_CLASS: &str = "undefined-class";
+/// Rule name for duplicate rule declarations; silencing one copy silences its whole group
+pub const DUPLICATE_RULE: &str = "duplicate-rule";
+
+/// Find every suppression comment in JS/TS files and stylesheets under `path`
+pub fn find_suppressions(path: &Path, config: &AnalysisConfig) -> error::Result<Vec<Suppression>> {
+    find_suppressions_in(&FsFiles::new(path), config)
+}
+
+/// Same as `find_suppressions`, reading from `files`
+pub fn find_suppressions_in(files: &dyn FileProvider, config: &AnalysisConfig) -> error::Result<Vec<Suppression>> {
+    let mut suppressions = Vec::new();
+
+    for file in source_files(files, config, |file, config| should_process_js_file(file, config) || should_process_css_file(file, config)) {
+        let file = file?;
+        suppressions.extend(parse_suppressions(&file.content, &file.file_path));
+    }
+
+    Ok(suppressions)
+}
+
+/// Find the suppression comments in one file
+/// In JS/TS the comment covers the next line. In a stylesheet it covers the next rule selector or
+/// declaration, from its first line to the line with its `{` or `;`.
+pub fn parse_suppressions(content: &str, file_path: &str) -> Vec<Suppression> {
+    let is_stylesheet = file_path.ends_with(".css") || file_path.ends_with(".scss") || file_path.ends_with(".sass");
+    let comment_regex = if is_stylesheet {
+        Regex::new(r"/\*\s*rustbrother-ignore\b([^*]*)\*/").unwrap()
+    } else {
+        Regex::new(r"(?://|/\*)\s*rustbrother-ignore-next-line\b([^*\n]*)").unwrap()
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let mut suppressions = Vec::new();
+
+    for capture in comment_regex.captures_iter(content) {
+        let comment = capture.get(0).unwrap();
+        let line_number = content[..comment.start()].matches('\n').count() + 1;
+        let end_line = content[..comment.end()].matches('\n').count() + 1;
+        let rules = capture[1]
+            .split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|rule| !rule.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let (target_start_line, target_end_line) = if is_stylesheet {
+            stylesheet_target(&lines, end_line)
+        } else {
+            (end_line + 1, end_line + 1)
+        };
+
+        suppressions.push(Suppression {
+            file_path: file_path.to_string(),
+            line_number,
+            rules,
+            target_start_line,
+            target_end_line,
+        });
+    }
+
+    suppressions
+}
+
+/// The lines of the selector or declaration following a comment that ends on `comment_line`
+fn stylesheet_target(lines: &[&str], comment_line: usize) -> (usize, usize) {
+    let start = (comment_line..lines.len())
+        .find(|&index| {
+            let line = lines[index].trim();
+            !line.is_empty() && !line.starts_with("/*") && !line.starts_with("//")
+        })
+        .map_or(comment_line + 1, |index| index + 1);
+    let end = (start - 1..lines.len())
+        .find(|&index| lines[index].contains(['{', ';']))
+        .map_or(start, |index| index + 1);
+    (start, end)
+}
+
+/// Drop the findings covered by a suppression and record the suppressions that covered nothing
+/// `all_complexity_warnings` includes the warnings of disabled rules and below the threshold: a comment
+/// silencing one of those is still doing its job once the config changes back.
+pub(crate) fn apply_suppressions(result: &mut AnalysisResult, suppressions: Vec<Suppression>, all_complexity_warnings: &[ComplexityWarning]) {
+    let mut used = vec![false; suppressions.len()];
+    let mut suppressed = |file_path: &str, line_number: usize, rule: &str| {
+        let mut matched = false;
+        for (index, suppression) in suppressions.iter().enumerate() {
+            if suppression.covers(file_path, line_number, rule) {
+                used[index] = true;
+                matched = true;
+            }
+        }
+        matched
+    };
+
+    result.unused_classes.retain(|class| !suppressed(&class.file_path, class.line_number, UNUSED_CLASS));
+    result.unused_custom_properties.retain(|property| !suppressed(&property.file_path, property.line_number, UNUSED_CUSTOM_PROPERTY));
+    result.complexity_warnings.retain(|warning| !suppressed(&warning.file_path, warning.start_line, &warning.rule_id));
+    result.stylesheet_warnings.retain(|warning| !suppressed(&warning.file_path, warning.line_number, warning.warning_type.rule_id()));
+    result.undefined_references.retain(|reference| !suppressed(&reference.file_path, reference.line_number, UNDEFINED_CLASS));
+    result.duplicate_rules.retain(|group| {
+        // Every copy is checked, so each comment on an intentional copy counts as used
+        group.occurrences.iter().filter(|copy| suppressed(&copy.file_path, copy.start_line, DUPLICATE_RULE)).count() == 0
+    });
+    for warning in all_complexity_warnings {
+        suppressed(&warning.file_path, warning.start_line, &warning.rule_id);
+    }
+
+    result.unused_suppressions = suppressions
+        .into_iter()
+        .zip(used)
+        .filter(|(_, used)| !used)
+        .map(|(suppression, _)| suppression)
+        .collect();
+    result.unused_suppressions.sort_by(|a, b| (&a.file_path, a.line_number).cmp(&(&b.file_path, b.line_number)));
+}
+
+impl StylesheetWarningType {
+    /// The rule name a suppression comment uses for this kind of stylesheet warning
+    pub fn rule_id(&self) -> &'static str {
+        match self {
+            StylesheetWarningType::ImportantOverusedInRule => "important-overused-in-rule",
+            StylesheetWarningType::ImportantOverusedOnSelector => "important-overused-on-selector",
+            StylesheetWarningType::ImportantDensity => "important-density",
+            StylesheetWarningType::ImportantInComponentModule => "important-in-component-module",
+            StylesheetWarningType::ShadowedDeclaration => "shadowed-declaration",
+            StylesheetWarningType::AlwaysOverriddenRule => "always-overridden-rule",
+            StylesheetWarningType::SpecificityOverBudget => "specificity-over-budget",
+            StylesheetWarningType::IdSelector => "id-selector",
+        }
+    }
+}
+
+impl Suppression {
+    /// True when this comment silences `rule` on `line_number` of `file_path`
+    pub fn covers(&self, file_path: &str, line_number: usize, rule: &str) -> bool {
+        self.file_path == file_path
+            && (self.target_start_line..=self.target_end_line).contains(&line_number)
+            && (self.rules.is_empty() || self.rules.iter().any(|r| r == rule))
+    }
+}
@@ -0,0 +1,218 @@
+// src/templates.rs - HTML report templates
+// The HTML report is rendered with minijinja from the templates in `templates/`, which are baked into the binary.
+// `--template-dir` (or `template_dir` in the config) points at a directory whose `.html` files replace the built-in
+// templates of the same name or add new partials to `{% include %}`. Every template sees the `ReportContext` below.
+
+use minijinja::{AutoEscape, Environment};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::json_report::ReportSummary;
+use crate::reporter::{format_reference, format_stylesheet_warning_type, format_warning_type, html_escape, line_range, severity_color};
+use crate::{AnalysisResult, ComplexityWarning, CssClass, DuplicateRuleGroup, StylesheetWarning, UndefinedReference};
+
+/// The built-in templates, by the name `{% include %}` and `--template-dir` overrides use
+pub const DEFAULT_TEMPLATES: &[(&str, &str)] = &[
+    ("report.html", include_str!("../templates/report.html")),
+    ("file-group.html", include_str!("../templates/file-group.html")),
+    ("class-item.html", include_str!("../templates/class-item.html")),
+    ("no-unused.html", include_str!("../templates/no-unused.html")),
+    ("possibly-used-file-group.html", include_str!("../templates/possibly-used-file-group.html")),
+    ("undefined-file-group.html", include_str!("../templates/undefined-file-group.html")),
+    ("undefined-reference.html", include_str!("../templates/undefined-reference.html")),
+    ("duplicate-group.html", include_str!("../templates/duplicate-group.html")),
+    ("duplicate-occurrence.html", include_str!("../templates/duplicate-occurrence.html")),
+    ("complexity-file-group.html", include_str!("../templates/complexity-file-group.html")),
+    ("complexity-warning.html", include_str!("../templates/complexity-warning.html")),
+];
+
+/// Everything a template can use; `report.html` is rendered with it
+#[derive(Debug, Serialize)]
+pub struct ReportContext {
+    /// `2024-05-01 12:00:00 UTC`, or none when timestamps are turned off
+    pub timestamp: Option<String>,
+    /// The same counts as the `summary` of the JSON report
+    pub summary: ReportSummary,
+    pub unused_files: Vec<ClassFileGroup>,
+    pub possibly_used_files: Vec<ClassFileGroup>,
+    pub undefined_files: Vec<ReferenceFileGroup>,
+    pub duplicate_groups: Vec<DuplicateRuleGroup>,
+    pub complexity_files: Vec<WarningFileGroup>,
+    pub stylesheet_warning_files: Vec<WarningFileGroup>,
+}
+
+/// The classes of one stylesheet, for the per-file loops
+#[derive(Debug, Serialize)]
+pub struct ClassFileGroup {
+    pub path: String,
+    pub classes: Vec<CssClass>,
+}
+
+/// The undefined references of one JS/TS file
+#[derive(Debug, Serialize)]
+pub struct ReferenceFileGroup {
+    pub path: String,
+    pub references: Vec<ReferenceItem>,
+}
+
+/// An undefined reference with its display form (`styles.x` or `className="x"`)
+#[derive(Debug, Serialize)]
+pub struct ReferenceItem {
+    pub display: String,
+    #[serde(flatten)]
+    pub reference: UndefinedReference,
+}
+
+/// The complexity or stylesheet warnings of one file
+#[derive(Debug, Serialize)]
+pub struct WarningFileGroup {
+    pub path: String,
+    pub warnings: Vec<WarningItem>,
+}
+
+/// A complexity or stylesheet warning, ready for display
+#[derive(Debug, Serialize)]
+pub struct WarningItem {
+    /// Human-readable warning type (`Dark conditional magic`)
+    pub label: String,
+    /// Rule ID for complexity warnings, empty for stylesheet warnings
+    pub rule_id: String,
+    /// `12` or `12-15`
+    pub lines: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub pattern: String,
+    pub suggestion: String,
+    /// `High`, `Medium` or `Low`
+    pub severity: String,
+    pub severity_color: String,
+}
+
+impl ReportContext {
+    /// Build the template context for `result`; files and their findings are in path and line order
+    pub fn from_result(result: &AnalysisResult, timestamp: Option<String>) -> Self {
+        let unused: Vec<&CssClass> = result.unused_classes.iter().collect();
+        let possible: Vec<&CssClass> = result.used_classes.iter().filter(|class| !class.is_definitely_used()).collect();
+
+        ReportContext {
+            timestamp,
+            summary: ReportSummary::from_result(result),
+            unused_files: class_file_groups(&unused),
+            possibly_used_files: class_file_groups(&possible),
+            undefined_files: group_by_file(&result.undefined_references, |reference| &reference.file_path, |reference| reference.line_number)
+                .into_iter()
+                .map(|(path, references)| ReferenceFileGroup {
+                    path,
+                    references: references
+                        .into_iter()
+                        .map(|reference| ReferenceItem {
+                            display: format_reference(reference.binding.as_deref(), &reference.name),
+                            reference: reference.clone(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            duplicate_groups: result.duplicate_rules.clone(),
+            complexity_files: group_by_file(&result.complexity_warnings, |warning| &warning.file_path, |warning| warning.start_line)
+                .into_iter()
+                .map(|(path, warnings)| WarningFileGroup { path, warnings: warnings.into_iter().map(complexity_item).collect() })
+                .collect(),
+            stylesheet_warning_files: group_by_file(&result.stylesheet_warnings, |warning| &warning.file_path, |warning| warning.line_number)
+                .into_iter()
+                .map(|(path, warnings)| WarningFileGroup { path, warnings: warnings.into_iter().map(stylesheet_item).collect() })
+                .collect(),
+        }
+    }
+}
+
+fn class_file_groups(classes: &[&CssClass]) -> Vec<ClassFileGroup> {
+    group_by_file(classes, |class| &class.file_path, |class| class.line_number)
+        .into_iter()
+        .map(|(path, classes)| ClassFileGroup { path, classes: classes.into_iter().map(|class| (*class).clone()).collect() })
+        .collect()
+}
+
+/// Group items by file path, files in path order and items in line order
+fn group_by_file<T>(items: &[T], file: impl Fn(&T) -> &String, line: impl Fn(&T) -> usize) -> Vec<(String, Vec<&T>)> {
+    let mut groups: BTreeMap<String, Vec<&T>> = BTreeMap::new();
+    for item in items {
+        groups.entry(file(item).clone()).or_default().push(item);
+    }
+    for group in groups.values_mut() {
+        group.sort_by_key(|item| line(item));
+    }
+    groups.into_iter().collect()
+}
+
+fn complexity_item(warning: &ComplexityWarning) -> WarningItem {
+    WarningItem {
+        label: format_warning_type(&warning.warning_type).to_string(),
+        rule_id: warning.rule_id.clone(),
+        lines: line_range(warning.start_line, warning.end_line),
+        start_line: warning.start_line,
+        end_line: warning.end_line,
+        pattern: warning.pattern.clone(),
+        suggestion: warning.suggestion.clone(),
+        severity: format!("{:?}", warning.severity),
+        severity_color: severity_color(&warning.severity).to_string(),
+    }
+}
+
+fn stylesheet_item(warning: &StylesheetWarning) -> WarningItem {
+    WarningItem {
+        label: format_stylesheet_warning_type(&warning.warning_type).to_string(),
+        rule_id: String::new(),
+        lines: warning.line_number.to_string(),
+        start_line: warning.line_number,
+        end_line: warning.line_number,
+        pattern: warning.pattern.clone(),
+        suggestion: warning.suggestion.clone(),
+        severity: format!("{:?}", warning.severity),
+        severity_color: severity_color(&warning.severity).to_string(),
+    }
+}
+
+/// The built-in templates, with every `.html` file in `template_dir` replacing or adding to them
+pub fn load_templates(template_dir: Option<&Path>) -> anyhow::Result<Environment<'static>> {
+    let mut env = Environment::new();
+    // Escape like the rest of the reports; minijinja's own HTML escaping also escapes `/` in every file path
+    env.set_formatter(|out, state, value| match value.as_str() {
+        Some(text) if state.auto_escape() == AutoEscape::Html && !value.is_safe() => {
+            out.write_str(&html_escape(text)).map_err(minijinja::Error::from)
+        }
+        _ => minijinja::escape_formatter(out, state, value),
+    });
+    for (name, source) in DEFAULT_TEMPLATES {
+        env.add_template(name, source)
+            .map_err(|e| anyhow::anyhow!("Invalid built-in template {}: {:#}", name, e))?;
+    }
+
+    if let Some(dir) = template_dir {
+        let entries = fs::read_dir(dir)
+            .map_err(|e| anyhow::anyhow!("Failed to read template directory {}: {}", dir.display(), e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| anyhow::anyhow!("Failed to read template directory {}: {}", dir.display(), e))?;
+            let path = entry.path();
+            // Anything else named `.html`, like a dangling symlink, fails to read below rather than being skipped
+            if path.is_dir() || path.extension().is_none_or(|ext| ext != "html") {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            let source = fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read template {}: {}", path.display(), e))?;
+            env.add_template_owned(name, source)
+                .map_err(|e| anyhow::anyhow!("Invalid template {}: {:#}", path.display(), e))?;
+        }
+    }
+    Ok(env)
+}
+
+/// Render `report.html` for `result`
+pub fn render_html_report(result: &AnalysisResult, template_dir: Option<&Path>, timestamp: Option<String>) -> anyhow::Result<String> {
+    let env = load_templates(template_dir)?;
+    let context = ReportContext::from_result(result, timestamp);
+    env.get_template("report.html")
+        .and_then(|template| template.render(&context))
+        .map_err(|e| anyhow::anyhow!("Failed to render the HTML report: {:#}", e))
+}
@@ -0,0 +1,685 @@
+// src/tui.rs - Interactive findings browser (`rustbrother tui`)
+// A tree of stylesheets -> classes -> usages next to a filterable list of complexity warnings, with the source
+// around the selected item. Classes can be queued for the safelist or for rule deletion; the queued edits are
+// shown as a diff and only written when the user confirms on exit (see `edits.rs`).
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use crate::edits::PendingEdits;
+use crate::fixer::plan_rule_removal;
+use crate::reporter::{format_usage_kind, format_warning_type, line_range};
+use crate::{AnalysisResult, ComplexityWarning, CssClass, UsageKind, WarningSeverity};
+
+/// Lines of source shown above the selected line
+const SNIPPET_CONTEXT: usize = 4;
+
+/// What the event loop should do after a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Continue,
+    /// Leave without writing the queued edits
+    Quit,
+    /// Leave and write the queued edits
+    ApplyAndQuit,
+}
+
+/// How a class in the tree was classified
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassStatus {
+    Used,
+    PossiblyUsed,
+    Safelisted,
+    DeadCode,
+    Unused,
+}
+
+impl ClassStatus {
+    fn label(self) -> &'static str {
+        match self {
+            ClassStatus::Used => "used",
+            ClassStatus::PossiblyUsed => "possibly used",
+            ClassStatus::Safelisted => "safelisted",
+            ClassStatus::DeadCode => "dead code",
+            ClassStatus::Unused => "unused",
+        }
+    }
+
+    fn color(self) -> Color {
+        match self {
+            ClassStatus::Used | ClassStatus::Safelisted => Color::Green,
+            ClassStatus::PossiblyUsed => Color::Yellow,
+            ClassStatus::DeadCode => Color::Magenta,
+            ClassStatus::Unused => Color::Red,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Tree,
+    Warnings,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Browse,
+    Search,
+    Review,
+}
+
+/// A visible line of the tree: a stylesheet, one of its classes or one usage of a class
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Row {
+    Stylesheet(usize),
+    Class(usize, usize),
+    Usage(usize, usize, usize),
+}
+
+struct Stylesheet {
+    path: String,
+    classes: Vec<(CssClass, ClassStatus)>,
+}
+
+/// State of the browser; `handle_key` updates it and `render` draws it
+pub struct Browser {
+    result: AnalysisResult,
+    stylesheets: Vec<Stylesheet>,
+    edits: PendingEdits,
+    expanded: HashSet<(usize, Option<usize>)>,
+    tree_state: ListState,
+    warning_state: ListState,
+    focus: Pane,
+    mode: Mode,
+    severity_filter: Option<WarningSeverity>,
+    unused_only: bool,
+    search: String,
+    review: Vec<String>,
+    review_scroll: usize,
+    status: String,
+    /// Lines of each file shown so far, or why it couldn't be read
+    sources: HashMap<String, Result<Vec<String>, String>>,
+}
+
+impl Browser {
+    /// Browse `result`; edits are queued into `edits`
+    pub fn new(result: AnalysisResult, edits: PendingEdits) -> Self {
+        let mut by_file: BTreeMap<String, Vec<(CssClass, ClassStatus)>> = BTreeMap::new();
+        for class in &result.used_classes {
+            let status = if class.evidence.iter().any(|e| e.kind == UsageKind::Safelisted) {
+                ClassStatus::Safelisted
+            } else if class.is_definitely_used() {
+                ClassStatus::Used
+            } else {
+                ClassStatus::PossiblyUsed
+            };
+            by_file.entry(class.file_path.clone()).or_default().push((class.clone(), status));
+        }
+        for class in &result.dead_code_classes {
+            by_file.entry(class.file_path.clone()).or_default().push((class.clone(), ClassStatus::DeadCode));
+        }
+        for class in &result.unused_classes {
+            by_file.entry(class.file_path.clone()).or_default().push((class.clone(), ClassStatus::Unused));
+        }
+
+        let stylesheets = by_file
+            .into_iter()
+            .map(|(path, mut classes)| {
+                classes.sort_by(|a, b| (a.0.line_number, &a.0.name).cmp(&(b.0.line_number, &b.0.name)));
+                Stylesheet { path, classes }
+            })
+            .collect();
+
+        let mut browser = Browser {
+            result,
+            stylesheets,
+            edits,
+            expanded: HashSet::new(),
+            tree_state: ListState::default(),
+            warning_state: ListState::default(),
+            focus: Pane::Tree,
+            mode: Mode::Browse,
+            severity_filter: None,
+            unused_only: false,
+            search: String::new(),
+            review: Vec::new(),
+            review_scroll: 0,
+            status: String::from("Tab switch pane · ←/→ collapse/expand · s safelist · d delete rule · v review edits · q quit"),
+            sources: HashMap::new(),
+        };
+        browser.clamp_selection();
+        browser
+    }
+
+    /// The queued edits
+    pub fn edits(&self) -> &PendingEdits {
+        &self.edits
+    }
+
+    /// Handle one key press
+    pub fn handle_key(&mut self, key: KeyCode) -> Action {
+        match self.mode {
+            Mode::Search => self.handle_search_key(key),
+            Mode::Review => return self.handle_review_key(key),
+            Mode::Browse => return self.handle_browse_key(key),
+        }
+        Action::Continue
+    }
+
+    fn handle_browse_key(&mut self, key: KeyCode) -> Action {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                if self.edits.is_empty() {
+                    return Action::Quit;
+                }
+                self.open_review();
+            }
+            KeyCode::Tab | KeyCode::BackTab => {
+                self.focus = if self.focus == Pane::Tree { Pane::Warnings } else { Pane::Tree };
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Enter => self.expand(),
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(),
+            KeyCode::Char('f') => {
+                self.severity_filter = match self.severity_filter {
+                    None => Some(WarningSeverity::High),
+                    Some(WarningSeverity::High) => Some(WarningSeverity::Medium),
+                    Some(WarningSeverity::Medium) => Some(WarningSeverity::Low),
+                    Some(WarningSeverity::Low) => None,
+                };
+                self.clamp_selection();
+            }
+            KeyCode::Char('u') => {
+                self.unused_only = !self.unused_only;
+                self.clamp_selection();
+            }
+            KeyCode::Char('/') => {
+                self.mode = Mode::Search;
+            }
+            KeyCode::Char('s') => self.toggle_safelist(),
+            KeyCode::Char('d') => self.toggle_rule_deletion(),
+            KeyCode::Char('v') => self.open_review(),
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn handle_search_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Enter => self.mode = Mode::Browse,
+            KeyCode::Esc => {
+                self.search.clear();
+                self.mode = Mode::Browse;
+            }
+            KeyCode::Backspace => {
+                self.search.pop();
+            }
+            KeyCode::Char(c) => self.search.push(c),
+            _ => {}
+        }
+        self.clamp_selection();
+    }
+
+    fn handle_review_key(&mut self, key: KeyCode) -> Action {
+        match key {
+            KeyCode::Char('y') => return Action::ApplyAndQuit,
+            KeyCode::Char('n') => return Action::Quit,
+            KeyCode::Esc | KeyCode::Char('v') => self.mode = Mode::Browse,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.review_scroll = (self.review_scroll + 1).min(self.review.len().saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.review_scroll = self.review_scroll.saturating_sub(1),
+            KeyCode::PageDown => self.review_scroll = (self.review_scroll + 20).min(self.review.len().saturating_sub(1)),
+            KeyCode::PageUp => self.review_scroll = self.review_scroll.saturating_sub(20),
+            _ => {}
+        }
+        Action::Continue
+    }
+
+    fn open_review(&mut self) {
+        if self.edits.is_empty() {
+            self.status = String::from("Nothing queued - mark classes with s (safelist) or d (delete rule)");
+            return;
+        }
+        match self.edits.diff() {
+            Ok(diff) => {
+                self.review = diff.lines().map(String::from).collect();
+                self.review_scroll = 0;
+                self.mode = Mode::Review;
+            }
+            Err(e) => self.status = format!("Can't show the queued edits: {}", e),
+        }
+    }
+
+    /// The tree rows that pass the filters, in display order
+    fn rows(&self) -> Vec<Row> {
+        let query = self.search.to_lowercase();
+        let mut rows = Vec::new();
+
+        for (s, stylesheet) in self.stylesheets.iter().enumerate() {
+            let path_matches = stylesheet.path.to_lowercase().contains(&query);
+            let classes: Vec<usize> = stylesheet.classes
+                .iter()
+                .enumerate()
+                .filter(|(_, (class, status))| {
+                    (!self.unused_only || matches!(status, ClassStatus::Unused | ClassStatus::DeadCode))
+                        && (path_matches || class.name.to_lowercase().contains(&query))
+                })
+                .map(|(c, _)| c)
+                .collect();
+            if classes.is_empty() {
+                continue;
+            }
+
+            rows.push(Row::Stylesheet(s));
+            if !self.expanded.contains(&(s, None)) {
+                continue;
+            }
+            for c in classes {
+                rows.push(Row::Class(s, c));
+                if self.expanded.contains(&(s, Some(c))) {
+                    rows.extend((0..stylesheet.classes[c].0.evidence.len()).map(|e| Row::Usage(s, c, e)));
+                }
+            }
+        }
+        rows
+    }
+
+    /// The complexity warnings that pass the filters
+    fn warnings(&self) -> Vec<&ComplexityWarning> {
+        let query = self.search.to_lowercase();
+        self.result.complexity_warnings
+            .iter()
+            .filter(|warning| match &self.severity_filter {
+                Some(severity) => std::mem::discriminant(severity) == std::mem::discriminant(&warning.severity),
+                None => true,
+            })
+            .filter(|warning| {
+                warning.file_path.to_lowercase().contains(&query)
+                    || warning.rule_id.to_lowercase().contains(&query)
+                    || warning.pattern.to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let (tree_len, warnings_len) = (self.rows().len(), self.warnings().len());
+        let (state, len) = match self.focus {
+            Pane::Tree => (&mut self.tree_state, tree_len),
+            Pane::Warnings => (&mut self.warning_state, warnings_len),
+        };
+        if len == 0 {
+            return;
+        }
+        let current = state.selected().unwrap_or(0) as isize;
+        state.select(Some((current + delta).clamp(0, len as isize - 1) as usize));
+    }
+
+    fn clamp_selection(&mut self) {
+        let (tree_len, warnings_len) = (self.rows().len(), self.warnings().len());
+        for (state, len) in [(&mut self.tree_state, tree_len), (&mut self.warning_state, warnings_len)] {
+            state.select(if len == 0 { None } else { Some(state.selected().unwrap_or(0).min(len - 1)) });
+        }
+    }
+
+    fn selected_row(&self) -> Option<Row> {
+        self.rows().get(self.tree_state.selected()?).copied()
+    }
+
+    fn selected_class(&self) -> Option<(&CssClass, ClassStatus)> {
+        match self.selected_row()? {
+            Row::Class(s, c) | Row::Usage(s, c, _) => {
+                let (class, status) = &self.stylesheets[s].classes[c];
+                Some((class, *status))
+            }
+            Row::Stylesheet(_) => None,
+        }
+    }
+
+    fn expand(&mut self) {
+        if self.focus != Pane::Tree {
+            return;
+        }
+        match self.selected_row() {
+            Some(Row::Stylesheet(s)) => {
+                self.expanded.insert((s, None));
+            }
+            Some(Row::Class(s, c)) => {
+                self.expanded.insert((s, Some(c)));
+            }
+            _ => {}
+        }
+    }
+
+    fn collapse(&mut self) {
+        if self.focus != Pane::Tree {
+            return;
+        }
+        let Some(row) = self.selected_row() else { return };
+        let (key, parent) = match row {
+            Row::Stylesheet(s) => ((s, None), Row::Stylesheet(s)),
+            Row::Class(s, c) if self.expanded.contains(&(s, Some(c))) => ((s, Some(c)), row),
+            Row::Class(s, _) => ((s, None), Row::Stylesheet(s)),
+            Row::Usage(s, c, _) => ((s, Some(c)), Row::Class(s, c)),
+        };
+        self.expanded.remove(&key);
+        let position = self.rows().iter().position(|candidate| *candidate == parent);
+        self.tree_state.select(position);
+    }
+
+    fn toggle_safelist(&mut self) {
+        let Some((class, status)) = self.selected_class() else {
+            self.status = String::from("Select a class to safelist");
+            return;
+        };
+        if matches!(status, ClassStatus::Used | ClassStatus::Safelisted) {
+            self.status = format!(".{} is already {}", class.name, status.label());
+            return;
+        }
+        let name = class.name.clone();
+        let entry = self.edits.safelist_entry(&class.file_path, &name);
+        if self.edits.removals.iter().any(|removal| removal.file_path == class.file_path && removal.class_name == name) {
+            self.status = format!("The rule of .{} is queued for deletion - press d to unqueue it before safelisting", name);
+            return;
+        }
+        self.status = if self.edits.toggle_safelist(entry) {
+            format!("Queued .{} for the safelist in {}", name, self.edits.config_path.display())
+        } else {
+            format!("Unqueued .{} from the safelist", name)
+        };
+    }
+
+    fn toggle_rule_deletion(&mut self) {
+        let Some((class, status)) = self.selected_class() else {
+            self.status = String::from("Select a class to delete its rule");
+            return;
+        };
+        if !matches!(status, ClassStatus::Unused | ClassStatus::DeadCode) {
+            self.status = format!(".{} is {} - only unused rules can be deleted", class.name, status.label());
+            return;
+        }
+        let class = class.clone();
+        if self.edits.is_safelisted(&class.file_path, &class.name) {
+            self.status = format!(".{} is queued for the safelist - press s to unqueue it before deleting its rule", class.name);
+            return;
+        }
+        let content = match self.source(&class.file_path) {
+            Ok(lines) => lines.join("\n"),
+            Err(e) => {
+                self.status = format!("Can't read {}: {}", class.file_path, e);
+                return;
+            }
+        };
+        let Some(removal) = plan_rule_removal(&self.result, &class, &content) else {
+            self.status = format!("The rule of .{} can't be deleted on its own (it contains used classes)", class.name);
+            return;
+        };
+        let lines = line_range(removal.start_line, removal.end_line);
+        self.status = if self.edits.toggle_removal(removal) {
+            format!("Queued lines {} of {} for deletion", lines, class.file_path)
+        } else {
+            format!("Unqueued the rule of .{}", class.name)
+        };
+    }
+
+    fn source(&mut self, file_path: &str) -> Result<&[String], String> {
+        self.sources
+            .entry(file_path.to_string())
+            .or_insert_with(|| {
+                fs::read_to_string(file_path)
+                    .map(|content| content.lines().map(String::from).collect())
+                    .map_err(|e| e.to_string())
+            })
+            .as_deref()
+            .map_err(Clone::clone)
+    }
+
+    /// File and line range of the selected item in the focused pane
+    fn selected_location(&self) -> Option<(String, usize, usize)> {
+        match self.focus {
+            Pane::Tree => match self.selected_row()? {
+                Row::Stylesheet(s) => {
+                    let first = self.stylesheets[s].classes.first().map(|(class, _)| class.line_number).unwrap_or(1);
+                    Some((self.stylesheets[s].path.clone(), first, first))
+                }
+                Row::Class(s, c) => {
+                    let class = &self.stylesheets[s].classes[c].0;
+                    Some((class.file_path.clone(), class.line_number, class.line_number))
+                }
+                Row::Usage(s, c, e) => {
+                    let evidence = &self.stylesheets[s].classes[c].0.evidence[e];
+                    Some((evidence.file_path.clone(), evidence.line_number, evidence.line_number))
+                }
+            },
+            Pane::Warnings => {
+                let warnings = self.warnings();
+                let warning = warnings.get(self.warning_state.selected()?)?;
+                Some((warning.file_path.clone(), warning.start_line, warning.end_line))
+            }
+        }
+    }
+
+    /// Draw the browser, or the diff of the queued edits while reviewing
+    pub fn render(&mut self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([Constraint::Length(1), Constraint::Min(3), Constraint::Length(1)]).areas(frame.area());
+        frame.render_widget(Paragraph::new(self.header_line()), header);
+        frame.render_widget(Paragraph::new(self.footer_line()), footer);
+
+        if self.mode == Mode::Review {
+            self.render_review(frame, body);
+            return;
+        }
+
+        let [tree_area, right] = Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(body);
+        let [warnings_area, source_area] = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(right);
+        self.render_tree(frame, tree_area);
+        self.render_warnings(frame, warnings_area);
+        self.render_source(frame, source_area);
+    }
+
+    fn header_line(&self) -> Line<'static> {
+        let severity = match &self.severity_filter {
+            Some(severity) => format!("{:?}", severity),
+            None => String::from("all"),
+        };
+        let mut spans = vec![
+            Span::styled(" ⚔️ RustBrother ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(format!(
+                "│ {} unused · {} warnings │ severity: {} │ {} │ queued: {} safelist, {} rule(s)",
+                self.result.unused_classes.len(),
+                self.result.complexity_warnings.len(),
+                severity,
+                if self.unused_only { "unused only" } else { "all classes" },
+                self.edits.safelist.len(),
+                self.edits.removals.len(),
+            )),
+        ];
+        if !self.search.is_empty() || self.mode == Mode::Search {
+            spans.push(Span::styled(format!(" │ /{}", self.search), Style::default().fg(Color::Cyan)));
+        }
+        Line::from(spans)
+    }
+
+    fn footer_line(&self) -> Line<'static> {
+        let text = match self.mode {
+            Mode::Search => String::from("Type to filter · Enter keep filter · Esc clear"),
+            Mode::Review => String::from("Queued edits · y apply and quit · n discard and quit · Esc keep browsing · j/k scroll"),
+            Mode::Browse => self.status.clone(),
+        };
+        Line::from(Span::styled(text, Style::default().fg(Color::DarkGray)))
+    }
+
+    fn pane_block(&self, title: String, pane: Pane) -> Block<'static> {
+        let style = if self.focus == pane { Style::default().fg(Color::Cyan) } else { Style::default() };
+        Block::default().borders(Borders::ALL).border_style(style).title(title)
+    }
+
+    fn render_tree(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self.rows().into_iter().map(|row| ListItem::new(self.row_line(row))).collect();
+        let list = List::new(items)
+            .block(self.pane_block(String::from(" Stylesheets "), Pane::Tree))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.tree_state);
+    }
+
+    fn row_line(&self, row: Row) -> Line<'static> {
+        match row {
+            Row::Stylesheet(s) => {
+                let stylesheet = &self.stylesheets[s];
+                let unused = stylesheet.classes.iter().filter(|(_, status)| *status == ClassStatus::Unused).count();
+                let marker = if self.expanded.contains(&(s, None)) { "▾" } else { "▸" };
+                Line::from(format!("{} {} ({} unused / {})", marker, stylesheet.path, unused, stylesheet.classes.len()))
+            }
+            Row::Class(s, c) => {
+                let (class, status) = &self.stylesheets[s].classes[c];
+                let marker = if class.evidence.is_empty() {
+                    " "
+                } else if self.expanded.contains(&(s, Some(c))) {
+                    "▾"
+                } else {
+                    "▸"
+                };
+                let mut spans = vec![
+                    Span::raw(format!("  {} .{} ", marker, class.name)),
+                    Span::styled(format!("{} ", status.label()), Style::default().fg(status.color())),
+                    Span::styled(format!("line {}", class.line_number), Style::default().fg(Color::DarkGray)),
+                ];
+                if self.edits.is_safelisted(&class.file_path, &class.name) {
+                    spans.push(Span::styled(" [safelist]", Style::default().fg(Color::Cyan)));
+                }
+                if self.edits.removals.iter().any(|removal| removal.file_path == class.file_path && removal.class_name == class.name) {
+                    spans.push(Span::styled(" [delete]", Style::default().fg(Color::Red)));
+                }
+                Line::from(spans)
+            }
+            Row::Usage(s, c, e) => {
+                let evidence = &self.stylesheets[s].classes[c].0.evidence[e];
+                Line::from(vec![
+                    Span::raw(format!("      ↳ {}:{} ", evidence.file_path, evidence.line_number)),
+                    Span::styled(format_usage_kind(&evidence.kind).to_string(), Style::default().fg(Color::DarkGray)),
+                ])
+            }
+        }
+    }
+
+    fn render_warnings(&mut self, frame: &mut Frame, area: Rect) {
+        let warnings = self.warnings();
+        let title = format!(" Complexity warnings ({}/{}) ", warnings.len(), self.result.complexity_warnings.len());
+        let items: Vec<ListItem> = warnings
+            .iter()
+            .map(|warning| {
+                let color = match warning.severity {
+                    WarningSeverity::High => Color::Red,
+                    WarningSeverity::Medium => Color::Yellow,
+                    WarningSeverity::Low => Color::Green,
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!("{:<6} ", format!("{:?}", warning.severity)), Style::default().fg(color)),
+                    Span::raw(format!("{}:{} ", warning.file_path, line_range(warning.start_line, warning.end_line))),
+                    Span::styled(format!("{} [{}]", format_warning_type(&warning.warning_type), warning.rule_id), Style::default().fg(Color::DarkGray)),
+                ]))
+            })
+            .collect();
+        let list = List::new(items)
+            .block(self.pane_block(title, Pane::Warnings))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.warning_state);
+    }
+
+    fn render_source(&mut self, frame: &mut Frame, area: Rect) {
+        let Some((file_path, start_line, end_line)) = self.selected_location() else {
+            frame.render_widget(Paragraph::new("").block(Block::default().borders(Borders::ALL).title(" Source ")), area);
+            return;
+        };
+        let first = start_line.saturating_sub(SNIPPET_CONTEXT).max(1);
+        let height = area.height.saturating_sub(2) as usize;
+        let title = format!(" {}:{} ", file_path, line_range(start_line, end_line));
+        let source = match self.source(&file_path) {
+            Ok(source) => source,
+            Err(e) => {
+                let error = Paragraph::new(format!("Can't read {}: {}", file_path, e)).style(Style::default().fg(Color::Red));
+                frame.render_widget(error.block(Block::default().borders(Borders::ALL).title(title)), area);
+                return;
+            }
+        };
+        let lines: Vec<Line> = source
+            .iter()
+            .enumerate()
+            .skip(first - 1)
+            .take(height)
+            .map(|(index, text)| {
+                let line_number = index + 1;
+                let style = if (start_line..=end_line).contains(&line_number) {
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                Line::from(vec![
+                    Span::styled(format!("{:>5} │ ", line_number), Style::default().fg(Color::DarkGray)),
+                    Span::styled(text.clone(), style),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), area);
+    }
+
+    fn render_review(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = self.review
+            .iter()
+            .skip(self.review_scroll)
+            .map(|line| {
+                let style = if line.starts_with("+++") || line.starts_with("---") {
+                    Style::default().add_modifier(Modifier::BOLD)
+                } else if line.starts_with('+') {
+                    Style::default().fg(Color::Green)
+                } else if line.starts_with('-') {
+                    Style::default().fg(Color::Red)
+                } else if line.starts_with("@@") {
+                    Style::default().fg(Color::Cyan)
+                } else {
+                    Style::default()
+                };
+                Line::styled(line.clone(), style)
+            })
+            .collect();
+        let title = format!(" Queued edits: {} safelist entr(ies), {} rule(s) to delete ", self.edits.safelist.len(), self.edits.removals.len());
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), area);
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> anyhow::Result<Action> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match self.handle_key(key.code) {
+                    Action::Continue => {}
+                    action => return Ok(action),
+                }
+            }
+        }
+    }
+}
+
+/// Run the browser in the terminal until the user quits
+/// Returns the number of files written when the user confirmed the queued edits, None otherwise.
+pub fn run(result: AnalysisResult, edits: PendingEdits) -> anyhow::Result<Option<usize>> {
+    let mut browser = Browser::new(result, edits);
+    let mut terminal = ratatui::init();
+    let action = browser.event_loop(&mut terminal);
+    ratatui::restore();
+
+    match action? {
+        Action::ApplyAndQuit => Ok(Some(browser.edits.apply()?)),
+        _ => Ok(None),
+    }
+}
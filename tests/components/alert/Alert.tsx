@@ -0,0 +1,26 @@
+// tests/components/alert/Alert.tsx
+import styles from './Alert.module.scss';
+
+export const Alert = ({ tone, compact, dismissible, children }) => {
+  // Ternary spread over several lines
+  const toneClass = tone
+    ? styles[`alert_${tone}`]
+    : styles.alert_info;
+
+  // Ternaries chained through intermediate variables
+  const density = compact ? 'compact' : 'comfortable';
+  const layout = dismissible ? `${density}_dismissible` : density;
+  const layoutClass = styles[`alert_${layout}`];
+
+  return (
+    <div role="alert" className={`${styles.alert} ${toneClass} ${layoutClass}`}>
+      <span
+        className={`${styles.alertIcon} ${
+          tone ? `${styles[`alertIcon_${tone}`]}` : ''
+        }`}
+      />
+      {/* Don't flag: styles[`alert_${tone}`] in a comment */}
+      <p>It's {children}</p>
+    </div>
+  );
+};
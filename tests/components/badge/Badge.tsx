@@ -0,0 +1,7 @@
+// tests/components/badge/Badge.tsx
+import './Badge.css';
+
+export const Badge = ({ children }) => (
+  // ❌ WRONG: typo in a global class name
+  <span className="badge badge-primray">{children}</span>
+);
@@ -0,0 +1,15 @@
+import React from 'react';
+import Swiper from 'swiper';
+import './carousel.css';
+import './vendor/swiper-overrides.css';
+
+export const Carousel = ({ slides, html }) => (
+  <div className="carousel">
+    <div className="swiper">
+      {slides.map((slide) => (
+        <div key={slide.id} className="swiper-slide">{slide.content}</div>
+      ))}
+    </div>
+    <div className="carouselCaption" dangerouslySetInnerHTML={{ __html: html }} />
+  </div>
+);
@@ -0,0 +1,14 @@
+import React from 'react';
+import styles from './Gallery.module.scss';
+import lightbox from './GalleryLightbox.module.scss';
+import './gallery-base.scss';
+
+export const Gallery = ({ images }) => (
+  <div className={styles.gallery}>
+    {images.map((image) => (
+      <figure key={image.src}>
+        <img src={image.src} alt={image.alt} />
+      </figure>
+    ))}
+  </div>
+);
@@ -0,0 +1,12 @@
+// tests/components/modal/Modal.tsx
+import styles from './Modal.module.scss';
+import utils from './utils.module.css';
+
+export const Modal = ({ open, onClose, children }) => (
+  <div className={`${styles.modal} ${open ? utils['u-block'] : utils['u-hidden']}`}>
+    <div className={styles.modalContent}>
+      <button className={`${styles.modalClose} ${utils['u-flex']}`} onClick={onClose}>×</button>
+      {children}
+    </div>
+  </div>
+);
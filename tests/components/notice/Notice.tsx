@@ -0,0 +1,15 @@
+// tests/components/notice/Notice.tsx
+import styles from './Notice.module.scss';
+
+export const Notice = ({ kind, size, children }) => {
+  // rustbrother-ignore-next-line dynamic-class-construction
+  const kindClass = styles[`notice_${kind}_${size}`];
+  // rustbrother-ignore-next-line deep-template-nesting
+  const bodyClass = styles.noticeBody;
+
+  return (
+    <div className={`${styles.notice} ${kindClass}`}>
+      <p className={bodyClass}>{children}</p>
+    </div>
+  );
+};
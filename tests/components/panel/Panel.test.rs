@@ -77,15 +77,12 @@ fn test_panel_detects_unused_classes() {
     
     // There should be some unused classes since the Panel.tsx doesn't use all CSS classes
     // This will depend on what's actually in your Panel.module.scss vs Panel.tsx
-    if unused_class_names.len() > 0 {
+    if !unused_class_names.is_empty() {
         println!("✅ Panel unused classes detection working - found {} unused classes", unused_class_names.len());
     } else {
         println!("ℹ️  Panel has no unused classes - all CSS classes are being used");
     }
     
-    // This test validates the unused class detection mechanism
-    assert!(result.unused_classes.len() >= 0, "Unused classes should be a valid list");
-    
     println!("✅ Panel unused classes test passed!");
 }
 
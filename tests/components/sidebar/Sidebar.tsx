@@ -0,0 +1,26 @@
+// tests/components/sidebar/Sidebar.tsx
+import styles from './Sidebar.module.scss';
+
+export const Sidebar = ({ title, items }) => (
+  <aside className={styles.sidebar}>
+    <nav className={styles.sidebarNav}>
+      <div className={styles.sidebarPanel}>
+        <section className={styles.sidebar_section}>
+          <h2 className={styles.sidebar_sectionTitle}>{title}</h2>
+          <ul className={styles.sectionList}>
+            {items.map((item) => (
+              <li key={item.href} className={styles.sectionItem}>
+                <a className={styles.sectionLink} href={item.href}>
+                  {item.label}
+                  <span className={styles.sectionBadge}>
+                    <i className={styles.sectionBadgeIcon} />
+                  </span>
+                </a>
+              </li>
+            ))}
+          </ul>
+        </section>
+      </div>
+    </nav>
+  </aside>
+);
@@ -0,0 +1,4 @@
+import { render } from '@testing-library/react';
+import { Badge } from './Badge';
+
+test('renders', () => render(<Badge label="New" />));
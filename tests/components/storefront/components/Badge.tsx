@@ -0,0 +1,4 @@
+import React from 'react';
+import './shared.css';
+
+export const Badge = ({ label }) => <span className="badge">{label}</span>;
@@ -0,0 +1,10 @@
+import React from 'react';
+import styles from './LegacyBanner.module.scss';
+import '../shared.css';
+
+export const LegacyBanner = () => (
+  <div className={styles.banner}>
+    <strong className="highlight">Sale!</strong>
+    <p className={styles.bannerText}>Everything must go</p>
+  </div>
+);
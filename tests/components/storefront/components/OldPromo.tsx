@@ -0,0 +1,4 @@
+const React = require('react');
+const { LegacyBanner } = require('./LegacyBanner/LegacyBanner');
+
+export const OldPromo = () => <LegacyBanner />;
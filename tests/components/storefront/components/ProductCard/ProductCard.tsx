@@ -0,0 +1,10 @@
+import React from 'react';
+import styles from './ProductCard.module.scss';
+import { Badge } from '../Badge';
+
+export const ProductCard = ({ product }) => (
+  <article className={styles.card}>
+    <Badge label={product.tag} />
+    <span className={styles.price}>{product.price}</span>
+  </article>
+);
@@ -0,0 +1 @@
+export { ProductCard } from './ProductCard';
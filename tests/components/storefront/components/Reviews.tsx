@@ -0,0 +1,10 @@
+import React from 'react';
+import styles from './Reviews.module.scss';
+
+export default function Reviews() {
+  return (
+    <section className={styles.reviews}>
+      <h2 className="heading">Reviews</h2>
+    </section>
+  );
+}
@@ -0,0 +1,18 @@
+import React, { lazy, Suspense } from 'react';
+import { ProductCard } from '../components/ProductCard';
+import '../styles/main.scss';
+
+const Reviews = lazy(() => import('../components/Reviews'));
+
+export default function HomePage({ products }) {
+  return (
+    <main className="page">
+      {products.map((product) => <ProductCard key={product.id} product={product} />)}
+      <Suspense fallback={null}>
+        <Reviews />
+      </Suspense>
+    </main>
+  );
+}
+
+// export { OldPromo } from '../components/OldPromo';
@@ -0,0 +1,15 @@
+// tests/components/tabs/Tabs.tsx
+import styles from './Tabs.module.scss';
+
+export const Tabs = ({ tabs, active }) => (
+  <div id="tabs-root" className={styles.tabs}>
+    {tabs.map((tab) => (
+      <button key={tab.id} className={tab.id === active ? styles.tabActive : styles.tab}>
+        <span className={styles.label}>
+          <span className={styles.icon}><span>{tab.icon}</span></span>
+          {tab.label}
+        </span>
+      </button>
+    ))}
+  </div>
+);
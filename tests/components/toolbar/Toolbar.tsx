@@ -0,0 +1,10 @@
+// tests/components/toolbar/Toolbar.tsx
+import styles from './Toolbar.module.scss';
+
+export const Toolbar = ({ label, children }) => (
+  <div className={styles.toolbar}>
+    <span className={styles.visuallyHidden}>{label}</span>
+    <div className={styles.toolbarActions}>{children}</div>
+    <div className={styles.toolbarFooter} />
+  </div>
+);
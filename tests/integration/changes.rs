@@ -0,0 +1,111 @@
+use rustbrother::{affected_files, analyze_changes, Error, ModuleGraph};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::support::{config, scratch_tree};
+
+const STOREFRONT: &str = "tests/components/storefront";
+
+fn relative(files: impl IntoIterator<Item = PathBuf>, root: &str) -> Vec<String> {
+    let mut files: Vec<String> = files.into_iter().map(|f| f.to_string_lossy().trim_start_matches(root).to_string()).collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn test_changed_component_pulls_in_its_stylesheets() {
+    let graph = ModuleGraph::build(Path::new(STOREFRONT), &config()).unwrap();
+    let changed = vec![Path::new(STOREFRONT).join("components/LegacyBanner/LegacyBanner.tsx").canonicalize().unwrap()];
+    assert_eq!(relative(affected_files(&graph, &changed), STOREFRONT), vec![
+        "/components/LegacyBanner/LegacyBanner.module.scss",
+        "/components/LegacyBanner/LegacyBanner.tsx",
+        "/components/shared.css",
+    ]);
+}
+
+#[test]
+fn test_changed_stylesheet_pulls_in_its_components() {
+    let graph = ModuleGraph::build(Path::new(STOREFRONT), &config()).unwrap();
+    let changed = vec![Path::new(STOREFRONT).join("components/shared.css")];
+    assert_eq!(relative(affected_files(&graph, &changed), STOREFRONT), vec![
+        "/components/Badge.tsx",
+        "/components/LegacyBanner/LegacyBanner.tsx",
+        "/components/shared.css",
+    ]);
+}
+
+fn git(repo: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C").arg(repo)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com", "-c", "commit.gpgsign=false"])
+        .args(args)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+#[test]
+fn test_analyze_changes_since_ref() {
+    let repo = scratch_tree("since", "repo", &[
+        ("src/Card.tsx", "import styles from './Card.module.css';\nexport const Card = () => <div className={styles.card} />;\n"),
+        ("src/Card.module.css", ".card { padding: 1rem; }\n.cardOld { padding: 0; }\n"),
+        ("src/Menu.tsx", "import styles from './Menu.module.css';\nexport const Menu = () => <nav className={styles.menu} />;\n"),
+        ("src/Menu.module.css", ".menu { display: flex; }\n.menuOld { display: block; }\n"),
+    ]);
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-qm", "initial"]);
+
+    // Only the menu component changes; its stylesheet comes along, the card's findings don't
+    fs::write(repo.join("src/Menu.tsx"), "import styles from './Menu.module.css';\nexport const Menu = () => <nav className={styles.menu} data-open />;\n").unwrap();
+
+    let result = analyze_changes(&repo.join("src"), &config(), "HEAD").unwrap();
+    let unused: Vec<&str> = result.unused_classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(unused, vec!["menuOld"]);
+    assert!(result.used_classes.iter().all(|c| c.name == "menu"));
+
+    let error = analyze_changes(&repo.join("src"), &config(), "no-such-ref").unwrap_err();
+    assert!(matches!(&error, Error::Git { args, .. } if args.contains("no-such-ref")), "{:?}", error);
+    fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn test_option_looking_ref_is_rejected() {
+    let repo = scratch_tree("since", "options", &[("src/Card.tsx", "export const Card = () => <div className=\"card\" />;\n")]);
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-qm", "initial"]);
+
+    // Passed to `git diff` as is, this would write the diff to `injected.txt`
+    let output = repo.join("injected.txt");
+    let git_ref = format!("--output={}", output.display());
+    let error = analyze_changes(&repo.join("src"), &config(), &git_ref).unwrap_err();
+    assert!(matches!(&error, Error::Git { args, .. } if args == &git_ref), "{:?}", error);
+    assert!(!output.exists());
+    fs::remove_dir_all(&repo).unwrap();
+}
+
+#[test]
+fn test_deleted_component_reports_its_stylesheet() {
+    let repo = scratch_tree("since", "deleted", &[
+        ("src/Card.tsx", "import styles from './Card.module.css';\nexport const Card = () => <div className={styles.card} />;\n"),
+        ("src/Card.module.css", ".card { padding: 1rem; }\n"),
+        ("src/Menu.tsx", "import styles from './Menu.module.css';\nexport const Menu = () => <nav className={styles.menu} />;\n"),
+        ("src/Menu.module.css", ".menu { display: flex; }\n.menuOld { display: block; }\n"),
+    ]);
+    git(&repo, &["init", "-q"]);
+    git(&repo, &["add", "-A"]);
+    git(&repo, &["commit", "-qm", "initial"]);
+
+    // Deleting the card component leaves its module behind with nothing using it
+    git(&repo, &["rm", "-q", "src/Card.tsx"]);
+    git(&repo, &["commit", "-qm", "remove card"]);
+
+    let result = analyze_changes(&repo.join("src"), &config(), "HEAD~1").unwrap();
+    let unused: Vec<&str> = result.unused_classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(unused, vec!["card"]);
+    let files: Vec<&str> = result.unused_stylesheet_files.iter().map(|file| file.file_path.as_str()).collect();
+    assert!(files.len() == 1 && files[0].ends_with("src/Card.module.css"), "{:?}", files);
+    fs::remove_dir_all(&repo).unwrap();
+}
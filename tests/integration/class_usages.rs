@@ -0,0 +1,35 @@
+use rustbrother::{find_class_usages, find_style_imports, rule_block_range};
+
+#[test]
+fn test_class_usages_are_located() {
+    let content = r#"import styles from './Card.module.scss';
+
+export const Card = () => {
+  const { card, cardHeader: header } = styles;
+  return <div className={`${card} ${styles.cardBody}`}><span className="legacy-title" /></div>;
+};"#;
+
+    let imports = find_style_imports(content);
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].binding, "styles");
+    assert_eq!(imports[0].source, "./Card.module.scss");
+
+    let usages = find_class_usages(content, "Card.tsx");
+    let located: Vec<(&str, usize, usize)> = usages.iter().map(|u| (u.name.as_str(), u.line_number, u.column)).collect();
+    assert!(located.contains(&("card", 4, 11)), "destructured card should be located: {:?}", located);
+    assert!(located.contains(&("cardHeader", 4, 17)), "aliased destructuring keeps the class name: {:?}", located);
+    assert!(located.contains(&("cardBody", 5, 44)), "styles.cardBody should be located: {:?}", located);
+
+    let legacy = usages.iter().find(|u| u.name == "legacy-title").expect("className string should be found");
+    assert!(legacy.binding.is_none(), "plain className strings have no module binding");
+}
+
+#[test]
+fn test_rule_block_range_spans_nested_rules() {
+    let content = ".card {\n  color: red;\n\n  &_dark {\n    color: black;\n  }\n}\n.other { color: blue; }\n";
+
+    assert_eq!(rule_block_range(content, 1), Some((1, 7)));
+    assert_eq!(rule_block_range(content, 4), Some((4, 6)));
+    assert_eq!(rule_block_range(content, 8), Some((8, 8)));
+    assert_eq!(rule_block_range(content, 2), None, "declarations are not rules");
+}
@@ -0,0 +1,57 @@
+use rustbrother::fixer::{plan_unused_rule_removals, remove_rules};
+use rustbrother::{analyze_directory, directory_stats, explain_class, AnalysisConfig};
+use std::path::Path;
+use crate::support::config;
+
+#[test]
+fn test_explain_used_and_unused_classes() {
+    let used = explain_class(Path::new("tests/components/card"), &config(), ".cardHeader").unwrap();
+    assert!(used.is_used, "cardHeader should be used");
+    assert_eq!(used.definitions.len(), 1);
+    assert!(used.references.iter().any(|r| r.file_path.ends_with("Card.tsx")), "destructured reference should be listed");
+
+    let unused = explain_class(Path::new("tests/components/button"), &config(), "iconLarge").unwrap();
+    assert!(!unused.is_used, "iconLarge should be unused");
+    assert!(unused.references.is_empty());
+
+    let phantom = explain_class(Path::new("tests/components/panel"), &config(), "panel_iconContainer").unwrap();
+    assert!(phantom.definitions.is_empty(), "panel_iconContainer isn't defined in the SCSS");
+    assert_eq!(phantom.references.len(), 1);
+}
+
+#[test]
+fn test_directory_stats_group_by_component() {
+    let result = analyze_directory(Path::new("tests/components"), &config()).unwrap();
+    let stats = directory_stats(&result);
+
+    let button = stats.iter().find(|s| s.directory.ends_with("button")).expect("button directory stats");
+    assert_eq!(button.css_files, 1);
+    assert_eq!(button.unused_classes, 1);
+    assert_eq!(button.total_classes, button.used_classes + button.unused_classes);
+}
+
+#[test]
+fn test_fix_plans_only_unused_blocks() {
+    let result = analyze_directory(Path::new("tests/components/button"), &config()).unwrap();
+    let removals = plan_unused_rule_removals(&result).unwrap();
+
+    assert_eq!(removals.len(), 1);
+    assert_eq!(removals[0].class_name, "iconLarge");
+
+    let content = std::fs::read_to_string(&removals[0].file_path).unwrap();
+    let fixed = remove_rules(&content, &[&removals[0]]);
+    assert!(!fixed.contains(".iconLarge"));
+    assert!(fixed.contains(".icon {"));
+}
+
+#[test]
+fn test_starter_config_round_trips() {
+    let starter = rustbrother::config::starter_config().unwrap();
+    let parsed: AnalysisConfig = serde_json::from_str(&starter).unwrap();
+    assert!(parsed.include_css_modules);
+
+    // Partial configs keep defaults for everything left out
+    let partial: AnalysisConfig = serde_json::from_str(r#"{ "ignore_patterns": ["legacy"] }"#).unwrap();
+    assert_eq!(partial.ignore_patterns, vec!["legacy".to_string()]);
+    assert!(partial.enable_complexity_warnings);
+}
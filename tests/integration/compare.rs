@@ -0,0 +1,73 @@
+use rustbrother::{analyze_directory, compare_reports, generate_report, load_report, ReportSnapshot};
+use rustbrother::reporter::format_comparison;
+use std::path::Path;
+use crate::support::config;
+
+fn comparison() -> rustbrother::ReportComparison {
+    let before = load_report(Path::new("tests/reports/before.json")).unwrap();
+    let after = load_report(Path::new("tests/reports/after.json")).unwrap();
+    compare_reports(&before, &after)
+}
+
+fn changes(deltas: &[rustbrother::CountDelta]) -> Vec<(&str, usize, usize, i64)> {
+    deltas.iter().map(|d| (d.name.as_str(), d.old, d.new, d.change)).collect()
+}
+
+#[test]
+fn test_added_and_removed_unused_classes() {
+    let comparison = comparison();
+    assert_eq!((comparison.unused_classes.old, comparison.unused_classes.new, comparison.unused_classes.change), (3, 2, -1));
+
+    // cardShadow only moved lines, so it is neither added nor removed
+    let added: Vec<&str> = comparison.added_unused_classes.iter().map(|c| c.name.as_str()).collect();
+    let removed: Vec<&str> = comparison.removed_unused_classes.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(added, vec!["tabsIndicator"]);
+    assert_eq!(removed, vec!["cardOld", "menuLegacy"]);
+}
+
+#[test]
+fn test_warning_deltas_by_severity_type_and_directory() {
+    let comparison = comparison();
+    assert_eq!(changes(&comparison.warnings_by_severity), vec![("High", 1, 0, -1), ("Medium", 2, 1, -1), ("Low", 0, 1, 1)]);
+    assert_eq!(changes(&comparison.warnings_by_type), vec![
+        ("DeepTemplateNesting", 2, 1, -1),
+        ("DynamicClassConstruction", 1, 0, -1),
+        ("Inline style prop", 0, 1, 1),
+    ]);
+
+    let directories: Vec<(&str, i64, i64)> = comparison.directories
+        .iter()
+        .map(|d| (d.directory.as_str(), d.unused_classes.change, d.complexity_warnings.change))
+        .collect();
+    assert_eq!(directories, vec![("src/card", -1, -1), ("src/menu", -1, -1), ("src/tabs", 1, 1)]);
+}
+
+#[test]
+fn test_comparison_formats() {
+    let comparison = comparison();
+
+    let text = format_comparison(&comparison, "text").unwrap();
+    assert!(text.contains("Unused classes: 3 → 2 (-1)"));
+    assert!(text.contains(".tabsIndicator (src/tabs/Tabs.module.scss:7)"));
+
+    let markdown = format_comparison(&comparison, "markdown").unwrap();
+    assert!(markdown.contains("| Unused classes | 3 | 2 | -1 |"));
+    assert!(markdown.contains("| `src/tabs` | 0 → 1 (+1) | 0 → 1 (+1) |"));
+    assert!(markdown.contains("- `.cardOld` in `src/card/Card.module.scss:12`"));
+
+    let json: serde_json::Value = serde_json::from_str(&format_comparison(&comparison, "json").unwrap()).unwrap();
+    assert_eq!(json["unused_classes"]["change"], -1);
+    assert_eq!(json["added_unused_classes"][0]["name"], "tabsIndicator");
+}
+
+#[test]
+fn test_compares_reports_written_by_json_format() {
+    let config = config();
+    let result = analyze_directory(Path::new("tests/components/gallery"), &config).unwrap();
+    let report: ReportSnapshot = serde_json::from_str(&generate_report(&result, "json").unwrap()).unwrap();
+    assert_eq!(report.unused_classes.len(), result.unused_classes.len());
+
+    let comparison = compare_reports(&report, &report);
+    assert_eq!(comparison.unused_classes.change, 0);
+    assert!(comparison.added_unused_classes.is_empty() && comparison.removed_unused_classes.is_empty());
+}
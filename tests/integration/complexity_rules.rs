@@ -0,0 +1,113 @@
+use rustbrother::{
+    find_complexity_warnings, find_complexity_warnings_with_rules, AnalysisConfig, ComplexityRule, ComplexityWarning,
+    ComplexityWarningType, RuleConfig, RuleRegistry, ScriptFile, WarningSeverity,
+};
+use std::path::Path;
+
+fn config(rules: &str) -> AnalysisConfig {
+    let mut config: AnalysisConfig = serde_json::from_str(&format!(r#"{{"complexity_rules": {}}}"#, rules)).unwrap();
+    config.ignore_patterns = vec![];
+    config.complexity_threshold = WarningSeverity::Low;
+    config
+}
+
+fn rule_ids(path: &str, config: &AnalysisConfig) -> Vec<String> {
+    find_complexity_warnings(Path::new(path), config).unwrap().into_iter().map(|w| w.rule_id).collect()
+}
+
+#[test]
+fn test_builtin_rules_have_stable_ids() {
+    assert_eq!(
+        RuleRegistry::default().ids(),
+        vec![
+            "dynamic-class-construction",
+            "conditional-class-assignment",
+            "deep-template-nesting",
+            "untracked-dynamic-pattern",
+            "deep-selector-nesting",
+            "long-descendant-chain",
+            "selector-concatenation",
+            "oversized-rule-block",
+        ]
+    );
+
+    let ids = rule_ids("tests/components/button", &config("{}"));
+    assert!(ids.contains(&"deep-template-nesting".to_string()));
+    assert!(ids.iter().all(|id| !id.is_empty()));
+}
+
+#[test]
+fn test_rules_can_be_disabled() {
+    let ids = rule_ids("tests/components/button", &config(r#"{"deep-template-nesting": {"enabled": false}, "selector-concatenation": {"enabled": false}}"#));
+    assert!(!ids.is_empty());
+    assert!(!ids.iter().any(|id| id == "deep-template-nesting" || id == "selector-concatenation"));
+}
+
+#[test]
+fn test_severity_and_suggestion_overrides() {
+    let config = config(r#"{"dynamic-class-construction": {"severity": "High", "suggestion": "Use the CLASS_MAP helper"}}"#);
+    let warnings: Vec<ComplexityWarning> = find_complexity_warnings(Path::new("tests/components/button"), &config)
+        .unwrap()
+        .into_iter()
+        .filter(|w| w.rule_id == "dynamic-class-construction")
+        .collect();
+
+    assert_eq!(warnings.len(), 2);
+    assert!(warnings.iter().all(|w| matches!(w.severity, WarningSeverity::High) && w.suggestion == "Use the CLASS_MAP helper"));
+}
+
+#[test]
+fn test_thresholds_from_config() {
+    let ids = rule_ids("tests/components/sidebar", &config("{}"));
+    assert!(ids.contains(&"oversized-rule-block".to_string()));
+    assert!(ids.contains(&"deep-selector-nesting".to_string()));
+
+    let relaxed = config(r#"{"oversized-rule-block": {"thresholds": {"max_declarations": 30}}, "deep-selector-nesting": {"thresholds": {"max_levels": 8}}}"#);
+    let ids = rule_ids("tests/components/sidebar", &relaxed);
+    assert!(!ids.contains(&"oversized-rule-block".to_string()));
+    assert!(!ids.contains(&"deep-selector-nesting".to_string()));
+}
+
+/// Flags `styles={...}` props - almost always a typo for `className`
+struct StylesPropRule;
+
+impl ComplexityRule for StylesPropRule {
+    fn id(&self) -> &str {
+        "styles-prop"
+    }
+
+    fn check_script(&self, file: &ScriptFile, _settings: &RuleConfig) -> Vec<ComplexityWarning> {
+        file.code()
+            .match_indices(" styles={")
+            .map(|(offset, found)| {
+                file.warning(
+                    offset + 1,
+                    offset + found.len(),
+                    ComplexityWarningType::Custom("styles prop typo".to_string()),
+                    file.snippet(offset + 1, offset + found.len()),
+                    "Did you mean className?",
+                    WarningSeverity::Medium,
+                )
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_custom_rules_run_alongside_builtins() {
+    let mut registry = RuleRegistry::default();
+    registry.register(Box::new(StylesPropRule));
+
+    let config = config("{}");
+    let warnings = find_complexity_warnings_with_rules(Path::new("tests/components/button"), &config, &registry).unwrap();
+
+    let custom: Vec<&ComplexityWarning> = warnings.iter().filter(|w| w.rule_id == "styles-prop").collect();
+    assert_eq!(custom.len(), 1);
+    assert_eq!(custom[0].start_line, 13);
+    assert!(warnings.iter().any(|w| w.rule_id == "dynamic-class-construction"));
+
+    // Custom rules honour config like the built-ins
+    let disabled = AnalysisConfig { complexity_rules: [("styles-prop".to_string(), RuleConfig { enabled: false, ..Default::default() })].into(), ..config };
+    let warnings = find_complexity_warnings_with_rules(Path::new("tests/components/button"), &disabled, &registry).unwrap();
+    assert!(warnings.iter().all(|w| w.rule_id != "styles-prop"));
+}
@@ -0,0 +1,135 @@
+use rustbrother::{analyze_directory, explain_class, find_dead_components, find_module_imports, find_stylesheet_imports, find_unreachable_stylesheets, generate_report, resolve_module_import, resolve_stylesheet_import, AnalysisConfig, AnalysisResult, ModuleGraph};
+use std::path::Path;
+use crate::support::{self, names};
+
+const STOREFRONT: &str = "tests/components/storefront";
+
+fn config() -> AnalysisConfig {
+    AnalysisConfig { entry_points: vec!["pages/".to_string()], ..support::config() }
+}
+
+fn analyze_storefront() -> AnalysisResult {
+    analyze_directory(Path::new(STOREFRONT), &config()).unwrap()
+}
+
+#[test]
+fn test_find_module_imports() {
+    let js = "import React from 'react';\nimport {\n  a,\n  b,\n} from './ab';\nimport './polyfill';\nexport * from \"./reexport\";\nconst c = require('./c');\nconst D = lazy(() => import('./D'));\n";
+    assert_eq!(find_module_imports(js), vec!["react", "./ab", "./polyfill", "./reexport", "./c", "./D"]);
+
+    // Commented-out imports and import-like strings don't add edges
+    let js = "// import Old from './Old';\n/* const legacy = require('./legacy');\n   import './polyfill'; */\nconst hint = \"import x from './x'\";\nimport Real from './Real'; // import './also-old'\n";
+    assert_eq!(find_module_imports(js), vec!["./Real"]);
+}
+
+#[test]
+fn test_resolve_module_import_tries_extensions_and_index_files() {
+    let page = Path::new(STOREFRONT).join("pages/index.tsx");
+    let card = resolve_module_import(&page, "../components/ProductCard").unwrap();
+    assert!(card.ends_with("components/ProductCard/index.ts"));
+    let reviews = resolve_module_import(&page, "../components/Reviews").unwrap();
+    assert!(reviews.ends_with("components/Reviews.tsx"));
+    assert_eq!(resolve_module_import(&page, "react"), None);
+}
+
+#[test]
+fn test_finds_components_unreachable_from_entry_points() {
+    let dead = find_dead_components(Path::new(STOREFRONT), &config()).unwrap();
+    let files: Vec<&str> = dead.iter().map(|c| c.file_path.trim_start_matches(STOREFRONT)).collect();
+    // The page's commented-out OldPromo export doesn't keep it alive; the unreachable formatPrice.ts utility and
+    // Badge.test.tsx aren't components
+    assert_eq!(files, vec!["/components/LegacyBanner/LegacyBanner.tsx", "/components/OldPromo.tsx"]);
+    assert!(dead[0].stylesheets[0].ends_with("LegacyBanner.module.scss"));
+
+    // Without entry points nothing is considered dead
+    let no_entries = AnalysisConfig { entry_points: vec![], ..config() };
+    assert!(find_dead_components(Path::new(STOREFRONT), &no_entries).unwrap().is_empty());
+}
+
+#[test]
+fn test_classes_used_only_by_dead_code_are_reclassified() {
+    let result = analyze_storefront();
+    assert_eq!(names(&result.dead_code_classes), vec!["banner", "bannerText", "highlight"]);
+    assert_eq!(names(&result.unused_classes), vec!["bannerClose", "oldHero"]);
+    assert_eq!(names(&result.used_classes), vec!["badge", "card", "heading", "page", "price", "reviews"]);
+
+    let text = generate_report(&result, "text").unwrap();
+    assert!(text.contains("Total CSS classes found: 11"));
+    assert!(text.contains("Dead Components"));
+    assert!(text.contains(".highlight (line 5)"));
+
+    let json: serde_json::Value = serde_json::from_str(&generate_report(&result, "json").unwrap()).unwrap();
+    assert_eq!(json["summary"]["dead_components"], 2);
+    assert_eq!(json["summary"]["dead_code_classes"], 3);
+    assert_eq!(json["summary"]["total_css_classes"], 11);
+}
+
+#[test]
+fn test_explain_class_used_only_by_dead_code() {
+    let explanation = explain_class(Path::new(STOREFRONT), &config(), "highlight").unwrap();
+    assert!(!explanation.is_used);
+    assert!(explanation.used_only_by_dead_code);
+    assert!(explanation.reason.contains("LegacyBanner.tsx"), "{}", explanation.reason);
+}
+
+#[test]
+fn test_find_stylesheet_imports() {
+    let scss = "@use 'sass:math';\n@use \"tokens\" as t;\n@import 'a', 'b';\n@import url(\"print.css\") print;\n@import url(https://fonts.example.com/inter.css);\n@forward 'mixins' show hero;\n";
+    assert_eq!(find_stylesheet_imports(scss), vec!["tokens", "a", "b", "print.css", "mixins"]);
+}
+
+#[test]
+fn test_resolve_stylesheet_import_finds_partials() {
+    let main = Path::new(STOREFRONT).join("styles/main.scss");
+    assert!(resolve_stylesheet_import(&main, "tokens").unwrap().ends_with("styles/_tokens.scss"));
+    assert!(resolve_stylesheet_import(&main, "typography").unwrap().ends_with("styles/typography.css"));
+    assert_eq!(resolve_stylesheet_import(&main, "missing"), None);
+}
+
+#[test]
+fn test_module_graph_follows_scripts_and_stylesheets() {
+    let graph = ModuleGraph::build(Path::new(STOREFRONT), &config()).unwrap();
+    let reachable: Vec<String> = {
+        let mut files: Vec<String> = graph
+            .reachable_from(&config().entry_points)
+            .into_iter()
+            .map(|file| file.to_string_lossy().trim_start_matches(STOREFRONT).to_string())
+            .collect();
+        files.sort();
+        files
+    };
+    assert_eq!(reachable, vec![
+        "/components/Badge.tsx",
+        "/components/ProductCard/ProductCard.module.scss",
+        "/components/ProductCard/ProductCard.tsx",
+        "/components/ProductCard/index.ts",
+        "/components/Reviews.module.scss",
+        "/components/Reviews.tsx",
+        "/components/shared.css",
+        "/pages/index.tsx",
+        "/styles/_tokens.scss",
+        "/styles/main.scss",
+        "/styles/typography.css",
+    ]);
+}
+
+#[test]
+fn test_reports_unreachable_stylesheets() {
+    let unreachable = find_unreachable_stylesheets(Path::new(STOREFRONT), &config()).unwrap();
+    let files: Vec<&str> = unreachable.iter().map(|file| file.trim_start_matches(STOREFRONT)).collect();
+    assert_eq!(files, vec![
+        "/components/LegacyBanner/LegacyBanner.module.scss",
+        "/styles/_old-mixins.scss",
+        "/styles/legacy.scss",
+    ]);
+
+    let result = analyze_storefront();
+    assert_eq!(result.unreachable_stylesheets, unreachable);
+    let text = generate_report(&result, "text").unwrap();
+    assert!(text.contains("Unreachable Stylesheets"));
+
+    // A stylesheet entry point makes its own imports reachable
+    let with_legacy = AnalysisConfig { entry_points: vec!["pages/".to_string(), "legacy.scss".to_string()], ..config() };
+    let unreachable = find_unreachable_stylesheets(Path::new(STOREFRONT), &with_legacy).unwrap();
+    assert_eq!(unreachable.len(), 1);
+}
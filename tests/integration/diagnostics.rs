@@ -0,0 +1,74 @@
+use rustbrother::{
+    analyze_directory, find_css_classes, find_file_diagnostics, generate_report_with_config, read_source_file, AnalysisConfig,
+    DiagnosticKind, Error,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::support::{scratch_tree, write_file};
+
+/// A tree with one good stylesheet, one with unbalanced braces and one that isn't UTF-8
+fn broken_tree(name: &str) -> PathBuf {
+    let dir = scratch_tree("diagnostics", name, &[
+        ("good.css", ".good { color: red; }\n"),
+        ("open.scss", ".open {\n  color: red;\n  .inner { color: blue; }\n"),
+        ("App.tsx", "export const App = () => <div className=\"good\" />;\n"),
+    ]);
+    write_file(&dir.join("latin1.css"), b".caf\xe9 { color: red; }\n");
+    dir
+}
+
+#[test]
+fn test_lenient_analysis_collects_diagnostics() {
+    let dir = broken_tree("lenient");
+    let result = analyze_directory(&dir, &AnalysisConfig::default()).unwrap();
+
+    let kinds: Vec<(String, DiagnosticKind, Option<usize>)> = result.diagnostics
+        .iter()
+        .map(|d| (Path::new(&d.file_path).file_name().unwrap().to_string_lossy().to_string(), d.kind, d.line_number))
+        .collect();
+    assert_eq!(kinds, [
+        ("latin1.css".to_string(), DiagnosticKind::NonUtf8, None),
+        ("open.scss".to_string(), DiagnosticKind::ParseError, Some(1)),
+    ]);
+    assert!(result.diagnostics[1].message.contains("never closed"));
+
+    // The readable files are still analyzed
+    assert!(result.used_classes.iter().any(|class| class.name == "good"));
+    assert!(result.unused_classes.iter().any(|class| class.name == "inner"));
+
+    let config = AnalysisConfig { report_timestamp: false, ..Default::default() };
+    let json: serde_json::Value = serde_json::from_str(&generate_report_with_config(&result, "json", &config).unwrap()).unwrap();
+    assert_eq!(json["summary"]["file_diagnostics"], 2);
+    assert_eq!(json["diagnostics"][0]["kind"], "NonUtf8");
+    let text = generate_report_with_config(&result, "text", &config).unwrap();
+    assert!(text.contains("Files Skipped or Misread"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_strict_mode_fails_with_every_diagnostic() {
+    let dir = broken_tree("strict");
+    let config = AnalysisConfig { strict: true, ..Default::default() };
+
+    let error = analyze_directory(&dir, &config).unwrap_err();
+    match &error {
+        Error::Strict { diagnostics } => assert_eq!(diagnostics.len(), 2),
+        other => panic!("expected a strict mode error, got {:?}", other),
+    }
+
+    // Finders called on their own fail on the first unreadable file
+    let error = find_css_classes(&dir, &config).unwrap_err();
+    assert!(matches!(error, Error::NonUtf8 { .. }), "{}", error);
+    assert!(find_css_classes(&dir, &AnalysisConfig::default()).is_ok());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_unreadable_file_and_clean_fixtures() {
+    // A directory can't be read as a file
+    let error = read_source_file(Path::new("tests/components")).err().unwrap();
+    assert!(matches!(error, Error::Read { .. }));
+    assert_eq!(error.to_diagnostic().unwrap().kind, DiagnosticKind::Unreadable);
+
+    assert!(find_file_diagnostics(Path::new("tests/components"), &AnalysisConfig::default()).is_empty());
+}
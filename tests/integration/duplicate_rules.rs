@@ -0,0 +1,63 @@
+use rustbrother::{analyze_directory, group_duplicate_rules, parse_rules, AnalysisConfig};
+use std::path::Path;
+use crate::support::config;
+
+#[test]
+fn test_parse_rules_resolves_nesting() {
+    let content = std::fs::read_to_string("tests/components/panel/Panel.module.scss").unwrap();
+    let rules = parse_rules(&content, "Panel.module.scss");
+
+    let outline = rules.iter().find(|r| r.raw_selector == "&_outline").expect("&_outline rule");
+    assert_eq!(outline.selector, ".panel_outline");
+    assert_eq!(outline.depth, 1);
+    assert_eq!((outline.start_line, outline.end_line), (7, 11));
+    assert_eq!(outline.declarations.len(), 3);
+
+    // `@media` inside `.panelGraphicHide` is a conditional copy of that rule
+    let hide: Vec<_> = rules.iter().filter(|r| r.selector == ".panelGraphicHide").collect();
+    assert_eq!(hide.len(), 2);
+    assert_eq!(hide[1].conditions, vec!["@media (min-width: 768px)".to_string()]);
+    assert_eq!(hide[1].declarations[0].value, "block");
+}
+
+#[test]
+fn test_parse_rules_reads_important_and_selector_lists() {
+    let css = ".a, .b {\n  color: red !important;\n  margin: 0\n}\n.a { &:hover, .c { color: blue } }\n@keyframes spin { from { opacity: 0; } }";
+    let rules = parse_rules(css, "test.scss");
+
+    assert_eq!(rules.len(), 3, "keyframe steps aren't rules");
+    assert_eq!(rules[0].selector, ".a, .b");
+    assert!(rules[0].declarations[0].important);
+    assert_eq!(rules[0].declarations[0].value, "red");
+    assert_eq!(rules[0].declarations[1].property, "margin");
+    assert_eq!(rules[2].selector, ".a:hover, .a .c");
+}
+
+#[test]
+fn test_identical_and_near_identical_groups() {
+    let result = analyze_directory(Path::new("tests/components"), &config()).unwrap();
+
+    // cardFooter and toolbarFooter only differ in hex color case
+    let footer = result.duplicate_rules.iter().find(|g| g.suggested_name == "footer").expect("footer group");
+    assert!(footer.identical);
+    assert_eq!(footer.occurrences.len(), 2);
+    assert!(footer.estimated_bytes_saved > 0);
+
+    // Toolbar's copy of srOnly has one extra declaration
+    let hidden = result.duplicate_rules.iter().find(|g| g.suggested_name == "visually-hidden").expect("visually-hidden group");
+    assert!(!hidden.identical);
+    assert!(hidden.occurrences.iter().any(|o| o.selector == ".srOnly"));
+    assert!(hidden.occurrences.iter().any(|o| o.selector == ".visuallyHidden"));
+    assert!(!hidden.declarations.iter().any(|d| d.starts_with("clip-path")), "only shared declarations are listed");
+}
+
+#[test]
+fn test_similarity_threshold_is_configurable() {
+    let content = std::fs::read_to_string("tests/components/toolbar/Toolbar.module.scss").unwrap();
+    let mut rules = parse_rules(&content, "Toolbar.module.scss");
+    let panel = std::fs::read_to_string("tests/components/panel/Panel.module.scss").unwrap();
+    rules.extend(parse_rules(&panel, "Panel.module.scss"));
+
+    let strict = AnalysisConfig { duplicate_similarity: 1.0, ..config() };
+    assert!(group_duplicate_rules(&rules, &strict).iter().all(|g| g.identical));
+}
@@ -18,7 +18,7 @@ fn test_full_component_analysis() {
     println!("  Unused classes: {}", result.unused_classes.len());
     
     // Should find some classes
-    assert!(result.used_classes.len() > 0, "Should find used classes");
+    assert!(!result.used_classes.is_empty(), "Should find used classes");
     
     println!("✅ Full analysis test passed");
 }
\ No newline at end of file
@@ -0,0 +1,83 @@
+use rustbrother::{analyze_directory, generate_report_with_config, AnalysisConfig};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::support::scratch_tree;
+
+fn template_dir(name: &str, templates: &[(&str, &str)]) -> PathBuf {
+    scratch_tree("templates", name, templates)
+}
+
+fn render(fixture: &str, template_dir: Option<PathBuf>) -> anyhow::Result<String> {
+    let config = AnalysisConfig {
+        include_css_modules: true,
+        report_timestamp: false,
+        template_dir,
+        ..Default::default()
+    };
+    let result = analyze_directory(&Path::new("tests/components").join(fixture), &config).unwrap();
+    generate_report_with_config(&result, "html", &config)
+}
+
+#[test]
+fn test_builtin_templates_render_every_section() {
+    let html = render("alert", None).unwrap();
+    assert!(html.contains("⚔️ RustBrother CSS Analysis Report"));
+    assert!(!html.contains("Generated on"), "the timestamp is turned off");
+    assert!(html.contains("📄 tests/components/alert/Alert.tsx"));
+    assert!(html.contains("4 dark spells"));
+    assert!(html.contains("border-left: 4px solid #fbd38d;"));
+    // Patterns are escaped
+    assert!(html.contains("&#x27;&#x27;"));
+    assert!(!html.contains("{{") && !html.contains("{%"));
+}
+
+#[test]
+fn test_template_dir_overrides_a_partial() {
+    let dir = template_dir("partial", &[(
+        "complexity-warning.html",
+        r#"<li data-rule="{{ warning.rule_id }}" data-severity="{{ warning.severity }}">{{ file.path }}:{{ warning.lines }}</li>"#,
+    )]);
+    let html = render("alert", Some(dir.clone())).unwrap();
+    fs::remove_dir_all(&dir).ok();
+
+    assert!(html.contains(r#"<li data-rule="conditional-class-assignment" data-severity="Medium">tests/components/alert/Alert.tsx:6-8</li>"#), "{}", html);
+    // The other built-in templates are still used
+    assert!(html.contains("4 dark spells"));
+}
+
+#[test]
+fn test_template_dir_replaces_the_report_with_loops_and_custom_partials() {
+    let dir = template_dir("report", &[
+        (
+            "report.html",
+            "<h1>Acme CSS</h1>\n{{ summary.unused_classes }} unused\n{% for file in unused_files %}{% include \"acme-file.html\" %}{% endfor %}",
+        ),
+        ("acme-file.html", "[{{ file.path }}:{% for class in file.classes %} .{{ class.name }}@{{ class.line_number }}{% endfor %}]\n"),
+    ]);
+    let html = render("modal", Some(dir.clone())).unwrap();
+    fs::remove_dir_all(&dir).ok();
+
+    assert_eq!(html, "<h1>Acme CSS</h1>\n3 unused\n[tests/components/modal/utils.module.css: .u-hidden@2 .u-block@3 .u-flex@4]");
+}
+
+#[test]
+fn test_template_errors_name_the_template() {
+    let dir = template_dir("broken", &[("class-item.html", "{% for class in %}")]);
+    let error = render("modal", Some(dir.clone())).unwrap_err().to_string();
+    fs::remove_dir_all(&dir).ok();
+    assert!(error.contains("class-item.html"), "{}", error);
+
+    let error = render("modal", Some(PathBuf::from("tests/no-such-theme"))).unwrap_err().to_string();
+    assert!(error.contains("Failed to read template directory tests/no-such-theme"), "{}", error);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_unreadable_template_is_an_error() {
+    // A dangling `report.html` must not quietly fall back to the built-in report
+    let dir = template_dir("dangling", &[]);
+    std::os::unix::fs::symlink(dir.join("missing.html"), dir.join("report.html")).unwrap();
+    let error = render("modal", Some(dir.clone())).unwrap_err().to_string();
+    fs::remove_dir_all(&dir).ok();
+    assert!(error.contains("Failed to read template") && error.contains("report.html"), "{}", error);
+}
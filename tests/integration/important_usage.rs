@@ -0,0 +1,54 @@
+use rustbrother::{analyze_directory, find_excessive_important_usage, parse_rules, AnalysisConfig, StylesheetWarningType, WarningSeverity};
+use std::path::Path;
+use crate::support::config;
+
+#[test]
+fn test_important_overuse_in_component_module() {
+    let result = analyze_directory(Path::new("tests/components/modal"), &config()).unwrap();
+    let modal: Vec<_> = result.stylesheet_warnings.iter().filter(|w| w.file_path.ends_with("Modal.module.scss")).collect();
+
+    let barrage = modal.iter().find(|w| w.warning_type == StylesheetWarningType::ImportantOverusedInRule).expect("rule warning");
+    assert_eq!(barrage.selector.as_deref(), Some(".modalClose"));
+    assert_eq!(barrage.line_number, 14);
+
+    let scoped = modal.iter().find(|w| w.warning_type == StylesheetWarningType::ImportantInComponentModule).expect("module warning");
+    assert_eq!(scoped.selector.as_deref(), Some(".modalContent"));
+
+    let density = modal.iter().find(|w| w.warning_type == StylesheetWarningType::ImportantDensity).expect("density warning");
+    assert!(matches!(density.severity, WarningSeverity::High), "50% is more than twice the 10% default");
+}
+
+#[test]
+fn test_utility_sheets_may_use_important() {
+    let result = analyze_directory(Path::new("tests/components/modal"), &config()).unwrap();
+    assert!(result.stylesheet_warnings.iter().all(|w| !w.file_path.ends_with("utils.module.css")));
+}
+
+#[test]
+fn test_important_thresholds_are_configurable() {
+    let css = ".a { color: red !important; margin: 0; }\n.a:hover { color: blue !important; }\n.b { padding: 0; margin: 0; border: 0; top: 0; }";
+    let rules = parse_rules(css, "global.css");
+
+    let lenient = AnalysisConfig { important_rule_limit: 3, important_density: 0.5, ..config() };
+    assert!(find_excessive_important_usage(&rules, &lenient).is_empty());
+
+    // Two !important on `.a`-family selectors over 7 declarations is ~29%
+    let strict = AnalysisConfig { important_density: 0.2, complexity_threshold: WarningSeverity::Low, ..config() };
+    let warnings = find_excessive_important_usage(&rules, &strict);
+    assert!(warnings.iter().any(|w| w.warning_type == StylesheetWarningType::ImportantDensity && w.selector.is_none()));
+}
+
+#[test]
+fn test_important_piling_up_on_a_selector_is_reported_by_default() {
+    let css = ".a { color: red !important; }\n@media (min-width: 40rem) {\n  .a { margin: 0 !important; }\n}\n@media print {\n  .a { display: none !important; }\n}\n";
+    let warnings = find_excessive_important_usage(&parse_rules(css, "global.css"), &AnalysisConfig::default());
+    let piled = warnings.iter().find(|w| w.warning_type == StylesheetWarningType::ImportantOverusedOnSelector).expect("selector warning");
+    assert_eq!(piled.selector.as_deref(), Some(".a"));
+    assert!(matches!(piled.severity, WarningSeverity::Medium));
+
+    // Twice the limit is High, like the density check
+    let css = css.repeat(2);
+    let warnings = find_excessive_important_usage(&parse_rules(&css, "global.css"), &AnalysisConfig::default());
+    let piled = warnings.iter().find(|w| w.warning_type == StylesheetWarningType::ImportantOverusedOnSelector).expect("selector warning");
+    assert!(matches!(piled.severity, WarningSeverity::High));
+}
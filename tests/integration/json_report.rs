@@ -0,0 +1,59 @@
+use rustbrother::{analyze_directory, generate_report_with_config, load_report, report_json_schema, AnalysisConfig, JsonReport, REPORT_SCHEMA_VERSION};
+use std::path::Path;
+
+fn config(report_timestamp: bool) -> AnalysisConfig {
+    AnalysisConfig {
+        include_css_modules: true,
+        report_timestamp,
+        ..Default::default()
+    }
+}
+
+fn json_report(config: &AnalysisConfig) -> String {
+    let result = analyze_directory(Path::new("tests/components"), config).unwrap();
+    generate_report_with_config(&result, "json", config).unwrap()
+}
+
+#[test]
+fn test_published_schema_matches_the_report_types() {
+    let published = std::fs::read_to_string("schema/report.schema.json").unwrap();
+    assert_eq!(published, report_json_schema().unwrap(), "schema/report.schema.json is stale - regenerate it with `rustbrother schema`");
+}
+
+#[test]
+fn test_report_is_versioned_and_reproducible_without_timestamp() {
+    let config = config(false);
+    let first = json_report(&config);
+    assert_eq!(first, json_report(&config));
+
+    let json: serde_json::Value = serde_json::from_str(&first).unwrap();
+    assert_eq!(json["schema_version"], REPORT_SCHEMA_VERSION);
+    assert!(json.get("timestamp").is_none());
+}
+
+#[test]
+fn test_report_has_timestamp_by_default() {
+    let json: serde_json::Value = serde_json::from_str(&json_report(&config(true))).unwrap();
+    assert!(json["timestamp"].as_str().is_some_and(|timestamp| chrono::DateTime::parse_from_rfc3339(timestamp).is_ok()));
+}
+
+#[test]
+fn test_report_round_trips_with_findings_in_file_and_line_order() {
+    let report: JsonReport = serde_json::from_str(&json_report(&config(false))).unwrap();
+    assert_eq!(report.summary.unused_classes, report.unused_classes.len());
+    assert_eq!(report.summary.complexity_warnings.total, report.complexity_warnings.len());
+
+    let classes: Vec<(&str, usize)> = report.unused_classes.iter().map(|c| (c.file_path.as_str(), c.line_number)).collect();
+    assert!(classes.windows(2).all(|pair| pair[0] <= pair[1]), "unused classes are not sorted: {:?}", classes);
+    let warnings: Vec<(&str, usize)> = report.complexity_warnings.iter().map(|w| (w.file_path.as_str(), w.start_line)).collect();
+    assert!(warnings.windows(2).all(|pair| pair[0] <= pair[1]), "complexity warnings are not sorted: {:?}", warnings);
+}
+
+#[test]
+fn test_compare_rejects_reports_from_another_major_version() {
+    let path = std::env::temp_dir().join(format!("rustbrother-report-v99-{}.json", std::process::id()));
+    std::fs::write(&path, r#"{ "schema_version": "99.0", "unused_classes": [] }"#).unwrap();
+    let error = load_report(&path).unwrap_err().to_string();
+    std::fs::remove_file(&path).ok();
+    assert!(error.contains("schema version 99.0"), "{}", error);
+}
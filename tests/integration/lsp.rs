@@ -0,0 +1,223 @@
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use rustbrother::{lsp, AnalysisConfig};
+use serde_json::json;
+use std::path::PathBuf;
+use std::thread;
+use crate::support::{config, scratch_tree, write_file};
+
+fn button_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/components/button")
+}
+
+fn request(client: &Connection, id: i32, method: &str, params: serde_json::Value) -> serde_json::Value {
+    client.sender.send(Message::Request(Request::new(RequestId::from(id), method.to_string(), params))).unwrap();
+    loop {
+        match client.receiver.recv().unwrap() {
+            Message::Response(response) if response.id == RequestId::from(id) => {
+                return response.result.expect("request should succeed");
+            }
+            _ => continue,
+        }
+    }
+}
+
+fn notify(client: &Connection, method: &str, params: serde_json::Value) {
+    client.sender.send(Message::Notification(Notification::new(method.to_string(), params))).unwrap();
+}
+
+/// The params of the next notification with `method`
+fn next_notification(client: &Connection, method: &str) -> serde_json::Value {
+    loop {
+        if let Message::Notification(notification) = client.receiver.recv().unwrap() {
+            if notification.method == method {
+                return notification.params;
+            }
+        }
+    }
+}
+
+#[test]
+fn test_lsp_diagnostics_and_definition() {
+    let (server, client) = Connection::memory();
+    let root = button_dir();
+    let config = config();
+    let server_thread = thread::spawn(move || lsp::serve(&server, Some(root), config).unwrap());
+
+    let root_uri = lsp_types::Url::from_file_path(button_dir()).unwrap();
+    request(&client, 1, "initialize", json!({ "capabilities": {}, "rootUri": root_uri }));
+    notify(&client, "initialized", json!({}));
+
+    // The initial analysis publishes unused classes for the stylesheet
+    let stylesheet_uri = lsp_types::Url::from_file_path(button_dir().join("Button.module.scss")).unwrap();
+    let diagnostics = loop {
+        if let Message::Notification(notification) = client.receiver.recv().unwrap() {
+            if notification.method == "textDocument/publishDiagnostics" && notification.params["uri"] == json!(stylesheet_uri) {
+                break notification.params["diagnostics"].as_array().unwrap().clone();
+            }
+        }
+    };
+    assert!(diagnostics.iter().any(|d| d["message"] == "Unused CSS class `.iconLarge`"), "iconLarge should be reported: {:?}", diagnostics);
+    assert!(diagnostics.iter().all(|d| d["code"] == "unused-class"));
+
+    // styles.buttonText jumps to the class in the module
+    let component = std::fs::read_to_string(button_dir().join("Button.tsx")).unwrap();
+    let (line, text) = component.lines().enumerate().find(|(_, l)| l.contains("styles.buttonText")).unwrap();
+    let character = text.find("buttonText").unwrap() + 2;
+    let component_uri = lsp_types::Url::from_file_path(button_dir().join("Button.tsx")).unwrap();
+    let definition = request(&client, 2, "textDocument/definition", json!({
+        "textDocument": { "uri": component_uri },
+        "position": { "line": line, "character": character },
+    }));
+    assert_eq!(definition["uri"], json!(stylesheet_uri), "definition should point at the stylesheet");
+
+    // And back again: the class finds its JSX usage
+    let line = definition["range"]["start"]["line"].clone();
+    let character = definition["range"]["start"]["character"].as_u64().unwrap() + 1;
+    let references = request(&client, 3, "textDocument/references", json!({
+        "textDocument": { "uri": stylesheet_uri },
+        "position": { "line": line, "character": character },
+        "context": { "includeDeclaration": false },
+    }));
+    assert!(references.as_array().unwrap().iter().any(|r| r["uri"] == json!(component_uri)));
+
+    // The unused rule can be deleted with a quick fix covering the whole block
+    let icon_large = diagnostics.iter().find(|d| d["message"] == "Unused CSS class `.iconLarge`").unwrap().clone();
+    let actions = request(&client, 4, "textDocument/codeAction", json!({
+        "textDocument": { "uri": stylesheet_uri },
+        "range": icon_large["range"],
+        "context": { "diagnostics": [icon_large] },
+    }));
+    let edits = &actions[0]["edit"]["changes"][stylesheet_uri.as_str()];
+    assert_eq!(edits[0]["newText"], "");
+    assert_eq!(edits[0]["range"]["start"]["line"], icon_large["range"]["start"]["line"]);
+    assert_eq!(edits[0]["range"]["end"]["line"].as_u64().unwrap(), icon_large["range"]["start"]["line"].as_u64().unwrap() + 4);
+
+    request(&client, 5, "shutdown", json!(null));
+    notify(&client, "exit", json!(null));
+    server_thread.join().unwrap();
+}
+
+#[test]
+fn test_lsp_keeps_serving_when_the_analysis_fails() {
+    let root = scratch_tree("lsp", "failing", &[
+        ("Button.tsx", "import styles from './Button.module.css';\nexport const Button = () => <button className={styles.primary} />;\n"),
+        ("Button.module.css", ".primary { color: red; }\n.stale {\n  color: gray;\n}\n"),
+    ]);
+    let config = AnalysisConfig { strict: true, ..config() };
+    let (server, client) = Connection::memory();
+    let server_root = root.clone();
+    let server_thread = thread::spawn(move || lsp::serve(&server, Some(server_root), config).unwrap());
+
+    request(&client, 1, "initialize", json!({ "capabilities": {} }));
+    notify(&client, "initialized", json!({}));
+    let stylesheet_uri = lsp_types::Url::from_file_path(root.join("Button.module.css")).unwrap();
+    let published = next_notification(&client, "textDocument/publishDiagnostics");
+    assert_eq!(published["uri"], json!(stylesheet_uri));
+    let stale = published["diagnostics"][0].clone();
+
+    // In strict mode a file that isn't UTF-8 fails the analysis the next save runs
+    write_file(&root.join("legacy.css"), [0xff, 0xfe, b'.']);
+    notify(&client, "textDocument/didSave", json!({ "textDocument": { "uri": stylesheet_uri } }));
+    let shown = next_notification(&client, "window/showMessage");
+    assert!(shown["message"].as_str().unwrap().contains("strict mode"), "{}", shown);
+
+    // A malformed notification is logged instead of ending the session
+    notify(&client, "textDocument/didOpen", json!({ "textDocument": 42 }));
+    let logged = next_notification(&client, "window/logMessage");
+    assert!(logged["message"].as_str().unwrap().contains("textDocument/didOpen"), "{}", logged);
+
+    // Requests are still answered from the last good analysis
+    let actions = request(&client, 2, "textDocument/codeAction", json!({
+        "textDocument": { "uri": stylesheet_uri },
+        "range": stale["range"],
+        "context": { "diagnostics": [stale] },
+    }));
+    assert_eq!(actions.as_array().unwrap().len(), 1, "{}", actions);
+
+    request(&client, 3, "shutdown", json!(null));
+    notify(&client, "exit", json!(null));
+    server_thread.join().unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_lsp_analyzes_unsaved_edits() {
+    let root = scratch_tree("lsp", "editing", &[
+        ("Button.tsx", "import styles from './Button.module.css';\nexport const Button = () => <button className={styles.primary} />;\n"),
+        ("Button.module.css", ".primary { color: red; }\n.stale {\n  color: gray;\n}\n"),
+    ]);
+    let (server, client) = Connection::memory();
+    let server_root = root.clone();
+    let server_thread = thread::spawn(move || lsp::serve(&server, Some(server_root), config()).unwrap());
+
+    request(&client, 1, "initialize", json!({ "capabilities": {} }));
+    notify(&client, "initialized", json!({}));
+    let published = next_notification(&client, "textDocument/publishDiagnostics");
+    assert_eq!(published["diagnostics"][0]["message"], "Unused CSS class `.stale`");
+
+    // Typing a use of `.stale` clears its diagnostic without saving
+    let component_uri = lsp_types::Url::from_file_path(root.join("Button.tsx")).unwrap();
+    let edited = "import styles from './Button.module.css';\nexport const Button = () => <button className={`${styles.primary} ${styles.stale}`} />;\n";
+    notify(&client, "textDocument/didOpen", json!({
+        "textDocument": { "uri": component_uri, "languageId": "typescriptreact", "version": 1, "text": std::fs::read_to_string(root.join("Button.tsx")).unwrap() },
+    }));
+    notify(&client, "textDocument/didChange", json!({
+        "textDocument": { "uri": component_uri, "version": 2 },
+        "contentChanges": [{ "text": edited }],
+    }));
+    let published = next_notification(&client, "textDocument/publishDiagnostics");
+    assert_eq!(published["diagnostics"], json!([]), "{}", published);
+
+    // Closing the buffer without saving goes back to the file on disk
+    notify(&client, "textDocument/didClose", json!({ "textDocument": { "uri": component_uri } }));
+    let published = next_notification(&client, "textDocument/publishDiagnostics");
+    assert_eq!(published["diagnostics"][0]["message"], "Unused CSS class `.stale`");
+
+    request(&client, 2, "shutdown", json!(null));
+    notify(&client, "exit", json!(null));
+    server_thread.join().unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_lsp_positions_count_utf16_code_units() {
+    let line = "export const Card = () => <p>🎉 <span className={styles.title} /> {styles.titel}</p>;";
+    let root = scratch_tree("lsp", "utf16", &[
+        ("Card.tsx", &format!("import styles from './Card.module.css';\n{}\n", line)),
+        ("Card.module.css", ".title { font-weight: 600; }\n"),
+    ]);
+    let (server, client) = Connection::memory();
+    let server_root = root.clone();
+    let server_thread = thread::spawn(move || lsp::serve(&server, Some(server_root), config()).unwrap());
+
+    request(&client, 1, "initialize", json!({ "capabilities": {} }));
+    notify(&client, "initialized", json!({}));
+
+    // The emoji is one character but two UTF-16 code units
+    let utf16_column = |needle: &str| line[..line.find(needle).unwrap()].encode_utf16().count();
+    let component_uri = lsp_types::Url::from_file_path(root.join("Card.tsx")).unwrap();
+    let published = next_notification(&client, "textDocument/publishDiagnostics");
+    assert_eq!(published["uri"], json!(component_uri), "{}", published);
+    assert_eq!(published["diagnostics"][0]["range"]["start"]["character"], utf16_column("titel"));
+    assert_eq!(published["diagnostics"][0]["range"]["end"]["character"], utf16_column("titel") + 5);
+
+    let definition = request(&client, 2, "textDocument/definition", json!({
+        "textDocument": { "uri": component_uri },
+        "position": { "line": 1, "character": utf16_column("title") + 1 },
+    }));
+    let stylesheet_uri = lsp_types::Url::from_file_path(root.join("Card.module.css")).unwrap();
+    assert_eq!(definition["uri"], json!(stylesheet_uri), "{}", definition);
+
+    let references = request(&client, 3, "textDocument/references", json!({
+        "textDocument": { "uri": stylesheet_uri },
+        "position": { "line": 0, "character": 2 },
+        "context": { "includeDeclaration": false },
+    }));
+    assert_eq!(references[0]["range"]["start"]["character"], utf16_column("title"), "{}", references);
+    assert_eq!(references[0]["range"]["end"]["character"], utf16_column("title") + 5);
+
+    request(&client, 4, "shutdown", json!(null));
+    notify(&client, "exit", json!(null));
+    server_thread.join().unwrap();
+    std::fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,90 @@
+use rustbrother::{analyze_directory, generate_report, generate_report_with_config, AnalysisConfig};
+use std::fs;
+use std::path::Path;
+use crate::support::scratch_tree;
+
+fn analyze(fixture: &str) -> (rustbrother::AnalysisResult, AnalysisConfig) {
+    let config = AnalysisConfig {
+        include_css_modules: true,
+        ..Default::default()
+    };
+    let result = analyze_directory(&Path::new("tests/components").join(fixture), &config).unwrap();
+    (result, config)
+}
+
+#[test]
+fn test_markdown_summary_table_and_sections() {
+    let (result, _) = analyze("button");
+    let report = generate_report(&result, "markdown").unwrap();
+
+    assert!(report.starts_with("# ⚔️ RustBrother CSS Analysis Report"));
+    assert!(report.contains("| Metric | Count |"));
+    assert!(report.contains(&format!("| 🗑️ Unused classes | {} (", result.unused_classes.len())));
+    assert!(report.contains("| 🔴 High complexity warnings |"));
+
+    let class = result.unused_classes.first().expect("the button fixture has unused classes");
+    assert!(report.contains(&format!("## 🗑️ Unused classes ({})", result.unused_classes.len())));
+    assert!(report.contains(&format!("<summary><code>{}</code>", class.file_path)));
+    assert!(report.contains(&format!("- `.{}` (line {})", class.name, class.line_number)));
+    assert!(report.contains("</details>"));
+
+    assert!(!result.complexity_warnings.is_empty(), "the button fixture has complexity warnings");
+    assert!(report.contains(&format!("## ⚠️ Complexity warnings ({})", result.complexity_warnings.len())));
+    assert!(report.contains(&format!("`{}`", result.complexity_warnings[0].rule_id)));
+
+    // `md` is an alias
+    assert_eq!(generate_report(&result, "md").unwrap(), report);
+}
+
+#[test]
+fn test_markdown_source_links() {
+    let (result, mut config) = analyze("modal");
+    let class = result.unused_classes.first().expect("the modal fixture has unused classes").clone();
+    config.source_url_template = Some("https://github.com/acme/shop/blob/main/{file}#L{line}".to_string());
+
+    let report = generate_report_with_config(&result, "markdown", &config).unwrap();
+    let url = format!("https://github.com/acme/shop/blob/main/{}#L{}", class.file_path.trim_start_matches("./"), class.line_number);
+    assert!(report.contains(&format!("- `.{}` ([line {}]({}))", class.name, class.line_number, url)), "{}", report);
+}
+
+#[test]
+fn test_markdown_complexity_warnings_link_line_spans() {
+    let mut config = AnalysisConfig {
+        include_css_modules: true,
+        ..Default::default()
+    };
+    let result = analyze_directory(Path::new("tests/components/card"), &config).unwrap();
+    let warning = result.complexity_warnings.first().expect("the card fixture has complexity warnings").clone();
+    config.source_url_template = Some("https://git.example.com/shop/-/blob/main/{file}#L{line}-{end_line}".to_string());
+
+    let report = generate_report_with_config(&result, "markdown", &config).unwrap();
+    assert!(report.contains(&format!("## ⚠️ Complexity warnings ({})", result.complexity_warnings.len())));
+    assert!(report.contains(&format!("<summary><code>{}</code> - {} warning(s)</summary>", warning.file_path, result.complexity_warnings.len())));
+    assert!(report.contains(&format!("`{}`", warning.rule_id)));
+    assert!(report.contains(&format!("#L{}-{})", warning.start_line, warning.end_line)), "{}", report);
+    assert!(report.contains(&format!("💡 {}", warning.suggestion)));
+}
+
+#[test]
+fn test_markdown_links_are_relative_to_the_repository_root() {
+    let repo = scratch_tree("markdown", "repo", &[
+        ("app/src/Card.tsx", "import styles from './<Card>.module.css';\nexport const Card = () => <div className={styles.card} />;\n"),
+        ("app/src/<Card>.module.css", ".card { padding: 0; }\n.old { margin: 0; }\n"),
+    ]);
+    fs::create_dir(repo.join(".git")).unwrap();
+
+    // Analyzed by absolute path, the link still starts at the repository root
+    let config = AnalysisConfig {
+        include_css_modules: true,
+        source_url_template: Some("https://github.com/acme/shop/blob/main/{file}#L{line}".to_string()),
+        ..Default::default()
+    };
+    let result = analyze_directory(&repo.join("app/src"), &config).unwrap();
+    let report = generate_report_with_config(&result, "markdown", &config).unwrap();
+    assert!(report.contains("(https://github.com/acme/shop/blob/main/app/src/<Card>.module.css#L2)"), "{}", report);
+
+    // The summary is raw HTML, so the path is escaped there
+    assert!(report.contains("<summary><code>"), "{}", report);
+    assert!(report.contains("&lt;Card&gt;.module.css</code> - 1 unused</summary>"), "{}", report);
+    fs::remove_dir_all(&repo).unwrap();
+}
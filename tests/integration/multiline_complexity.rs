@@ -0,0 +1,59 @@
+use rustbrother::{find_complexity_warnings, AnalysisConfig, ComplexityWarning, ComplexityWarningType, WarningSeverity};
+use std::path::Path;
+use crate::support::config;
+
+fn alert_warnings() -> Vec<ComplexityWarning> {
+    let config = AnalysisConfig { complexity_threshold: WarningSeverity::Low, ..config() };
+    find_complexity_warnings(Path::new("tests/components/alert"), &config)
+        .unwrap()
+        .into_iter()
+        .filter(|w| w.file_path.ends_with(".tsx"))
+        .collect()
+}
+
+fn spans(warnings: &[ComplexityWarning], kind: ComplexityWarningType) -> Vec<(usize, usize)> {
+    let kind = format!("{:?}", kind);
+    warnings
+        .iter()
+        .filter(|w| format!("{:?}", w.warning_type) == kind)
+        .map(|w| (w.start_line, w.end_line))
+        .collect()
+}
+
+#[test]
+fn test_multi_line_ternary_is_one_expression() {
+    let warnings = alert_warnings();
+    let conditional = spans(&warnings, ComplexityWarningType::ConditionalClassAssignment);
+
+    assert!(conditional.contains(&(6, 8)), "{:?}", conditional);
+    let tone = warnings.iter().find(|w| w.start_line == 6).unwrap();
+    assert_eq!(tone.pattern, "const toneClass = tone ? styles[`alert_${tone}`] : styles.alert_info");
+}
+
+#[test]
+fn test_ternaries_followed_through_intermediate_variables() {
+    let warnings = alert_warnings();
+
+    let chained = warnings
+        .iter()
+        .find(|w| matches!(w.warning_type, ComplexityWarningType::ConditionalClassAssignment) && w.start_line == 13)
+        .expect("styles[`alert_${layout}`] should be flagged");
+    assert!(chained.pattern.contains("`layout` is picked by the ternary on line 12"), "{}", chained.pattern);
+}
+
+#[test]
+fn test_nested_template_spanning_lines() {
+    let warnings = alert_warnings();
+
+    assert_eq!(spans(&warnings, ComplexityWarningType::DeepTemplateNesting), vec![(18, 20)]);
+    assert_eq!(spans(&warnings, ComplexityWarningType::DynamicClassConstruction), vec![(7, 7), (13, 13), (19, 19)]);
+}
+
+#[test]
+fn test_comments_strings_and_components_are_not_expressions() {
+    let warnings = alert_warnings();
+
+    // The commented-out access on line 22 and the `Alert` component itself aren't flagged
+    assert!(warnings.iter().all(|w| w.start_line != 22 && w.start_line != 4));
+    assert_eq!(warnings.len(), 7);
+}
@@ -0,0 +1,92 @@
+use rustbrother::{analyze_directory_with_progress, AnalysisConfig, Phase, ProgressEvent, StderrReporter, Verbosity};
+use std::cell::RefCell;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+#[test]
+fn test_callback_receives_every_phase_in_order() {
+    let events = RefCell::new(Vec::new());
+    let callback = |event: &ProgressEvent| events.borrow_mut().push(event.clone());
+    analyze_directory_with_progress(Path::new("tests/components/button"), &AnalysisConfig::default(), &callback).unwrap();
+    let events = events.into_inner();
+
+    assert_eq!(events.first(), Some(&ProgressEvent::PhaseStarted(Phase::CssClasses)));
+    assert!(matches!(events.last(), Some(ProgressEvent::Finished { .. })));
+
+    // Every phase that starts also finishes before the next one starts
+    let mut open = None;
+    for event in &events {
+        match event {
+            ProgressEvent::PhaseStarted(phase) => {
+                assert_eq!(open, None, "{:?} started before the previous phase finished", phase);
+                open = Some(*phase);
+            }
+            ProgressEvent::PhaseFinished { phase, .. } => assert_eq!(open.take(), Some(*phase)),
+            _ => {}
+        }
+    }
+    assert_eq!(open, None);
+
+    let started: Vec<Phase> = events.iter().filter_map(|e| match e { ProgressEvent::PhaseStarted(p) => Some(*p), _ => None }).collect();
+    assert!(started.contains(&Phase::Suppressions));
+    assert!(!started.contains(&Phase::Reachability), "reachability only runs with entry points");
+    assert!(events.iter().any(|e| matches!(e, ProgressEvent::Log { level: Verbosity::Verbose, message } if message.ends_with("class definitions"))));
+}
+
+#[test]
+fn test_stderr_reporter_levels() {
+    let started = ProgressEvent::PhaseStarted(Phase::CssClasses);
+    let finished = ProgressEvent::PhaseFinished { phase: Phase::CssClasses, elapsed: Duration::from_millis(12) };
+    let detail = ProgressEvent::Log { level: Verbosity::Verbose, message: String::from("12 class definitions") };
+
+    let quiet = StderrReporter::new(Verbosity::Quiet);
+    assert!([&started, &finished, &detail].iter().all(|event| quiet.format(event).is_none()));
+
+    let normal = StderrReporter::new(Verbosity::Normal);
+    assert_eq!(normal.format(&started).as_deref(), Some("🔍 Scanning CSS files for class definitions..."));
+    assert!(normal.format(&detail).is_none());
+    assert!(normal.format(&finished).is_none());
+
+    let verbose = StderrReporter::new(Verbosity::Verbose);
+    assert!(verbose.format(&detail).is_some());
+    assert!(verbose.format(&finished).is_none());
+
+    let debug = StderrReporter::new(Verbosity::Debug);
+    assert!(debug.format(&finished).unwrap().contains("css-classes took 12.0 ms"));
+}
+
+#[test]
+fn test_json_report_on_stdout_stays_clean() {
+    let output = Command::new(env!("CARGO_BIN_EXE_rustbrother"))
+        .args(["tests/components/button", "--format", "json", "--verbose"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout is only the JSON report");
+    assert_eq!(report["summary"]["unused_classes"], 1);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Scanning CSS files"), "{}", stderr);
+
+    let quiet = Command::new(env!("CARGO_BIN_EXE_rustbrother"))
+        .args(["tests/components/button", "--format", "json", "--quiet"])
+        .output()
+        .unwrap();
+    assert!(quiet.status.success());
+    assert!(quiet.stderr.is_empty(), "{}", String::from_utf8_lossy(&quiet.stderr));
+}
+
+#[test]
+fn test_log_flags_only_on_commands_that_report_progress() {
+    let run = |args: &[&str]| Command::new(env!("CARGO_BIN_EXE_rustbrother")).args(args).output().unwrap();
+
+    let stats = run(&["stats", "tests/components/button", "--quiet"]);
+    assert!(stats.status.success());
+    assert!(stats.stderr.is_empty(), "{}", String::from_utf8_lossy(&stats.stderr));
+
+    for args in [["explain", "icon", "tests/components/button", "--quiet"], ["lsp", "tests/components/button", "--verbose", "--debug"]] {
+        let output = run(&args);
+        assert!(!output.status.success(), "{:?} should reject the log flags", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("unexpected argument"));
+    }
+}
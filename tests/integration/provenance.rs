@@ -0,0 +1,37 @@
+use rustbrother::{analyze_directory, generate_report, UsageKind};
+use std::path::Path;
+use crate::support::config;
+
+#[test]
+fn test_used_classes_carry_evidence() {
+    let result = analyze_directory(Path::new("tests/components/panel"), &config()).unwrap();
+
+    let image = result.used_classes.iter().find(|c| c.name == "panelImage").expect("panelImage should be used");
+    assert!(image.is_definitely_used());
+    assert!(image.evidence.iter().any(|e| e.kind == UsageKind::Direct && e.file_path.ends_with("Panel.tsx")));
+
+    // Only reachable through styles[`panel_${color}`]
+    let ochre = result.used_classes.iter().find(|c| c.name == "panel_ochre").expect("panel_ochre should be used");
+    assert!(!ochre.is_definitely_used(), "panel_ochre is only a dynamic guess");
+    assert!(ochre.evidence.iter().all(|e| !e.kind.is_definite()));
+    assert!(ochre.evidence.iter().any(|e| e.kind == UsageKind::PrefixExpansion && e.line_number == 18));
+}
+
+#[test]
+fn test_reports_separate_definite_and_possible_usage() {
+    let result = analyze_directory(Path::new("tests/components/panel"), &config()).unwrap();
+    let possible = result.used_classes.iter().filter(|c| !c.is_definitely_used()).count();
+    assert!(possible > 0);
+
+    let json: serde_json::Value = serde_json::from_str(&generate_report(&result, "json").unwrap()).unwrap();
+    assert_eq!(json["summary"]["possibly_used_classes"], possible);
+    assert_eq!(
+        json["summary"]["definitely_used_classes"].as_u64().unwrap() as usize + possible,
+        result.used_classes.len()
+    );
+    assert!(json["possibly_used_classes"].as_array().unwrap().iter().any(|c| c["name"] == "panel_ochre"));
+
+    let text = generate_report(&result, "text").unwrap();
+    assert!(text.contains("Possibly Used (dynamic matches only)"));
+    assert!(text.contains(".panel_ochre"));
+}
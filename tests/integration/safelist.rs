@@ -0,0 +1,95 @@
+use rustbrother::{analyze_directory, explain_class, generate_report, load_config, AnalysisConfig, AnalysisResult, Error, SafelistEntry, UsageKind};
+use std::path::Path;
+use crate::support::{names, scratch_tree};
+
+fn config() -> AnalysisConfig {
+    serde_json::from_str(r#"{
+        "ignore_patterns": [],
+        "safelist": [
+            "is-active",
+            { "class": "swiper-*", "paths": ["vendor/"] },
+            "/^cms-[a-z]+$/",
+            "legacy-banner",
+            { "class": "carousel*", "paths": ["themes/"] }
+        ]
+    }"#).unwrap()
+}
+
+fn analyze_carousel() -> AnalysisResult {
+    analyze_directory(Path::new("tests/components/carousel"), &config()).unwrap()
+}
+
+#[test]
+fn test_safelist_entries_parse_from_strings_and_objects() {
+    let config = config();
+    assert_eq!(config.safelist[0], SafelistEntry { class: "is-active".to_string(), paths: vec![] });
+    assert_eq!(config.safelist[1], SafelistEntry { class: "swiper-*".to_string(), paths: vec!["vendor/".to_string()] });
+}
+
+#[test]
+fn test_safelisted_classes_count_as_used() {
+    let result = analyze_carousel();
+
+    // Not in vendor/, so the scoped swiper-* entry doesn't apply; cms-quote-legacy fails the regex
+    assert_eq!(names(&result.unused_classes), vec!["cms-quote-legacy", "swiper-pagination-fallback"]);
+
+    for name in ["is-active", "cms-hero", "cms-quote", "swiper-slide-active", "swiper-pagination"] {
+        let class = result.used_classes.iter().find(|c| c.name == name).unwrap_or_else(|| panic!("{} should be used", name));
+        assert!(class.evidence.iter().all(|e| e.kind == UsageKind::Safelisted), "{} is only safelisted", name);
+        assert!(class.is_definitely_used());
+    }
+
+    // Referenced from JS and safelisted: both kinds of evidence are kept
+    let slide = result.used_classes.iter().find(|c| c.name == "swiper-slide").unwrap();
+    assert!(slide.evidence.iter().any(|e| e.kind == UsageKind::Direct));
+    assert!(slide.evidence.iter().any(|e| e.kind == UsageKind::Safelisted && e.pattern.contains("swiper-*")));
+}
+
+#[test]
+fn test_reports_unused_safelist_entries() {
+    let result = analyze_carousel();
+    let unused: Vec<&str> = result.unused_safelist_entries.iter().map(|e| e.class.as_str()).collect();
+    assert_eq!(unused, vec!["legacy-banner", "carousel*"]);
+
+    let text = generate_report(&result, "text").unwrap();
+    assert!(text.contains("Unused Safelist Entries"));
+    assert!(text.contains("carousel* (only in themes/) - matches no class"));
+
+    let json: serde_json::Value = serde_json::from_str(&generate_report(&result, "json").unwrap()).unwrap();
+    assert_eq!(json["summary"]["safelisted_classes"], 6);
+    assert_eq!(json["summary"]["unused_safelist_entries"], 2);
+    assert_eq!(json["unused_safelist_entries"][0]["class"], "legacy-banner");
+}
+
+#[test]
+fn test_explain_safelisted_class() {
+    let explanation = explain_class(Path::new("tests/components/carousel"), &config(), "is-active").unwrap();
+    assert!(explanation.is_used);
+    assert!(explanation.reason.starts_with("Safelisted"), "{}", explanation.reason);
+}
+
+#[test]
+fn test_invalid_safelist_pattern_is_an_error() {
+    let config = AnalysisConfig { safelist: vec![SafelistEntry { class: "/cms-(/".to_string(), paths: vec![] }], ..config() };
+    let error = analyze_directory(Path::new("tests/components/carousel"), &config).unwrap_err();
+    assert!(matches!(&error, Error::Safelist { pattern, .. } if pattern == "/cms-(/"), "{:?}", error);
+    assert!(error.to_string().contains("Invalid safelist pattern /cms-(/"));
+}
+
+#[test]
+fn test_safelist_evidence_names_the_config_file_in_use() {
+    let dir = scratch_tree("safelist", "config-file", &[
+        ("other.json", r#"{ "safelist": ["is-active"] }"#),
+        ("src/tabs.css", ".is-active { color: red; }\n"),
+    ]);
+    let config = load_config(&dir.join("other.json")).unwrap();
+    let result = analyze_directory(&dir.join("src"), &config).unwrap();
+    let evidence = &result.used_classes[0].evidence[0];
+    assert_eq!(evidence.pattern, format!("safelist entry \"is-active\" in {}", dir.join("other.json").display()));
+
+    // A config built in code has no file to point at
+    let config = AnalysisConfig { config_file: None, ..config };
+    let result = analyze_directory(&dir.join("src"), &config).unwrap();
+    assert_eq!(result.used_classes[0].evidence[0].pattern, "safelist entry \"is-active\" in the config");
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,79 @@
+use rustbrother::{
+    analyze_directory, analyze_sources, find_css_classes_in, find_js_css_references_with_context_in, resolve_stylesheet_import_in,
+    AnalysisConfig, FileProvider, FsFiles, MemoryFiles,
+};
+use std::path::{Path, PathBuf};
+use crate::support::names;
+
+/// A small project that only exists in memory
+fn project() -> MemoryFiles {
+    MemoryFiles::from_iter([
+        ("src/App.tsx", "import { Button } from './Button';\nexport const App = () => <main className=\"layout\"><Button /></main>;\n"),
+        ("src/Button.tsx", "import styles from './Button.module.scss';\nexport const Button = () => <button className={styles.primary}>Go</button>;\n"),
+        ("src/Button.module.scss", "@use './tokens';\n.primary { color: tokens.$brand; }\n.secondary { color: gray; }\n"),
+        ("src/_tokens.scss", "$brand: red;\n"),
+        ("src/global.css", ".layout { display: grid; }\n.sidebar { width: 12rem; }\n"),
+    ])
+}
+
+#[test]
+fn test_analyze_sources_in_memory() {
+    let result = analyze_sources(&project(), &AnalysisConfig::default()).unwrap();
+
+    assert_eq!(names(&result.used_classes), ["layout", "primary"]);
+    assert_eq!(names(&result.unused_classes), ["secondary", "sidebar"]);
+    assert!(result.undefined_references.is_empty());
+    assert!(result.diagnostics.is_empty());
+    assert!(result.used_classes.iter().any(|class| class.file_path == "src/Button.module.scss"));
+
+    // A CSS module binding pointing at a class the stylesheet lacks is caught without any disk access
+    let mut files = project();
+    files.insert("src/Badge.tsx", "import styles from './Button.module.scss';\nexport const Badge = () => <span className={styles.primray} />;\n");
+    let result = analyze_sources(&files, &AnalysisConfig::default()).unwrap();
+    assert_eq!(result.undefined_references.len(), 1);
+    assert_eq!(result.undefined_references[0].suggestion.as_deref(), Some("primary"));
+}
+
+#[test]
+fn test_entry_points_follow_in_memory_imports() {
+    let mut files = project();
+    files.insert("src/Unused.tsx", "export const Unused = () => <aside className=\"sidebar\" />;\n");
+    let config = AnalysisConfig { entry_points: vec![String::from("src/App.tsx")], ..Default::default() };
+
+    let result = analyze_sources(&files, &config).unwrap();
+    assert!(result.used_classes.iter().any(|class| class.name == "primary"));
+    assert!(result.dead_code_classes.iter().any(|class| class.name == "sidebar"));
+}
+
+#[test]
+fn test_finders_and_resolution_over_memory_files() {
+    let files = project();
+    let config = AnalysisConfig::default();
+
+    let classes = find_css_classes_in(&files, &config).unwrap();
+    assert_eq!(names(&classes), ["layout", "primary", "secondary", "sidebar"]);
+
+    let known: Vec<String> = classes.into_iter().map(|class| class.name).collect();
+    let references = find_js_css_references_with_context_in(&files, &config, &known).unwrap();
+    assert!(references.contains(&String::from("layout")));
+
+    // Sass partials resolve among the provided files, and nothing outside them exists
+    assert_eq!(
+        resolve_stylesheet_import_in(&files, Path::new("src/Button.module.scss"), "./tokens"),
+        Some(PathBuf::from("src/_tokens.scss"))
+    );
+    assert!(files.is_file(Path::new("./src/../src/App.tsx")));
+    assert!(files.read(Path::new("src/Missing.tsx")).is_err());
+}
+
+#[test]
+fn test_filesystem_provider_matches_analyze_directory() {
+    let path = Path::new("tests/components/button");
+    let config = AnalysisConfig::default();
+    let from_directory = analyze_directory(path, &config).unwrap();
+    let from_sources = analyze_sources(&FsFiles::new(path), &config).unwrap();
+
+    assert_eq!(names(&from_sources.used_classes), names(&from_directory.used_classes));
+    assert_eq!(names(&from_sources.unused_classes), names(&from_directory.unused_classes));
+    assert_eq!(from_sources.complexity_warnings.len(), from_directory.complexity_warnings.len());
+}
@@ -0,0 +1,59 @@
+use rustbrother::{analyze_directory, selector_specificity, AnalysisConfig, Specificity, StylesheetWarningType};
+use std::path::Path;
+use crate::support::config;
+
+fn specificity(ids: u32, classes: u32, elements: u32) -> Specificity {
+    Specificity { ids, classes, elements }
+}
+
+#[test]
+fn test_selector_specificity() {
+    assert_eq!(selector_specificity(".a"), specificity(0, 1, 0));
+    assert_eq!(selector_specificity("#nav .item > a:hover"), specificity(1, 2, 1));
+    assert_eq!(selector_specificity("ul li::before"), specificity(0, 0, 3));
+    assert_eq!(selector_specificity(".btn[disabled]:not(.primary, #x)"), specificity(1, 2, 0));
+    assert_eq!(selector_specificity(":where(.a, #b) .c"), specificity(0, 1, 0));
+    assert_eq!(selector_specificity(".a, #b"), specificity(1, 0, 0), "a list takes its most specific selector");
+    assert_eq!(selector_specificity(".icon-#{$name} *"), specificity(0, 1, 0));
+}
+
+#[test]
+fn test_shadowed_and_always_overridden_rules() {
+    let result = analyze_directory(Path::new("tests/components/tabs"), &config()).unwrap();
+    let warnings = &result.stylesheet_warnings;
+
+    let shadowed = warnings.iter().find(|w| w.warning_type == StylesheetWarningType::ShadowedDeclaration).expect("shadowed declaration");
+    assert_eq!(shadowed.selector.as_deref(), Some(".tab"));
+    assert_eq!(shadowed.line_number, 8);
+    assert_eq!(shadowed.competing_rule.as_ref().unwrap().selector, ".tab.tab");
+
+    let overridden = warnings.iter().find(|w| w.warning_type == StylesheetWarningType::AlwaysOverriddenRule).expect("overridden rule");
+    assert_eq!(overridden.line_number, 18);
+    assert_eq!(overridden.competing_rule.as_ref().unwrap().start_line, 24);
+
+    // padding and border-bottom of .tab still apply
+    assert_eq!(warnings.iter().filter(|w| w.warning_type == StylesheetWarningType::ShadowedDeclaration).count(), 1);
+}
+
+#[test]
+fn test_media_queries_dont_shadow_base_rules() {
+    // .panelGraphicHide sets display twice, once inside @media
+    let result = analyze_directory(Path::new("tests/components/panel"), &config()).unwrap();
+    assert!(result.stylesheet_warnings.iter().all(|w| w.warning_type != StylesheetWarningType::ShadowedDeclaration));
+}
+
+#[test]
+fn test_id_selectors_and_specificity_budget() {
+    let result = analyze_directory(Path::new("tests/components/tabs"), &config()).unwrap();
+    let warnings = &result.stylesheet_warnings;
+
+    let id = warnings.iter().find(|w| w.warning_type == StylesheetWarningType::IdSelector).expect("id selector");
+    assert_eq!(id.selector.as_deref(), Some("#tabs-root .tab"));
+
+    let budget = warnings.iter().find(|w| w.warning_type == StylesheetWarningType::SpecificityOverBudget).expect("over budget");
+    assert_eq!(budget.line_number, 35);
+
+    let generous = AnalysisConfig { specificity_budget: specificity(0, 5, 5), ..config() };
+    let result = analyze_directory(Path::new("tests/components/tabs"), &generous).unwrap();
+    assert!(result.stylesheet_warnings.iter().all(|w| w.warning_type != StylesheetWarningType::SpecificityOverBudget));
+}
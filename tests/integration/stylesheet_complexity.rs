@@ -0,0 +1,59 @@
+use rustbrother::{find_complexity_warnings, find_stylesheet_complexity_warnings, parse_rules, AnalysisConfig, ComplexityWarningType, WarningSeverity};
+use std::path::Path;
+use crate::support::config;
+
+fn stylesheet_warnings(path: &str, config: &AnalysisConfig) -> Vec<(usize, ComplexityWarningType)> {
+    find_complexity_warnings(Path::new(path), config)
+        .unwrap()
+        .into_iter()
+        .filter(|w| w.file_path.ends_with(".scss"))
+        .map(|w| (w.start_line, w.warning_type))
+        .collect()
+}
+
+#[test]
+fn test_sidebar_stylesheet_complexity() {
+    let warnings = stylesheet_warnings("tests/components/sidebar", &config());
+    let kinds: Vec<String> = warnings.iter().map(|(line, kind)| format!("{}:{:?}", line, kind)).collect();
+
+    assert_eq!(
+        kinds,
+        vec![
+            "9:SelectorConcatenation",
+            "19:DeepSelectorNesting",
+            "35:LongDescendantChain",
+            "39:OversizedRuleBlock",
+        ]
+    );
+}
+
+#[test]
+fn test_nesting_severity_uses_the_deepest_descendant() {
+    let rules = parse_rules(".a { .b { .c { .d { .e { color: red; .f { .g { color: blue; } } } } } } }", "deep.scss");
+    let warnings = find_stylesheet_complexity_warnings(&rules, &config());
+
+    let nesting = warnings
+        .iter()
+        .find(|w| matches!(w.warning_type, ComplexityWarningType::DeepSelectorNesting))
+        .expect("deep nesting");
+    assert!(nesting.pattern.contains("7 levels"), "{}", nesting.pattern);
+    assert!(matches!(nesting.severity, WarningSeverity::High));
+    assert_eq!(warnings.iter().filter(|w| matches!(w.warning_type, ComplexityWarningType::DeepSelectorNesting)).count(), 1);
+}
+
+#[test]
+fn test_flat_bem_suffixes_are_low_severity() {
+    // `&_outline` is the usual BEM style - only reported when asking for low severity warnings
+    assert!(stylesheet_warnings("tests/components/panel", &config()).is_empty());
+
+    let low = AnalysisConfig { complexity_threshold: WarningSeverity::Low, ..config() };
+    let warnings = stylesheet_warnings("tests/components/panel", &low);
+    assert!(warnings.iter().all(|(_, kind)| matches!(kind, ComplexityWarningType::SelectorConcatenation)));
+    assert!(warnings.iter().any(|(line, _)| *line == 117), "`&Hide` builds .panelGraphicHide");
+}
+
+#[test]
+fn test_disabled_complexity_warnings_skip_stylesheets() {
+    let disabled = AnalysisConfig { enable_complexity_warnings: false, ..config() };
+    assert!(stylesheet_warnings("tests/components/sidebar", &disabled).is_empty());
+}
@@ -0,0 +1,85 @@
+use rustbrother::{analyze_directory, analyze_sources, generate_report, AnalysisConfig, AnalysisResult, MemoryFiles, UnusedFileReason};
+use std::path::Path;
+use crate::support::config;
+
+fn analyze_gallery() -> AnalysisResult {
+    analyze_directory(Path::new("tests/components/gallery"), &config()).unwrap()
+}
+
+#[test]
+fn test_reports_whole_stylesheets_to_delete() {
+    let result = analyze_gallery();
+    let files: Vec<(&str, UnusedFileReason)> = result.unused_stylesheet_files
+        .iter()
+        .map(|file| (file.file_path.trim_start_matches("tests/components/gallery/"), file.reason))
+        .collect();
+
+    // Gallery.module.scss is partly used, the tokens partial is pulled in with @use and Gallery.tsx imports
+    // the class-less gallery-base.scss, so none of them is listed
+    assert_eq!(files, vec![
+        ("GalleryLegacy.module.scss", UnusedFileReason::NotImported),
+        ("GalleryLightbox.module.scss", UnusedFileReason::AllClassesUnused),
+        ("gallery-print.scss", UnusedFileReason::NoClassDefinitions),
+    ]);
+}
+
+#[test]
+fn test_stylesheet_files_carry_size_and_class_ratio() {
+    let result = analyze_gallery();
+    let lightbox = result.unused_stylesheet_files.iter().find(|f| f.file_path.ends_with("GalleryLightbox.module.scss")).unwrap();
+    let size = std::fs::metadata("tests/components/gallery/GalleryLightbox.module.scss").unwrap().len();
+    assert_eq!(lightbox.size_bytes, size);
+    assert_eq!((lightbox.used_classes, lightbox.unused_classes), (0, 2));
+    assert_eq!(lightbox.unused_ratio, 1.0);
+
+    let print = result.unused_stylesheet_files.iter().find(|f| f.file_path.ends_with("gallery-print.scss")).unwrap();
+    assert_eq!((print.used_classes, print.unused_classes, print.unused_ratio), (0, 0, 0.0));
+}
+
+#[test]
+fn test_stylesheet_files_in_reports() {
+    let result = analyze_gallery();
+    let total: u64 = result.unused_stylesheet_files.iter().map(|f| f.size_bytes).sum();
+
+    let text = generate_report(&result, "text").unwrap();
+    assert!(text.contains(&format!("Stylesheet files to delete: 3 (~{} bytes)", total)));
+    assert!(text.contains("GalleryLightbox.module.scss - none of its classes are used"));
+
+    let json: serde_json::Value = serde_json::from_str(&generate_report(&result, "json").unwrap()).unwrap();
+    assert_eq!(json["summary"]["unused_stylesheet_files"], 3);
+    assert_eq!(json["summary"]["unused_stylesheet_bytes"], total);
+    assert_eq!(json["unused_stylesheet_files"][0]["reason"], "NotImported");
+}
+
+#[test]
+fn test_suppressed_classes_keep_their_stylesheet() {
+    let mut files = MemoryFiles::from_iter([
+        ("src/Card.tsx", "import styles from './Card.module.css';\nexport const Card = () => <div className={styles.card} />;\n"),
+        ("src/Card.module.css", ".card { padding: 1rem; }\n"),
+        ("src/Badge.tsx", "import styles from './Badge.module.css';\nexport const Badge = () => <span />;\n"),
+        ("src/Badge.module.css", "/* rustbrother-ignore unused-class */\n.badge { color: red; }\n"),
+    ]);
+    let result = analyze_sources(&files, &AnalysisConfig::default()).unwrap();
+    assert!(result.unused_classes.is_empty());
+    assert!(result.unused_stylesheet_files.is_empty(), "{:?}", result.unused_stylesheet_files);
+
+    // Without the comment the whole module can go
+    files.insert("src/Badge.module.css", ".badge { color: red; }\n");
+    let result = analyze_sources(&files, &AnalysisConfig::default()).unwrap();
+    let reasons: Vec<(&str, UnusedFileReason)> = result.unused_stylesheet_files.iter().map(|file| (file.file_path.as_str(), file.reason)).collect();
+    assert_eq!(reasons, [("src/Badge.module.css", UnusedFileReason::AllClassesUnused)]);
+}
+
+#[test]
+fn test_modules_behind_unresolved_imports_are_not_listed() {
+    let files = MemoryFiles::from_iter([
+        ("src/App.tsx", "import styles from '@/components/Card.module.scss';\nexport const App = () => <div className={styles.card} />;\n"),
+        ("src/components/Card.module.scss", ".card { padding: 1rem; }\n"),
+        ("src/components/Legacy.module.scss", ".legacy { color: red; }\n"),
+    ]);
+    let result = analyze_sources(&files, &AnalysisConfig::default()).unwrap();
+
+    // The aliased Card module may well be in use; Legacy is imported by nothing at all
+    let reasons: Vec<(&str, UnusedFileReason)> = result.unused_stylesheet_files.iter().map(|file| (file.file_path.as_str(), file.reason)).collect();
+    assert_eq!(reasons, [("src/components/Legacy.module.scss", UnusedFileReason::NotImported)]);
+}
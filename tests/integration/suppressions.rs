@@ -0,0 +1,142 @@
+use rustbrother::{analyze_directory, analyze_sources, parse_suppressions, AnalysisConfig, AnalysisResult, MemoryFiles, RuleConfig, StylesheetWarningType, WarningSeverity};
+use std::path::Path;
+
+fn analyze_notice() -> AnalysisResult {
+    analyze_notice_with(AnalysisConfig::default())
+}
+
+fn analyze_notice_with(config: AnalysisConfig) -> AnalysisResult {
+    let config = AnalysisConfig { ignore_patterns: vec![], ..config };
+    analyze_directory(Path::new("tests/components/notice"), &config).unwrap()
+}
+
+fn unused_suppressions(result: &AnalysisResult) -> Vec<(String, usize)> {
+    result
+        .unused_suppressions
+        .iter()
+        .map(|s| (s.file_path.rsplit('/').next().unwrap().to_string(), s.line_number))
+        .collect()
+}
+
+/// The comments the notice fixture leaves unused with the default config
+fn default_unused_suppressions() -> Vec<(String, usize)> {
+    vec![("Notice.module.scss".to_string(), 27), ("Notice.tsx".to_string(), 7)]
+}
+
+#[test]
+fn test_parse_suppression_targets() {
+    let css = "/* rustbrother-ignore */\n.a,\n.b {\n  color: red;\n}\n.c {\n  /* rustbrother-ignore unused-custom-property, unused-class */\n\n  --x: 1;\n}\n";
+    let suppressions = parse_suppressions(css, "a.css");
+    assert_eq!(suppressions.len(), 2);
+    assert_eq!((suppressions[0].target_start_line, suppressions[0].target_end_line), (2, 3));
+    assert!(suppressions[0].rules.is_empty());
+    assert_eq!((suppressions[1].target_start_line, suppressions[1].target_end_line), (9, 9));
+    assert_eq!(suppressions[1].rules, vec!["unused-custom-property", "unused-class"]);
+
+    let js = "const a = 1;\n{/* rustbrother-ignore-next-line */}\n// rustbrother-ignore-next-line deep-template-nesting\nconst b = `${x}`;\n";
+    let suppressions = parse_suppressions(js, "a.tsx");
+    assert_eq!(suppressions.iter().map(|s| s.target_start_line).collect::<Vec<_>>(), vec![3, 4]);
+    assert_eq!(suppressions[1].rules, vec!["deep-template-nesting"]);
+}
+
+#[test]
+fn test_suppressed_unused_classes_and_properties() {
+    let result = analyze_notice();
+    let unused: Vec<&str> = result.unused_classes.iter().map(|c| c.name.as_str()).collect();
+
+    assert!(unused.contains(&"noticeStale"));
+    for suppressed in ["noticeThirdParty", "noticeWidget", "noticeWidgetTitle"] {
+        assert!(!unused.contains(&suppressed), "{} should be suppressed", suppressed);
+    }
+    assert!(result.unused_custom_properties.iter().all(|p| p.name != "--notice-legacy-accent"));
+}
+
+#[test]
+fn test_suppressed_complexity_warnings() {
+    let result = analyze_notice();
+    assert!(result.complexity_warnings.iter().all(|w| w.rule_id != "dynamic-class-construction"));
+}
+
+#[test]
+fn test_unused_suppressions_are_reported() {
+    let unused = unused_suppressions(&analyze_notice());
+
+    // .noticeBody is used and the deep-template-nesting comment points at a plain property access
+    assert_eq!(unused, default_unused_suppressions());
+}
+
+#[test]
+fn test_suppressions_below_the_threshold_are_still_used() {
+    let result = analyze_notice_with(AnalysisConfig { complexity_threshold: WarningSeverity::High, ..Default::default() });
+    assert!(result.complexity_warnings.iter().all(|w| matches!(w.severity, WarningSeverity::High)));
+    assert_eq!(unused_suppressions(&result), default_unused_suppressions());
+}
+
+#[test]
+fn test_suppressions_with_complexity_warnings_off_are_still_used() {
+    let result = analyze_notice_with(AnalysisConfig { enable_complexity_warnings: false, ..Default::default() });
+    assert!(result.complexity_warnings.is_empty());
+    assert_eq!(unused_suppressions(&result), default_unused_suppressions());
+}
+
+#[test]
+fn test_suppressions_of_disabled_rules_are_still_used() {
+    let disabled = RuleConfig { enabled: false, ..Default::default() };
+    let config = AnalysisConfig {
+        complexity_rules: [("dynamic-class-construction".to_string(), disabled)].into_iter().collect(),
+        ..Default::default()
+    };
+    let result = analyze_notice_with(config);
+    assert!(result.complexity_warnings.iter().all(|w| w.rule_id != "dynamic-class-construction"));
+    assert_eq!(unused_suppressions(&result), default_unused_suppressions());
+}
+
+/// Analyze `files` with and without their suppression comments
+fn analyze_with_and_without_comments(files: &[(&str, &str)]) -> (AnalysisResult, AnalysisResult) {
+    let comment = regex::Regex::new(r"(?m)^\s*(?:/\*\s*rustbrother-ignore[^*]*\*/|// rustbrother-ignore-next-line.*)\n").unwrap();
+    let with = MemoryFiles::from_iter(files.iter().map(|(path, content)| (*path, content.to_string())));
+    let without = MemoryFiles::from_iter(files.iter().map(|(path, content)| (*path, comment.replace_all(content, "").into_owned())));
+    (analyze_sources(&with, &AnalysisConfig::default()).unwrap(), analyze_sources(&without, &AnalysisConfig::default()).unwrap())
+}
+
+#[test]
+fn test_suppressed_important_warnings() {
+    let (suppressed, reported) = analyze_with_and_without_comments(&[
+        ("src/global.css", "/* rustbrother-ignore important-overused-in-rule */\n.reset {\n  margin: 0 !important;\n  padding: 0 !important;\n  border: 0 !important;\n  color: inherit !important;\n}\n"),
+    ]);
+    assert!(reported.stylesheet_warnings.iter().any(|w| w.warning_type == StylesheetWarningType::ImportantOverusedInRule));
+    assert!(suppressed.stylesheet_warnings.iter().all(|w| w.warning_type != StylesheetWarningType::ImportantOverusedInRule));
+    assert!(suppressed.unused_suppressions.is_empty(), "{:?}", suppressed.unused_suppressions);
+}
+
+#[test]
+fn test_suppressed_specificity_warnings() {
+    let (suppressed, reported) = analyze_with_and_without_comments(&[
+        ("src/Nav.module.css", "/* rustbrother-ignore id-selector */\n#nav .link {\n  color: red;\n}\n"),
+    ]);
+    assert!(reported.stylesheet_warnings.iter().any(|w| w.warning_type == StylesheetWarningType::IdSelector));
+    assert!(suppressed.stylesheet_warnings.iter().all(|w| w.warning_type != StylesheetWarningType::IdSelector));
+    assert!(suppressed.unused_suppressions.is_empty(), "{:?}", suppressed.unused_suppressions);
+}
+
+#[test]
+fn test_suppressed_undefined_references() {
+    let (suppressed, reported) = analyze_with_and_without_comments(&[
+        ("src/Card.tsx", "import styles from './Card.module.css';\nexport const Card = () => (\n  // rustbrother-ignore-next-line undefined-class\n  <div className={styles.cardInjected} />\n);\n"),
+        ("src/Card.module.css", ".card { padding: 1rem; }\n"),
+    ]);
+    assert_eq!(reported.undefined_references.len(), 1);
+    assert!(suppressed.undefined_references.is_empty());
+    assert!(suppressed.unused_suppressions.is_empty(), "{:?}", suppressed.unused_suppressions);
+}
+
+#[test]
+fn test_suppressed_duplicate_rules() {
+    let (suppressed, reported) = analyze_with_and_without_comments(&[
+        ("src/a.css", ".a {\n  display: flex;\n  align-items: center;\n  justify-content: center;\n}\n"),
+        ("src/b.css", "/* rustbrother-ignore duplicate-rule */\n.b {\n  display: flex;\n  align-items: center;\n  justify-content: center;\n}\n"),
+    ]);
+    assert_eq!(reported.duplicate_rules.len(), 1);
+    assert!(suppressed.duplicate_rules.is_empty());
+    assert!(suppressed.unused_suppressions.is_empty(), "{:?}", suppressed.unused_suppressions);
+}
@@ -0,0 +1,232 @@
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::Terminal;
+use rustbrother::edits::{add_to_safelist, unified_diff, PendingEdits};
+use rustbrother::tui::{Action, Browser};
+use rustbrother::{analyze_directory, AnalysisConfig, SafelistEntry};
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::support::{config, scratch_dir, scratch_tree};
+
+/// A scratch copy of the button fixture, so applying edits doesn't touch the real one
+fn button_copy(name: &str) -> PathBuf {
+    let dir = scratch_dir("tui", name);
+    for file in ["Button.module.scss", "Button.tsx"] {
+        fs::copy(Path::new("tests/components/button").join(file), dir.join(file)).unwrap();
+    }
+    dir
+}
+
+fn browser(dir: &Path) -> Browser {
+    let result = analyze_directory(dir, &config()).unwrap();
+    Browser::new(result, PendingEdits::new(dir.join("rustbrother.json")))
+}
+
+/// Safelist entries without `paths`
+fn entries(classes: &[&str]) -> Vec<SafelistEntry> {
+    classes.iter().map(|class| SafelistEntry { class: class.to_string(), paths: Vec::new() }).collect()
+}
+
+/// The entry the browser queues for `iconLarge` in the button copy
+fn icon_large_entry() -> SafelistEntry {
+    SafelistEntry { class: "iconLarge".to_string(), paths: vec!["Button.module.scss".to_string()] }
+}
+
+fn press(browser: &mut Browser, keys: &[KeyCode]) -> Action {
+    keys.iter().fold(Action::Continue, |_, key| browser.handle_key(*key))
+}
+
+fn select_icon_large(browser: &mut Browser) {
+    press(browser, &[KeyCode::Char('/')]);
+    press(browser, &"iconLarge".chars().map(KeyCode::Char).collect::<Vec<_>>());
+    press(browser, &[KeyCode::Enter, KeyCode::Right, KeyCode::Down]);
+}
+
+fn screen(browser: &mut Browser) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+    terminal.draw(|frame| browser.render(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.height)
+        .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn test_add_to_safelist_keeps_settings_and_skips_duplicates() {
+    let config = "{\n  \"include_css_modules\": true,\n  \"safelist\": [\"btn\"],\n  \"ignore_patterns\": []\n}\n";
+    let updated = add_to_safelist(config, &entries(&["btn", "iconLarge"])).unwrap();
+
+    assert_eq!(updated, "{\n  \"include_css_modules\": true,\n  \"safelist\": [\"btn\", \"iconLarge\"],\n  \"ignore_patterns\": []\n}\n");
+    assert_eq!(add_to_safelist(&updated, &entries(&["btn"])).unwrap(), updated);
+
+    // Multi-line lists keep their layout, and a missing safelist goes after the last setting
+    let config = "{\n    \"safelist\": [\n        \"btn\",\n        { \"class\": \"tab\", \"paths\": [\"tabs/\"] }\n    ]\n}";
+    let updated = add_to_safelist(config, &entries(&["iconLarge"])).unwrap();
+    assert_eq!(updated, "{\n    \"safelist\": [\n        \"btn\",\n        { \"class\": \"tab\", \"paths\": [\"tabs/\"] },\n        \"iconLarge\"\n    ]\n}");
+    let updated = add_to_safelist("{\n  \"report_timestamp\": false\n}\n", &entries(&["iconLarge"])).unwrap();
+    assert_eq!(updated, "{\n  \"report_timestamp\": false,\n  \"safelist\": [\n    \"iconLarge\"\n  ]\n}\n");
+    assert_eq!(add_to_safelist("{\"safelist\": []}", &entries(&["a\"b"])).unwrap(), "{\"safelist\": [\"a\\\"b\"]}");
+
+    let fresh = add_to_safelist("", &entries(&["iconLarge"])).unwrap();
+    assert_eq!(fresh, "{\n  \"safelist\": [\n    \"iconLarge\"\n  ]\n}\n");
+    assert!(add_to_safelist("[]", &entries(&["x"])).is_err());
+}
+
+#[test]
+fn test_add_to_safelist_skips_classes_listed_as_objects() {
+    // `{ "class": ... }` without paths lists the class everywhere, so it isn't added again
+    let config = "{\n  \"safelist\": [{ \"class\": \"is-active\" }, { \"class\": \".is-open\" }]\n}\n";
+    assert_eq!(add_to_safelist(config, &entries(&["is-active", "is-open"])).unwrap(), config);
+
+    // An entry scoped to some paths doesn't cover the class elsewhere
+    let config = "{\n  \"safelist\": [{ \"class\": \"tab\", \"paths\": [\"tabs/\"] }]\n}\n";
+    let updated = add_to_safelist(config, &entries(&["tab"])).unwrap();
+    assert_eq!(updated, "{\n  \"safelist\": [{ \"class\": \"tab\", \"paths\": [\"tabs/\"] }, \"tab\"]\n}\n");
+}
+
+#[test]
+fn test_add_to_safelist_writes_scoped_entries() {
+    let scoped = SafelistEntry { class: "title".to_string(), paths: vec!["src/Card.module.css".to_string()] };
+    let config = "{\n  \"safelist\": [\"btn\"]\n}\n";
+    let updated = add_to_safelist(config, std::slice::from_ref(&scoped)).unwrap();
+    assert_eq!(updated, "{\n  \"safelist\": [\"btn\", { \"class\": \"title\", \"paths\": [\"src/Card.module.css\"] }]\n}\n");
+    assert_eq!(add_to_safelist(&updated, &[scoped]).unwrap(), updated);
+}
+
+#[test]
+fn test_unified_diff_hunks() {
+    let old = "a\nb\nc\nd\ne\nf\ng\nh\n";
+    let new = "a\nb\nc\nd\nE\nf\ng\nh\n";
+    let diff = unified_diff("style.css", old, new);
+    assert_eq!(diff, "--- a/style.css\n+++ b/style.css\n@@ -2,7 +2,7 @@\n b\n c\n d\n-e\n+E\n f\n g\n h\n");
+    assert_eq!(unified_diff("style.css", old, old), "");
+}
+
+#[test]
+fn test_browser_queues_edits_and_applies_them_on_confirm() {
+    let dir = button_copy("apply");
+    let mut browser = browser(&dir);
+    select_icon_large(&mut browser);
+
+    press(&mut browser, &[KeyCode::Char('s')]);
+    assert_eq!(browser.edits().safelist, [icon_large_entry()]);
+
+    // Quitting with queued edits opens the review instead
+    assert_eq!(press(&mut browser, &[KeyCode::Char('q')]), Action::Continue);
+    let review = screen(&mut browser);
+    assert!(review.contains("+    { \"class\": \"iconLarge\", \"paths\": [\"Button.module.scss\"]"), "{}", review);
+    assert_eq!(press(&mut browser, &[KeyCode::Char('y')]), Action::ApplyAndQuit);
+    assert_eq!(browser.edits().apply().unwrap(), 1);
+    let config = fs::read_to_string(dir.join("rustbrother.json")).unwrap();
+    assert!(config.contains("{ \"class\": \"iconLarge\", \"paths\": [\"Button.module.scss\"] }"));
+
+    let mut browser = self::browser(&dir);
+    select_icon_large(&mut browser);
+    press(&mut browser, &[KeyCode::Char('d')]);
+    assert_eq!(browser.edits().removals.len(), 1);
+    assert_eq!(browser.edits().removals[0].class_name, "iconLarge");
+    press(&mut browser, &[KeyCode::Char('q')]);
+    let review = screen(&mut browser);
+    assert!(review.contains("-.iconLarge"), "{}", review);
+    assert_eq!(press(&mut browser, &[KeyCode::Char('y')]), Action::ApplyAndQuit);
+
+    assert_eq!(browser.edits().apply().unwrap(), 1);
+    let stylesheet = fs::read_to_string(dir.join("Button.module.scss")).unwrap();
+    assert!(!stylesheet.contains(".iconLarge"));
+    assert!(stylesheet.contains(".icon {"));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_browser_toggles_and_refuses_used_classes() {
+    let dir = button_copy("toggle");
+    let mut browser = browser(&dir);
+    select_icon_large(&mut browser);
+
+    press(&mut browser, &[KeyCode::Char('s'), KeyCode::Char('s')]);
+    assert!(browser.edits().is_empty(), "a second s unqueues the class");
+
+    // A class is either safelisted or deleted, never both
+    press(&mut browser, &[KeyCode::Char('s'), KeyCode::Char('d')]);
+    assert_eq!(browser.edits().safelist, [icon_large_entry()]);
+    assert!(browser.edits().removals.is_empty());
+    assert!(screen(&mut browser).contains(".iconLarge is queued for the safelist"));
+    press(&mut browser, &[KeyCode::Char('s'), KeyCode::Char('d'), KeyCode::Char('s')]);
+    assert!(browser.edits().safelist.is_empty());
+    assert_eq!(browser.edits().removals.len(), 1);
+    assert!(screen(&mut browser).contains("The rule of .iconLarge is queued for deletion"));
+    press(&mut browser, &[KeyCode::Char('d')]);
+    assert!(browser.edits().is_empty());
+
+    // Used classes can't be safelisted or deleted
+    press(&mut browser, &[KeyCode::Char('/'), KeyCode::Esc, KeyCode::Char('/')]);
+    press(&mut browser, &"icon".chars().map(KeyCode::Char).chain([KeyCode::Enter]).collect::<Vec<_>>());
+    press(&mut browser, &[KeyCode::Down, KeyCode::Char('s'), KeyCode::Char('d')]);
+    assert!(browser.edits().is_empty());
+
+    // Nothing queued: quitting doesn't ask for a review
+    assert_eq!(press(&mut browser, &[KeyCode::Char('q')]), Action::Quit);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_browser_reports_unreadable_stylesheets() {
+    let dir = button_copy("unreadable");
+    let mut browser = browser(&dir);
+    fs::remove_file(dir.join("Button.module.scss")).unwrap();
+    select_icon_large(&mut browser);
+
+    press(&mut browser, &[KeyCode::Char('d')]);
+    assert!(browser.edits().is_empty());
+    let text = screen(&mut browser);
+    assert!(text.contains("Can't read"), "{}", text);
+    assert!(!text.contains("contains used classes"), "{}", text);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_browser_renders_tree_warnings_and_source() {
+    let config = AnalysisConfig { include_css_modules: true, ..Default::default() };
+    let result = analyze_directory(Path::new("tests/components/alert"), &config).unwrap();
+    let mut browser = Browser::new(result, PendingEdits::new(PathBuf::from("rustbrother.json")));
+
+    let text = screen(&mut browser);
+    assert!(text.contains(" Stylesheets "), "{}", text);
+    assert!(text.contains(" Complexity warnings (4/4) "), "{}", text);
+
+    // Focus the warnings pane and show the first warning's source
+    press(&mut browser, &[KeyCode::Tab]);
+    let text = screen(&mut browser);
+    assert!(text.contains("Alert.tsx:"), "{}", text);
+
+    // Filtering by severity narrows the list
+    press(&mut browser, &[KeyCode::Char('f')]);
+    let text = screen(&mut browser);
+    assert!(!text.contains("(4/4)"), "{}", text);
+}
+
+#[test]
+fn test_browser_safelists_module_classes_in_their_file_only() {
+    let dir = scratch_tree("tui", "scoped", &[
+        ("Card.tsx", "import styles from './Card.module.scss';\nexport const Card = () => <div className={styles.card} />;\n"),
+        ("Card.module.scss", ".card { padding: 1rem; }\n.title { font-weight: 600; }\n"),
+        ("Modal.tsx", "import styles from './Modal.module.scss';\nexport const Modal = () => <div className={styles.modal} />;\n"),
+        ("Modal.module.scss", ".modal { padding: 2rem; }\n.title { font-size: 2rem; }\n"),
+    ]);
+    let mut browser = browser(&dir);
+    press(&mut browser, &[KeyCode::Char('/')]);
+    press(&mut browser, &"title".chars().map(KeyCode::Char).collect::<Vec<_>>());
+    press(&mut browser, &[KeyCode::Enter, KeyCode::Right, KeyCode::Down, KeyCode::Char('s')]);
+
+    let card_title = SafelistEntry { class: "title".to_string(), paths: vec!["Card.module.scss".to_string()] };
+    assert_eq!(browser.edits().safelist, [card_title]);
+    let text = screen(&mut browser);
+    assert_eq!(text.matches("[safelist]").count(), 1, "{}", text);
+
+    // The same name in the other module is still free to delete
+    press(&mut browser, &[KeyCode::Down, KeyCode::Right, KeyCode::Down, KeyCode::Char('d')]);
+    assert_eq!(browser.edits().removals.len(), 1, "{}", screen(&mut browser));
+    assert!(browser.edits().removals[0].file_path.ends_with("Modal.module.scss"));
+    fs::remove_dir_all(&dir).unwrap();
+}
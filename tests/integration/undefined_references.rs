@@ -0,0 +1,59 @@
+use rustbrother::{analyze_directory, find_undefined_references, AnalysisConfig, DiagnosticKind, Error};
+use std::fs;
+use std::path::Path;
+use crate::support::{config, scratch_tree, write_file};
+
+#[test]
+fn test_module_references_missing_from_stylesheet() {
+    let result = analyze_directory(Path::new("tests/components/panel"), &config()).unwrap();
+    let names: Vec<&str> = result.undefined_references.iter().map(|r| r.name.as_str()).collect();
+
+    assert!(names.contains(&"panel_iconContainer"), "panel_iconContainer isn't in Panel.module.scss");
+    assert!(names.contains(&"panel_wrongClass"));
+    assert!(!names.contains(&"panelFooter"), "panelFooter exists and must not be reported");
+
+    let container = result.undefined_references.iter().find(|r| r.name == "panel_iconContainer").unwrap();
+    assert_eq!(container.binding.as_deref(), Some("styles"));
+    assert!(container.stylesheet.as_deref().is_some_and(|s| s.ends_with("Panel.module.scss")));
+    assert_eq!(container.line_number, 39);
+
+    let graphic_icon = result.undefined_references.iter().find(|r| r.name == "panel_graphicIcon").unwrap();
+    assert_eq!(graphic_icon.suggestion.as_deref(), Some("panel_graphicImage"));
+}
+
+#[test]
+fn test_class_strings_checked_against_global_stylesheets() {
+    let result = analyze_directory(Path::new("tests/components/badge"), &config()).unwrap();
+
+    assert_eq!(result.undefined_references.len(), 1);
+    let typo = &result.undefined_references[0];
+    assert_eq!(typo.name, "badge-primray");
+    assert!(typo.binding.is_none());
+    assert!(typo.stylesheet.is_none());
+    assert_eq!(typo.suggestion.as_deref(), Some("badge-primary"));
+}
+
+#[test]
+fn test_class_strings_skipped_without_global_stylesheets() {
+    // Card only has a CSS module, so `className="legacy-title"` has nothing to be checked against
+    let result = analyze_directory(Path::new("tests/components/card"), &config()).unwrap();
+    assert!(result.undefined_references.iter().all(|r| r.binding.is_some()));
+}
+
+#[test]
+fn test_unreadable_module_is_a_diagnostic_not_undefined_references() {
+    let dir = scratch_tree("undefined", "unreadable-module", &[
+        ("Card.tsx", "import styles from './Card.module.css';\nexport const Card = () => <div className={`${styles.card} ${styles.title}`} />;\n"),
+    ]);
+    write_file(&dir.join("Card.module.css"), b".card { padding: 0; }\n.title { margin: 0; } /* \xff */\n");
+
+    let result = analyze_directory(&dir, &config()).unwrap();
+    assert!(result.undefined_references.is_empty(), "{:?}", result.undefined_references);
+    assert!(result.diagnostics.iter().any(|d| d.kind == DiagnosticKind::NonUtf8 && d.file_path.ends_with("Card.module.css")));
+
+    // With strict on, the finder called on its own fails on the stylesheet instead
+    let strict = AnalysisConfig { strict: true, ..config() };
+    let error = find_undefined_references(&dir, &strict, &[]).unwrap_err();
+    assert!(matches!(&error, Error::NonUtf8 { path } if path.ends_with("Card.module.css")), "{:?}", error);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,72 @@
+use rustbrother::{analyze_directory, generate_report, AnalysisConfig, AnalysisResult, WarningSeverity};
+use std::path::Path;
+
+fn analyze(fixture: &str) -> AnalysisResult {
+    let config = AnalysisConfig {
+        include_css_modules: true,
+        ..Default::default()
+    };
+    analyze_directory(&Path::new("tests/components").join(fixture), &config).unwrap()
+}
+
+#[test]
+fn test_junit_report_has_a_suite_per_file_and_a_case_per_finding() {
+    let result = analyze("sidebar");
+    let report = generate_report(&result, "junit").unwrap();
+    let high = result.complexity_warnings.iter().filter(|w| matches!(w.severity, WarningSeverity::High)).count();
+    let findings = result.unused_classes.len() + result.unused_custom_properties.len() + result.complexity_warnings.len();
+
+    assert!(report.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"rustbrother\""));
+    assert!(high > 0, "the sidebar fixture has a high severity warning");
+    assert_eq!(report.matches("<error type=").count(), high);
+    assert_eq!(report.matches("<failure type=").count(), findings - high);
+    assert!(report.contains(&format!("failures=\"{}\" errors=\"{}\">", findings - high, high)));
+
+    let warning = &result.complexity_warnings[0];
+    assert!(report.contains(&format!("<testsuite name=\"{}\"", warning.file_path)));
+    assert!(report.contains(&format!("file=\"{}\" line=\"{}\"", warning.file_path, warning.start_line)));
+    assert_eq!(report.matches("<testsuite ").count(), report.matches("</testsuite>").count());
+}
+
+#[test]
+fn test_junit_report_lists_clean_stylesheets_as_passing() {
+    let result = analyze("alert");
+    let report = generate_report(&result, "junit").unwrap();
+    let stylesheet = &result.used_classes[0].file_path;
+
+    assert!(report.contains(&format!("<testsuite name=\"{}\" tests=\"1\" failures=\"0\" errors=\"0\">", stylesheet)));
+    assert!(report.contains(&format!("<testcase name=\"no findings\" classname=\"{}\"/>", stylesheet)));
+}
+
+#[test]
+fn test_checkstyle_report_maps_severity_and_rule_ids() {
+    let result = analyze("panel");
+    let report = generate_report(&result, "checkstyle").unwrap();
+
+    assert!(report.contains("<checkstyle version=\"4.3\">"));
+    let property = result.unused_custom_properties.first().expect("the panel fixture has unused custom properties");
+    assert!(report.contains(&format!(
+        "<error line=\"{}\" severity=\"warning\" message=\"Unused custom property {}\" source=\"rustbrother.unused-custom-property\"/>",
+        property.line_number, property.name
+    )));
+    for class in &result.unused_classes {
+        assert!(report.contains(&format!("message=\"Unused class .{}\" source=\"rustbrother.unused-class\"", class.name)));
+    }
+
+    let result = analyze("sidebar");
+    let report = generate_report(&result, "checkstyle").unwrap();
+    let high = result.complexity_warnings.iter().filter(|w| matches!(w.severity, WarningSeverity::High)).count();
+    let low = result.complexity_warnings.iter().filter(|w| matches!(w.severity, WarningSeverity::Low)).count();
+    assert_eq!(report.matches("severity=\"error\"").count(), high);
+    assert_eq!(report.matches("severity=\"info\"").count(), low);
+}
+
+#[test]
+fn test_xml_reports_escape_patterns() {
+    let result = analyze("alert");
+    for format in ["junit", "checkstyle"] {
+        let report = generate_report(&result, format).unwrap();
+        assert!(!report.contains("''"), "{} report leaks unescaped quotes", format);
+        assert!(report.contains("&#x27;&#x27;"));
+    }
+}
@@ -10,7 +10,35 @@ mod panel_test;
 #[path = "components/card/Card.test.rs"]
 mod card_test;
 
+// Helpers shared by the integration tests
+mod support;
+
 // Integration tests
 mod integration {
     mod full_analysis;
+    mod class_usages;
+    mod lsp;
+    mod commands;
+    mod provenance;
+    mod undefined_references;
+    mod duplicate_rules;
+    mod important_usage;
+    mod specificity;
+    mod stylesheet_complexity;
+    mod multiline_complexity;
+    mod complexity_rules;
+    mod suppressions;
+    mod safelist;
+    mod dead_components;
+    mod stylesheet_files;
+    mod changes;
+    mod compare;
+    mod markdown_report;
+    mod xml_reports;
+    mod json_report;
+    mod html_templates;
+    mod tui;
+    mod progress;
+    mod diagnostics;
+    mod sources;
 }
\ No newline at end of file
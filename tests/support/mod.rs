@@ -0,0 +1,42 @@
+// tests/support/mod.rs - Helpers shared by the integration tests
+// The analysis config the fixtures need, scratch directories under the system temp dir, one per test,
+// and small accessors for analysis results.
+
+use rustbrother::{AnalysisConfig, CssClass};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The default config without ignore patterns, so fixtures under `tests/` are analyzed
+pub fn config() -> AnalysisConfig {
+    AnalysisConfig { ignore_patterns: vec![], ..Default::default() }
+}
+
+/// Class names, sorted, so results compare independent of scan order
+pub fn names(classes: &[CssClass]) -> Vec<&str> {
+    let mut names: Vec<&str> = classes.iter().map(|class| class.name.as_str()).collect();
+    names.sort();
+    names
+}
+
+/// An empty scratch directory for one test; whatever a previous run left there is removed first
+pub fn scratch_dir(suite: &str, name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rustbrother-{}-{}-{}", suite, name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A scratch directory holding `files`, given as paths relative to it and their contents
+pub fn scratch_tree(suite: &str, name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = scratch_dir(suite, name);
+    for (file, content) in files {
+        write_file(&dir.join(file), content);
+    }
+    dir
+}
+
+/// Write `content` to `path`, creating its directories
+pub fn write_file(path: &Path, content: impl AsRef<[u8]>) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}